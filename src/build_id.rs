@@ -0,0 +1,170 @@
+use crate::{
+    error::{Error, Result},
+    note::{ntype, owner, Note},
+    object::Object,
+    section::{shtype, SectionFlags},
+};
+
+/// The hash functions that linkers offer for `--build-id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildIdStyle {
+    /// 160-bit SHA-1, the default of GNU ld
+    Sha1,
+    /// 128-bit MD5
+    Md5,
+    /// 64-bit XXH3, the hash behind `--build-id=fast` of lld. lld hashes the file in chunks and then hashes
+    /// those hashes, so its IDs are not the same.
+    Fast,
+}
+
+impl BuildIdStyle {
+    /// Returns the number of bytes in an ID of this style.
+    pub fn size(&self) -> usize {
+        match self {
+            BuildIdStyle::Sha1 => 20,
+            BuildIdStyle::Md5 => 16,
+            BuildIdStyle::Fast => 8,
+        }
+    }
+
+    /// Guesses the style from the size of an existing ID.
+    pub fn from_size(size: usize) -> Option<Self> {
+        match size {
+            20 => Some(BuildIdStyle::Sha1),
+            16 => Some(BuildIdStyle::Md5),
+            8 => Some(BuildIdStyle::Fast),
+            _ => None,
+        }
+    }
+
+    /// Hashes a sequence of chunks as if they were one.
+    #[allow(unused_variables)]
+    fn hash<'a>(&self, chunks: impl Iterator<Item = &'a [u8]>) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "sha1")]
+            BuildIdStyle::Sha1 => {
+                let mut hasher = sha1_smol::Sha1::new();
+                chunks.for_each(|x| hasher.update(x));
+                Ok(hasher.digest().bytes().to_vec())
+            }
+            #[cfg(feature = "md5")]
+            BuildIdStyle::Md5 => {
+                let mut context = md5::Context::new();
+                chunks.for_each(|x| context.consume(x));
+                Ok(context.compute().0.to_vec())
+            }
+            // lld stores the hash in little endian byte order.
+            #[cfg(feature = "xxhash")]
+            BuildIdStyle::Fast => {
+                let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+                chunks.for_each(|x| hasher.update(x));
+                Ok(hasher.digest().to_le_bytes().to_vec())
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(self.disabled()),
+        }
+    }
+
+    /// The error for hash functions whose cargo feature isn't enabled.
+    #[allow(dead_code)]
+    fn disabled(&self) -> Error {
+        Error::BuildId(format!("{:?} support is not enabled", self))
+    }
+}
+
+/// Checks if a note holds a GNU build ID.
+fn is_build_id(note: &Note) -> bool {
+    note.name == owner::GNU && note.n_type == ntype::NT_GNU_BUILD_ID
+}
+
+impl Object {
+    /// Returns the GNU build ID of the object, if it has one.
+    pub fn build_id(&self) -> Result<Option<Vec<u8>>> {
+        Ok(self.notes()?.into_iter().find(is_build_id).map(|x| x.desc))
+    }
+
+    /// Returns the index of the note section that holds the build ID.
+    fn build_id_section(&self) -> Result<Option<usize>> {
+        for i in 0..self.sections.len() {
+            if self.sections[i].header.sh_type == shtype::SHT_NOTE
+                && self.section_notes(i)?.iter().any(is_build_id)
+            {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Computes a new build ID over the contents of all loaded sections, in the order of their addresses.
+    /// The current build ID is treated as all zeros, so the result doesn't depend on it.
+    ///
+    /// Linkers also hash the headers of the object while it is being written, so the result differs from the
+    /// ID the linker picked, but it is just as unique to the contents.
+    pub fn compute_build_id(&self, style: BuildIdStyle) -> Result<Vec<u8>> {
+        let build_id = self.build_id_section()?;
+        let zeroed = match build_id {
+            Some(idx) => {
+                let mut notes = self.section_notes(idx)?;
+                for note in notes.iter_mut().filter(|x| is_build_id(x)) {
+                    note.desc.fill(0);
+                }
+                Some(self.encode_notes(idx, &notes)?)
+            }
+            None => None,
+        };
+
+        let mut sections: Vec<_> = (0..self.sections.len())
+            .filter(|&i| {
+                let h = &self.sections[i].header;
+                h.flags().contains(SectionFlags::SHF_ALLOC) && h.sh_type != shtype::SHT_NOBITS
+            })
+            .collect();
+        sections.sort_by_key(|&i| self.sections[i].header.sh_addr);
+        let chunks = sections.into_iter().map(|i| match (&zeroed, build_id) {
+            (Some(body), Some(idx)) if idx == i => &body[..],
+            _ => &self.sections[i].body[..],
+        });
+        style.hash(chunks)
+    }
+
+    /// Replaces the build ID in the existing build ID note. The note section is moved if the new ID is longer.
+    pub fn set_build_id(&mut self, id: &[u8]) -> Result<()> {
+        let idx = self
+            .build_id_section()?
+            .ok_or_else(|| Error::MissingSection(".note.gnu.build-id".into()))?;
+        let mut notes = self.section_notes(idx)?;
+        for note in notes.iter_mut().filter(|x| is_build_id(x)) {
+            note.desc = id.to_vec();
+        }
+        let body = self.encode_notes(idx, &notes)?;
+        self.replace_note_body(idx, body)
+    }
+
+    /// Computes a new build ID of the same style as the current one and writes it to the build ID note.
+    /// Returns the new ID.
+    pub fn regenerate_build_id(&mut self) -> Result<Vec<u8>> {
+        let old = self
+            .build_id()?
+            .ok_or_else(|| Error::MissingSection(".note.gnu.build-id".into()))?;
+        let style = BuildIdStyle::from_size(old.len()).unwrap_or(BuildIdStyle::Sha1);
+        let mut id = self.compute_build_id(style)?;
+        // IDs of unusual sizes are truncated or padded, so the note doesn't change its size.
+        id.resize(old.len(), 0);
+        self.set_build_id(&id)?;
+        Ok(id)
+    }
+
+    /// Encodes notes with the alignment of the note section they belong to.
+    fn encode_notes(&self, idx: usize, notes: &[Note]) -> Result<Vec<u8>> {
+        let align = match self.sections[idx].header.sh_addralign {
+            8 => 8,
+            _ => 4,
+        };
+        let endian = &self.header.e_ident.ei_data;
+        let mut body = Vec::new();
+        for note in notes {
+            note.write(endian, align, &mut body)?;
+        }
+        Ok(body)
+    }
+}
@@ -0,0 +1,339 @@
+use std::io::{Read, Write};
+
+use crate::{
+    error::{Error, Result},
+    object::{Class, Endianness, Object},
+    section::{shtype, Section, SectionFlags, SectionId},
+    util::{ReadExt, WriteExt},
+};
+
+pub mod chtype {
+    /// ZLIB/DEFLATE
+    pub const ELFCOMPRESS_ZLIB: u32 = 1;
+    /// Zstandard
+    pub const ELFCOMPRESS_ZSTD: u32 = 2;
+    /// Start of OS specific
+    pub const ELFCOMPRESS_LOOS: u32 = 0x60000000;
+    /// End of OS specific
+    pub const ELFCOMPRESS_HIOS: u32 = 0x6FFFFFFF;
+    /// Start of processor specific
+    pub const ELFCOMPRESS_LOPROC: u32 = 0x70000000;
+    /// End of processor specific
+    pub const ELFCOMPRESS_HIPROC: u32 = 0x7FFFFFFF;
+}
+
+/// The prefix of sections that are compressed the old GNU way, e.g. `.zdebug_info`.
+const ZDEBUG_PREFIX: &str = ".zdebug";
+/// The magic number at the start of sections that are compressed the old GNU way.
+const ZDEBUG_MAGIC: &[u8; 4] = b"ZLIB";
+
+/// Upper bound for the memory reserved up front when decompressing, since the size comes from the file.
+const MAX_PREALLOC: u64 = 0x100_0000;
+
+/// The codecs that can be used for `SHF_COMPRESSED` sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionAlgorithm {
+    Zlib,
+    Zstd,
+}
+
+impl From<CompressionAlgorithm> for u32 {
+    fn from(value: CompressionAlgorithm) -> Self {
+        match value {
+            CompressionAlgorithm::Zlib => chtype::ELFCOMPRESS_ZLIB,
+            CompressionAlgorithm::Zstd => chtype::ELFCOMPRESS_ZSTD,
+        }
+    }
+}
+
+impl TryFrom<u32> for CompressionAlgorithm {
+    type Error = Error;
+
+    fn try_from(value: u32) -> Result<Self> {
+        match value {
+            chtype::ELFCOMPRESS_ZLIB => Ok(CompressionAlgorithm::Zlib),
+            chtype::ELFCOMPRESS_ZSTD => Ok(CompressionAlgorithm::Zstd),
+            x => Err(Error::Compression(format!("unknown type {:#x}", x))),
+        }
+    }
+}
+
+impl CompressionAlgorithm {
+    // The data is unused if no codec is enabled.
+    #[allow(unused_variables)]
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            #[cfg(feature = "zlib")]
+            CompressionAlgorithm::Zlib => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => Ok(zstd::stream::encode_all(data, 0)?),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.disabled()),
+        }
+    }
+
+    /// Decompresses `data`, which is expected to expand to `size` bytes. Reading stops one byte after that,
+    /// so a wrong size is detected without decompressing everything.
+    #[allow(unused_variables, unused_mut)]
+    fn decompress(&self, data: &[u8], size: u64) -> Result<Vec<u8>> {
+        let mut result = Vec::with_capacity(size.min(MAX_PREALLOC) as usize);
+        let limit = size.saturating_add(1);
+        match self {
+            #[cfg(feature = "zlib")]
+            CompressionAlgorithm::Zlib => {
+                flate2::read::ZlibDecoder::new(data)
+                    .take(limit)
+                    .read_to_end(&mut result)?;
+                Ok(())
+            }
+            #[cfg(feature = "zstd")]
+            CompressionAlgorithm::Zstd => {
+                zstd::stream::read::Decoder::new(data)?
+                    .take(limit)
+                    .read_to_end(&mut result)?;
+                Ok(())
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(self.disabled()),
+        }?;
+        match result.len() as u64 == size {
+            true => Ok(result),
+            false => Err(Error::Compression(format!(
+                "expected {:#x} bytes, got {:#x}",
+                size,
+                result.len()
+            ))),
+        }
+    }
+
+    /// The error for codecs whose cargo feature isn't enabled.
+    fn disabled(&self) -> Error {
+        Error::Compression(format!("{:?} support is not enabled", self))
+    }
+}
+
+/// The header at the start of a `SHF_COMPRESSED` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompressionHeader {
+    /// The codec, one of `chtype`.
+    pub ch_type: u32,
+    /// The size of the uncompressed data.
+    pub ch_size: u64,
+    /// The alignment of the uncompressed data.
+    pub ch_addralign: u64,
+}
+
+impl CompressionHeader {
+    pub fn size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 12,
+            Class::Bits64 => 24,
+        }
+    }
+
+    pub fn read(class: &Class, endian: &Endianness, mut buf: impl Read) -> Result<Self> {
+        let header = match class {
+            Class::Bits32 => Self {
+                ch_type: buf.read_u32(endian)?,
+                ch_size: buf.read_u32(endian)? as u64,
+                ch_addralign: buf.read_u32(endian)? as u64,
+            },
+            Class::Bits64 => {
+                let ch_type = buf.read_u32(endian)?;
+                let _reserved = buf.read_u32(endian)?;
+                Self {
+                    ch_type,
+                    ch_size: buf.read_u64(endian)?,
+                    ch_addralign: buf.read_u64(endian)?,
+                }
+            }
+        };
+        Ok(header)
+    }
+
+    pub fn write(&self, class: &Class, endian: &Endianness, mut buf: impl Write) -> Result<usize> {
+        let mut written = 0;
+        match class {
+            Class::Bits32 => {
+                written += buf.write_u32(endian, self.ch_type)?;
+                written += buf.write_u32(endian, self.ch_size as u32)?;
+                written += buf.write_u32(endian, self.ch_addralign as u32)?;
+            }
+            Class::Bits64 => {
+                written += buf.write_u32(endian, self.ch_type)?;
+                written += buf.write_u32(endian, 0)?;
+                written += buf.write_u64(endian, self.ch_size)?;
+                written += buf.write_u64(endian, self.ch_addralign)?;
+            }
+        }
+        Ok(written)
+    }
+}
+
+impl Section {
+    /// Checks if the body is compressed, either with `SHF_COMPRESSED` or the old GNU `.zdebug` format.
+    pub fn is_compressed(&self) -> bool {
+        self.header.flags().contains(SectionFlags::SHF_COMPRESSED) || self.is_zdebug()
+    }
+
+    fn is_zdebug(&self) -> bool {
+        self.name.starts_with(ZDEBUG_PREFIX) && self.body.starts_with(ZDEBUG_MAGIC)
+    }
+
+    /// Reads the compression header of the body. `.zdebug` sections don't have one, so an equivalent header
+    /// is made up for them. Returns `None` if the section isn't compressed.
+    pub fn compression_header(
+        &self,
+        class: &Class,
+        endian: &Endianness,
+    ) -> Result<Option<CompressionHeader>> {
+        if self.header.flags().contains(SectionFlags::SHF_COMPRESSED) {
+            let header = CompressionHeader::read(class, endian, &self.body[..])
+                .map_err(|e| e.truncated(0))?;
+            return Ok(Some(header));
+        }
+        if self.is_zdebug() {
+            // The magic number is followed by the uncompressed size in big endian.
+            let mut buf = &self.body[4..];
+            let size = buf.read_u64(&Endianness::Big).map_err(|e| e.truncated(4))?;
+            return Ok(Some(CompressionHeader {
+                ch_type: chtype::ELFCOMPRESS_ZLIB,
+                ch_size: size,
+                ch_addralign: self.header.sh_addralign,
+            }));
+        }
+        Ok(None)
+    }
+
+    /// Returns the uncompressed contents of the section. The body is returned as is if it isn't compressed.
+    pub fn decompressed_body(&self, class: &Class, endian: &Endianness) -> Result<Vec<u8>> {
+        let Some(header) = self.compression_header(class, endian)? else {
+            return Ok(self.body.clone());
+        };
+        let start = match self.header.flags().contains(SectionFlags::SHF_COMPRESSED) {
+            true => CompressionHeader::size(class) as usize,
+            false => ZDEBUG_MAGIC.len() + 8,
+        };
+        CompressionAlgorithm::try_from(header.ch_type)?
+            .decompress(&self.body[start..], header.ch_size)
+    }
+
+    /// Replaces the body with its uncompressed contents and clears `SHF_COMPRESSED`.
+    /// `.zdebug` sections are renamed to `.debug`.
+    pub fn decompress(&mut self, class: &Class, endian: &Endianness) -> Result<()> {
+        let Some(header) = self.compression_header(class, endian)? else {
+            return Ok(());
+        };
+        self.body = self.decompressed_body(class, endian)?;
+        if let Some(name) = self.name.strip_prefix(ZDEBUG_PREFIX) {
+            self.name = format!(".debug{}", name);
+        }
+        let h = &mut self.header;
+        h.set_flags(h.flags() - SectionFlags::SHF_COMPRESSED);
+        h.sh_addralign = header.ch_addralign;
+        Ok(())
+    }
+
+    /// Compresses the body and prepends a compression header for the given class and endianness.
+    /// Sections that are already compressed are decompressed first. Loaded sections can't be compressed.
+    pub fn compress(
+        &mut self,
+        algorithm: CompressionAlgorithm,
+        class: &Class,
+        endian: &Endianness,
+    ) -> Result<()> {
+        if self.header.flags().contains(SectionFlags::SHF_ALLOC) {
+            return Err(Error::Compression(format!(
+                "section \"{}\" is loaded at runtime",
+                self.name
+            )));
+        }
+        self.decompress(class, endian)?;
+
+        let header = CompressionHeader {
+            ch_type: algorithm.into(),
+            ch_size: self.body.len() as u64,
+            ch_addralign: self.header.sh_addralign,
+        };
+        let mut body = Vec::new();
+        header.write(class, endian, &mut body)?;
+        body.extend(algorithm.compress(&self.body)?);
+        self.body = body;
+
+        let h = &mut self.header;
+        h.set_flags(h.flags() | SectionFlags::SHF_COMPRESSED);
+        // The compression header is aligned like a word.
+        h.sh_addralign = match class {
+            Class::Bits32 => 4,
+            Class::Bits64 => 8,
+        };
+        Ok(())
+    }
+}
+
+impl Object {
+    /// Returns the uncompressed contents of a section, given by index or name.
+    pub fn decompressed_body(&self, section: impl SectionId) -> Result<Vec<u8>> {
+        let idx = section.section_idx(self)?;
+        let ident = &self.header.e_ident;
+        self.sections[idx].decompressed_body(&ident.ei_class, &ident.ei_data)
+    }
+
+    /// Decompresses a section in place. Relocation sections of `.zdebug` sections are renamed along with them.
+    pub fn decompress_section(&mut self, section: impl SectionId) -> Result<()> {
+        let idx = section.section_idx(self)?;
+        let old_name = self.sections[idx].name.clone();
+        let ident = &self.header.e_ident;
+        self.sections[idx].decompress(&ident.ei_class, &ident.ei_data)?;
+
+        let new_name = self.sections[idx].name.clone();
+        if new_name != old_name {
+            for section in self.relocation_sections_for(idx) {
+                let section = &mut self.sections[section];
+                if let Some(prefix) = section.name.strip_suffix(&old_name) {
+                    section.name = format!("{}{}", prefix, new_name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Compresses a section, given by index or name, with the given codec.
+    pub fn compress_section(
+        &mut self,
+        section: impl SectionId,
+        algorithm: CompressionAlgorithm,
+    ) -> Result<()> {
+        let idx = section.section_idx(self)?;
+        let ident = &self.header.e_ident;
+        self.sections[idx].compress(algorithm, &ident.ei_class, &ident.ei_data)
+    }
+
+    /// Compresses all sections with debug information, like `--compress-debug-sections` of linkers does.
+    pub fn compress_debug_sections(&mut self, algorithm: CompressionAlgorithm) -> Result<()> {
+        for i in 0..self.sections.len() {
+            let section = &self.sections[i];
+            let debug = section.name.starts_with(".debug") || section.is_zdebug();
+            if debug && !section.header.flags().contains(SectionFlags::SHF_ALLOC) {
+                self.decompress_section(i)?;
+                self.compress_section(i, algorithm)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the indices of the relocation sections that apply to a section.
+    fn relocation_sections_for(&self, idx: usize) -> Vec<usize> {
+        (0..self.sections.len())
+            .filter(|&i| {
+                let h = &self.sections[i].header;
+                matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA) && h.sh_info as usize == idx
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,118 @@
+use std::io::Cursor;
+
+use crate::{
+    error::{Error, Result},
+    object::{Endianness, Object},
+    section::{shtype, SectionFlags, SectionHeader},
+    strip::{StripMode, StripOptions},
+    util::{align_to, read_cstr_at, ReadExt, WriteExt},
+};
+
+/// The name of the section that links an object to its separate debug file.
+pub const GNU_DEBUGLINK: &str = ".gnu_debuglink";
+
+/// Computes the checksum of a debug file that `.gnu_debuglink` stores, the CRC-32 used by zlib.
+pub fn debuglink_crc(data: &[u8]) -> u32 {
+    crc32fast::hash(data)
+}
+
+/// The contents of a `.gnu_debuglink` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DebugLink {
+    /// The file name of the debug file, without a directory.
+    pub name: String,
+    /// The checksum of the whole debug file.
+    pub crc: u32,
+}
+
+impl DebugLink {
+    pub fn read(endian: &Endianness, data: &[u8]) -> Result<Self> {
+        let name = read_cstr_at(data, 0)?;
+        // The name is padded to a multiple of 4 bytes.
+        let offset = align_to(&(name.len() as u64 + 1), &4);
+        let mut buf = data.get(offset as usize..).unwrap_or_default();
+        let crc = buf.read_u32(endian).map_err(|e| e.truncated(offset))?;
+        Ok(Self { name, crc })
+    }
+
+    pub fn write(&self, endian: &Endianness) -> Result<Vec<u8>> {
+        let mut data = self.name.as_bytes().to_vec();
+        data.push(0);
+        data.resize(align_to(&(data.len() as u64), &4) as usize, 0);
+        data.write_u32(endian, self.crc)?;
+        Ok(data)
+    }
+}
+
+impl Object {
+    /// Reads the link to the separate debug file, if there is one.
+    pub fn debuglink(&self) -> Result<Option<DebugLink>> {
+        self.find_section(GNU_DEBUGLINK)
+            .map(|x| DebugLink::read(&self.header.e_ident.ei_data, &x.body))
+            .transpose()
+    }
+
+    /// Links the object to a separate debug file, like `objcopy --add-gnu-debuglink` does.
+    /// `name` is the file name that debuggers look for, `debug_file` the contents of that file.
+    pub fn set_debuglink(&mut self, name: &str, debug_file: &[u8]) -> Result<()> {
+        let link = DebugLink {
+            name: name.into(),
+            crc: debuglink_crc(debug_file),
+        };
+        let body = link.write(&self.header.e_ident.ei_data)?;
+        match self.find_section_idx(GNU_DEBUGLINK) {
+            Some(idx) => self.sections[idx as usize].body = body,
+            None => {
+                let header = SectionHeader {
+                    sh_type: shtype::SHT_PROGBITS,
+                    sh_addralign: 4,
+                    ..Default::default()
+                };
+                self.add_section(GNU_DEBUGLINK, header, body)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates a copy of the object that only holds what debuggers need, like `objcopy --only-keep-debug`.
+    ///
+    /// The debug sections, notes and the symbol table are kept. All other loaded sections become `SHT_NOBITS`,
+    /// so they keep their headers and addresses but no contents, and segments shrink accordingly.
+    pub fn only_keep_debug(&self) -> Object {
+        let mut result = self.clone();
+        for section in result.sections.iter_mut().skip(1) {
+            let h = &mut section.header;
+            if h.flags().contains(SectionFlags::SHF_ALLOC)
+                && !matches!(h.sh_type, shtype::SHT_NOTE | shtype::SHT_NOBITS)
+            {
+                h.sh_type = shtype::SHT_NOBITS;
+                section.body.clear();
+            }
+        }
+        result.pack();
+        result
+    }
+
+    /// Removes all debug sections and the symbols only debuggers need, like `strip --strip-debug` does.
+    pub fn strip_debug(&mut self) -> Result<()> {
+        self.strip(&StripOptions::new(StripMode::Debug))
+    }
+
+    /// Moves the debug information into a separate file and returns its contents.
+    ///
+    /// This combines `objcopy --only-keep-debug`, `strip --strip-debug` and `objcopy --add-gnu-debuglink`:
+    /// the object itself loses its debug sections and links to the debug file by `name`.
+    pub fn split_debug(&mut self, name: &str) -> Result<Vec<u8>> {
+        if !self.sections.iter().any(|x| x.is_debug()) {
+            return Err(Error::MissingSection(".debug_info".into()));
+        }
+        let mut debug = self.only_keep_debug();
+        let mut output = Cursor::new(Vec::new());
+        debug.write(&mut output)?;
+        let debug_file = output.into_inner();
+
+        self.strip_debug()?;
+        self.set_debuglink(name, &debug_file)?;
+        Ok(debug_file)
+    }
+}
@@ -0,0 +1,525 @@
+use std::io::{Read, Write};
+
+use bitflags::bitflags;
+
+use crate::{
+    error::{Error, Result},
+    object::{Class, Endianness, Object},
+    section::shtype,
+    segment::ptype,
+    util::{read_cstr_at, ReadExt, WriteExt},
+};
+
+/// Defines the enum of dynamic tags together with the conversions from and to the raw value.
+macro_rules! dynamic_tags {
+    ($($variant:ident = $value:literal, $name:literal;)*) => {
+        /// The type of a dynamic entry, stored in `d_tag`.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum DynamicTag {
+            $(#[doc = $name] $variant,)*
+            /// Operating system specific, between `0x6000000D` and `0x6FFFF000`
+            Os(u64),
+            /// Processor specific, between `0x70000000` and `0x7FFFFFFF`
+            Processor(u64),
+            /// Any other value
+            Unknown(u64),
+        }
+
+        impl From<u64> for DynamicTag {
+            fn from(value: u64) -> Self {
+                match value {
+                    $($value => DynamicTag::$variant,)*
+                    0x6000000D..=0x6FFFF000 => DynamicTag::Os(value),
+                    0x70000000..=0x7FFFFFFF => DynamicTag::Processor(value),
+                    x => DynamicTag::Unknown(x),
+                }
+            }
+        }
+
+        impl From<DynamicTag> for u64 {
+            fn from(value: DynamicTag) -> Self {
+                match value {
+                    $(DynamicTag::$variant => $value,)*
+                    DynamicTag::Os(x) | DynamicTag::Processor(x) | DynamicTag::Unknown(x) => x,
+                }
+            }
+        }
+    };
+}
+
+dynamic_tags! {
+    Null = 0, "DT_NULL";
+    Needed = 1, "DT_NEEDED";
+    PltRelSz = 2, "DT_PLTRELSZ";
+    PltGot = 3, "DT_PLTGOT";
+    Hash = 4, "DT_HASH";
+    StrTab = 5, "DT_STRTAB";
+    SymTab = 6, "DT_SYMTAB";
+    Rela = 7, "DT_RELA";
+    RelaSz = 8, "DT_RELASZ";
+    RelaEnt = 9, "DT_RELAENT";
+    StrSz = 10, "DT_STRSZ";
+    SymEnt = 11, "DT_SYMENT";
+    Init = 12, "DT_INIT";
+    Fini = 13, "DT_FINI";
+    SoName = 14, "DT_SONAME";
+    RPath = 15, "DT_RPATH";
+    Symbolic = 16, "DT_SYMBOLIC";
+    Rel = 17, "DT_REL";
+    RelSz = 18, "DT_RELSZ";
+    RelEnt = 19, "DT_RELENT";
+    PltRel = 20, "DT_PLTREL";
+    Debug = 21, "DT_DEBUG";
+    TextRel = 22, "DT_TEXTREL";
+    JmpRel = 23, "DT_JMPREL";
+    BindNow = 24, "DT_BIND_NOW";
+    InitArray = 25, "DT_INIT_ARRAY";
+    FiniArray = 26, "DT_FINI_ARRAY";
+    InitArraySz = 27, "DT_INIT_ARRAYSZ";
+    FiniArraySz = 28, "DT_FINI_ARRAYSZ";
+    RunPath = 29, "DT_RUNPATH";
+    Flags = 30, "DT_FLAGS";
+    PreinitArray = 32, "DT_PREINIT_ARRAY";
+    PreinitArraySz = 33, "DT_PREINIT_ARRAYSZ";
+    SymTabShndx = 34, "DT_SYMTAB_SHNDX";
+    RelrSz = 35, "DT_RELRSZ";
+    Relr = 36, "DT_RELR";
+    RelrEnt = 37, "DT_RELRENT";
+    GnuPrelinked = 0x6FFFFDF5, "DT_GNU_PRELINKED";
+    GnuConflictSz = 0x6FFFFDF6, "DT_GNU_CONFLICTSZ";
+    GnuLiblistSz = 0x6FFFFDF7, "DT_GNU_LIBLISTSZ";
+    Checksum = 0x6FFFFDF8, "DT_CHECKSUM";
+    PltPadSz = 0x6FFFFDF9, "DT_PLTPADSZ";
+    MoveEnt = 0x6FFFFDFA, "DT_MOVEENT";
+    MoveSz = 0x6FFFFDFB, "DT_MOVESZ";
+    Feature1 = 0x6FFFFDFC, "DT_FEATURE_1";
+    PosFlag1 = 0x6FFFFDFD, "DT_POSFLAG_1";
+    SymInSz = 0x6FFFFDFE, "DT_SYMINSZ";
+    SymInEnt = 0x6FFFFDFF, "DT_SYMINENT";
+    GnuHash = 0x6FFFFEF5, "DT_GNU_HASH";
+    TlsDescPlt = 0x6FFFFEF6, "DT_TLSDESC_PLT";
+    TlsDescGot = 0x6FFFFEF7, "DT_TLSDESC_GOT";
+    GnuConflict = 0x6FFFFEF8, "DT_GNU_CONFLICT";
+    GnuLiblist = 0x6FFFFEF9, "DT_GNU_LIBLIST";
+    Config = 0x6FFFFEFA, "DT_CONFIG";
+    DepAudit = 0x6FFFFEFB, "DT_DEPAUDIT";
+    Audit = 0x6FFFFEFC, "DT_AUDIT";
+    PltPad = 0x6FFFFEFD, "DT_PLTPAD";
+    MoveTab = 0x6FFFFEFE, "DT_MOVETAB";
+    SymInfo = 0x6FFFFEFF, "DT_SYMINFO";
+    VerSym = 0x6FFFFFF0, "DT_VERSYM";
+    RelaCount = 0x6FFFFFF9, "DT_RELACOUNT";
+    RelCount = 0x6FFFFFFA, "DT_RELCOUNT";
+    Flags1 = 0x6FFFFFFB, "DT_FLAGS_1";
+    VerDef = 0x6FFFFFFC, "DT_VERDEF";
+    VerDefNum = 0x6FFFFFFD, "DT_VERDEFNUM";
+    VerNeed = 0x6FFFFFFE, "DT_VERNEED";
+    VerNeedNum = 0x6FFFFFFF, "DT_VERNEEDNUM";
+    Auxiliary = 0x7FFFFFFD, "DT_AUXILIARY";
+    Filter = 0x7FFFFFFF, "DT_FILTER";
+}
+
+impl DynamicTag {
+    /// Checks if the value of this tag is an offset into the dynamic string table.
+    pub fn is_string(&self) -> bool {
+        matches!(
+            self,
+            DynamicTag::Needed
+                | DynamicTag::SoName
+                | DynamicTag::RPath
+                | DynamicTag::RunPath
+                | DynamicTag::Auxiliary
+                | DynamicTag::Filter
+                | DynamicTag::Config
+                | DynamicTag::DepAudit
+                | DynamicTag::Audit
+        )
+    }
+}
+
+bitflags! {
+    /// Flags of the `DT_FLAGS` entry.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct DynamicFlags: u64 {
+        /// The object may reference `$ORIGIN`.
+        const DF_ORIGIN = 0x1;
+        /// Symbol lookup starts at the object itself.
+        const DF_SYMBOLIC = 0x2;
+        /// Relocations may modify read-only segments.
+        const DF_TEXTREL = 0x4;
+        /// All relocations are processed at load time.
+        const DF_BIND_NOW = 0x8;
+        /// The object uses the static TLS model.
+        const DF_STATIC_TLS = 0x10;
+
+        const _ = !0;
+    }
+}
+
+bitflags! {
+    /// Flags of the `DT_FLAGS_1` entry.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct DynamicFlags1: u64 {
+        /// All relocations are processed at load time.
+        const DF_1_NOW = 0x1;
+        /// The symbols of the object are available to later loaded objects.
+        const DF_1_GLOBAL = 0x2;
+        /// The object and its dependencies are handled as a group.
+        const DF_1_GROUP = 0x4;
+        /// The object can't be unloaded.
+        const DF_1_NODELETE = 0x8;
+        /// Filtees of the object are loaded right away.
+        const DF_1_LOADFLTR = 0x10;
+        /// The initializers of the object run before those of other objects.
+        const DF_1_INITFIRST = 0x20;
+        /// The object can't be loaded with `dlopen`.
+        const DF_1_NOOPEN = 0x40;
+        /// The object may reference `$ORIGIN`.
+        const DF_1_ORIGIN = 0x80;
+        /// Direct binding is enabled.
+        const DF_1_DIRECT = 0x100;
+        /// Reserved, unused.
+        const DF_1_TRANS = 0x200;
+        /// The object interposes on all objects except the executable.
+        const DF_1_INTERPOSE = 0x400;
+        /// The default library search path is ignored for dependencies.
+        const DF_1_NODEFLIB = 0x800;
+        /// The object is ignored by `dldump`.
+        const DF_1_NODUMP = 0x1000;
+        /// The object is an alternative configuration file.
+        const DF_1_CONFALT = 0x2000;
+        /// Filtee lookup ends at the object.
+        const DF_1_ENDFILTEE = 0x4000;
+        /// Displacement relocations have been applied.
+        const DF_1_DISPRELDNE = 0x8000;
+        /// Displacement relocations are pending.
+        const DF_1_DISPRELPND = 0x10000;
+        /// The object has symbols that can't be bound directly.
+        const DF_1_NODIRECT = 0x20000;
+        /// Multiple definitions of symbols are ignored.
+        const DF_1_IGNMULDEF = 0x40000;
+        /// The symbols of the object are hidden from the kernel.
+        const DF_1_NOKSYMS = 0x80000;
+        /// The object has no ELF header in memory.
+        const DF_1_NOHDR = 0x100000;
+        /// The object was edited after linking.
+        const DF_1_EDITED = 0x200000;
+        /// The object has no relocations.
+        const DF_1_NORELOC = 0x400000;
+        /// Individual symbols of the object interpose.
+        const DF_1_SYMINTPOSE = 0x800000;
+        /// Global auditing is requested.
+        const DF_1_GLOBAUDIT = 0x1000000;
+        /// The object defines singleton symbols.
+        const DF_1_SINGLETON = 0x2000000;
+        /// The object is a stub.
+        const DF_1_STUB = 0x4000000;
+        /// The object is a position independent executable.
+        const DF_1_PIE = 0x8000000;
+        /// The object is a kernel module.
+        const DF_1_KMOD = 0x10000000;
+        /// The object is a weak filter.
+        const DF_1_WEAKFILTER = 0x20000000;
+        /// Common symbols are not allocated.
+        const DF_1_NOCOMMON = 0x40000000;
+
+        const _ = !0;
+    }
+}
+
+/// A single entry of the dynamic section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DynamicEntry {
+    pub d_tag: u64,
+    /// The value or address of this entry, depending on the tag.
+    pub d_val: u64,
+}
+
+impl DynamicEntry {
+    pub fn new(tag: DynamicTag, value: u64) -> Self {
+        Self {
+            d_tag: tag.into(),
+            d_val: value,
+        }
+    }
+
+    /// Returns the type of this entry.
+    pub fn tag(&self) -> DynamicTag {
+        self.d_tag.into()
+    }
+
+    /// Returns the size of a dynamic entry in bytes.
+    pub fn size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 8,
+            Class::Bits64 => 16,
+        }
+    }
+
+    pub fn read(class: &Class, endian: &Endianness, mut buf: impl Read) -> Result<Self> {
+        let entry = match class {
+            Class::Bits32 => Self {
+                d_tag: buf.read_u32(endian)? as u64,
+                d_val: buf.read_u32(endian)? as u64,
+            },
+            Class::Bits64 => Self {
+                d_tag: buf.read_u64(endian)?,
+                d_val: buf.read_u64(endian)?,
+            },
+        };
+        Ok(entry)
+    }
+
+    pub fn write(&self, class: &Class, endian: &Endianness, mut buf: impl Write) -> Result<usize> {
+        let mut written = 0;
+        match class {
+            Class::Bits32 => {
+                written += buf.write_u32(endian, self.d_tag as u32)?;
+                written += buf.write_u32(endian, self.d_val as u32)?;
+            }
+            Class::Bits64 => {
+                written += buf.write_u64(endian, self.d_tag)?;
+                written += buf.write_u64(endian, self.d_val)?;
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// The decoded dynamic section of an object.
+#[derive(Debug, Clone, Default)]
+pub struct Dynamic {
+    /// All entries up to the terminating `DT_NULL`.
+    pub entries: Vec<DynamicEntry>,
+    /// The contents of the string table given by `DT_STRTAB`. Empty if it couldn't be found.
+    pub strtab: Vec<u8>,
+}
+
+impl Dynamic {
+    /// Reads entries from the contents of a dynamic section, stopping at the first `DT_NULL`.
+    pub fn read_entries(
+        class: &Class,
+        endian: &Endianness,
+        data: &[u8],
+    ) -> Result<Vec<DynamicEntry>> {
+        let mut entries = Vec::new();
+        for chunk in data.chunks_exact(DynamicEntry::size(class) as usize) {
+            let entry = DynamicEntry::read(class, endian, chunk)?;
+            if entry.tag() == DynamicTag::Null {
+                break;
+            }
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Returns the value of the first entry with the given tag.
+    pub fn get(&self, tag: DynamicTag) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|x| x.tag() == tag)
+            .map(|x| x.d_val)
+    }
+
+    /// Returns the values of all entries with the given tag.
+    pub fn get_all(&self, tag: DynamicTag) -> Vec<u64> {
+        self.entries
+            .iter()
+            .filter(|x| x.tag() == tag)
+            .map(|x| x.d_val)
+            .collect()
+    }
+
+    /// Resolves the value of a string-valued entry through the string table.
+    /// Returns `None` for entries that don't hold a string.
+    pub fn string(&self, entry: &DynamicEntry) -> Result<Option<String>> {
+        if !entry.tag().is_string() {
+            return Ok(None);
+        }
+        let offset = u32::try_from(entry.d_val).map_err(|_| Error::OutOfRange {
+            offset: entry.d_val,
+            size: 1,
+            limit: self.strtab.len() as u64,
+        })?;
+        read_cstr_at(&self.strtab, offset).map(Some)
+    }
+
+    fn strings(&self, tag: DynamicTag) -> Result<Vec<String>> {
+        self.entries
+            .iter()
+            .filter(|x| x.tag() == tag)
+            .map(|x| self.string(x).map(Option::unwrap_or_default))
+            .collect()
+    }
+
+    /// Returns the names of all needed libraries, in load order.
+    pub fn needed(&self) -> Result<Vec<String>> {
+        self.strings(DynamicTag::Needed)
+    }
+
+    /// Returns the `DT_SONAME` of a shared library.
+    pub fn soname(&self) -> Result<Option<String>> {
+        Ok(self.strings(DynamicTag::SoName)?.into_iter().next())
+    }
+
+    /// Returns the deprecated `DT_RPATH` search path.
+    pub fn rpath(&self) -> Result<Option<String>> {
+        Ok(self.strings(DynamicTag::RPath)?.into_iter().next())
+    }
+
+    /// Returns the `DT_RUNPATH` search path.
+    pub fn runpath(&self) -> Result<Option<String>> {
+        Ok(self.strings(DynamicTag::RunPath)?.into_iter().next())
+    }
+
+    /// Returns the value of `DT_FLAGS`, or no flags if it is missing.
+    pub fn flags(&self) -> DynamicFlags {
+        DynamicFlags::from_bits_retain(self.get(DynamicTag::Flags).unwrap_or(0))
+    }
+
+    /// Returns the value of `DT_FLAGS_1`, or no flags if it is missing.
+    pub fn flags_1(&self) -> DynamicFlags1 {
+        DynamicFlags1::from_bits_retain(self.get(DynamicTag::Flags1).unwrap_or(0))
+    }
+}
+
+impl Object {
+    /// Decodes the dynamic section. Falls back to the `PT_DYNAMIC` segment if the object has no `SHT_DYNAMIC`
+    /// section, e.g. because its section headers were removed. Returns `None` for statically linked objects.
+    pub fn dynamic(&self) -> Result<Option<Dynamic>> {
+        let class = &self.header.e_ident.ei_class;
+        let endian = &self.header.e_ident.ei_data;
+        let section = self
+            .sections
+            .iter()
+            .find(|x| x.header.sh_type == shtype::SHT_DYNAMIC);
+        let entries = match section {
+            Some(section) => Dynamic::read_entries(class, endian, &section.body)?,
+            None => {
+                let Some(segment) = self
+                    .segments
+                    .iter()
+                    .find(|x| x.header.p_type == ptype::PT_DYNAMIC)
+                else {
+                    return Ok(None);
+                };
+                let h = &segment.header;
+                let start = usize::try_from(h.p_offset).ok();
+                let end = h
+                    .p_offset
+                    .checked_add(h.p_filesz)
+                    .and_then(|x| usize::try_from(x).ok());
+                let data = start
+                    .zip(end)
+                    .and_then(|(start, end)| self.image.get(start..end))
+                    .ok_or(Error::OutOfRange {
+                        offset: h.p_offset,
+                        size: h.p_filesz,
+                        limit: self.image.len() as u64,
+                    })?;
+                Dynamic::read_entries(class, endian, data)?
+            }
+        };
+        let mut dynamic = Dynamic {
+            entries,
+            strtab: Vec::new(),
+        };
+
+        // Prefer the linked section, it also reflects changes that weren't written yet.
+        let linked = section
+            .and_then(|x| self.sections.get(x.header.sh_link as usize))
+            .filter(|x| x.header.sh_type == shtype::SHT_STRTAB);
+        if let Some(strtab) = linked {
+            dynamic.strtab = strtab.body.clone();
+        } else if let Some(addr) = dynamic.get(DynamicTag::StrTab) {
+            let size = dynamic.get(DynamicTag::StrSz).unwrap_or(0);
+            if let Some(data) = self.data_at(addr, size) {
+                dynamic.strtab = data.to_vec();
+            }
+        }
+        Ok(Some(dynamic))
+    }
+
+    /// Returns the file contents that are mapped at a virtual address. Looks at the sections first and falls
+    /// back to the loadable segments, so that it also works for objects without section headers.
+    /// Returns `None` if the range isn't mapped or doesn't fit into the address space.
+    pub fn data_at(&self, addr: u64, size: u64) -> Option<&[u8]> {
+        let end = addr.checked_add(size)?;
+        let contains = |start: u64, len: u64| {
+            addr >= start && start.checked_add(len).is_some_and(|x| end <= x)
+        };
+        // Returns the range of `size` bytes at `offset`.
+        let range = |offset: u64| {
+            let start = usize::try_from(offset).ok()?;
+            Some(start..start.checked_add(usize::try_from(size).ok()?)?)
+        };
+        let section = self.sections.iter().find(|x| {
+            x.header.sh_type != shtype::SHT_NOBITS
+                && x.header.sh_addr != 0
+                && contains(x.header.sh_addr, x.body.len() as u64)
+        });
+        if let Some(section) = section {
+            return section.body.get(range(addr - section.header.sh_addr)?);
+        }
+        let segment = self.segments.iter().find(|x| {
+            x.header.p_type == ptype::PT_LOAD && contains(x.header.p_vaddr, x.header.p_filesz)
+        })?;
+        let offset = segment
+            .header
+            .p_offset
+            .checked_add(addr - segment.header.p_vaddr)?;
+        self.image.get(range(offset)?)
+    }
+
+    /// Updates the entries of the dynamic section that point to the dynamic symbol table and the sections
+    /// that belong to it, since they might have been moved or resized.
+    pub(crate) fn update_dynamic(&mut self) -> Result<()> {
+        let Some(idx) = (0..self.sections.len())
+            .find(|&i| self.sections[i].header.sh_type == shtype::SHT_DYNAMIC)
+        else {
+            return Ok(());
+        };
+        let find = |sh_type| self.sections.iter().find(|x| x.header.sh_type == sh_type);
+        let dynsym = find(shtype::SHT_DYNSYM);
+        let dynstr = dynsym.and_then(|x| self.sections.get(x.header.sh_link as usize));
+        let value = |tag| {
+            let section = match tag {
+                DynamicTag::StrSz => return dynstr.map(|x| x.body.len() as u64),
+                DynamicTag::VerDefNum => {
+                    return find(shtype::SHT_GNU_VERDEF).map(|x| x.header.sh_info as u64)
+                }
+                DynamicTag::VerNeedNum => {
+                    return find(shtype::SHT_GNU_VERNEED).map(|x| x.header.sh_info as u64)
+                }
+                DynamicTag::StrTab => dynstr,
+                DynamicTag::SymTab => dynsym,
+                DynamicTag::Hash => find(shtype::SHT_HASH),
+                DynamicTag::GnuHash => find(shtype::SHT_GNU_HASH),
+                DynamicTag::VerSym => find(shtype::SHT_GNU_VERSYM),
+                DynamicTag::VerDef => find(shtype::SHT_GNU_VERDEF),
+                DynamicTag::VerNeed => find(shtype::SHT_GNU_VERNEED),
+                _ => None,
+            };
+            section.map(|x| x.header.sh_addr).filter(|&addr| addr != 0)
+        };
+
+        let class = &self.header.e_ident.ei_class;
+        let endian = &self.header.e_ident.ei_data;
+        let mut body = self.sections[idx].body.clone();
+        for chunk in body.chunks_exact_mut(DynamicEntry::size(class) as usize) {
+            let mut entry = DynamicEntry::read(class, endian, &*chunk)?;
+            if entry.tag() == DynamicTag::Null {
+                break;
+            }
+            match value(entry.tag()) {
+                Some(x) if x != entry.d_val => {
+                    entry.d_val = x;
+                    entry.write(class, endian, chunk)?;
+                }
+                _ => {}
+            }
+        }
+        self.sections[idx].body = body;
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+use std::io;
+use std::string::FromUtf8Error;
+
+/// All errors that can occur while reading or writing an object.
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error occured on the underlying stream.
+    Io(io::Error),
+    /// The file does not start with the ELF magic number.
+    BadMagic([u8; 4]),
+    /// The value of `EI_CLASS` is neither 32-bit nor 64-bit.
+    UnsupportedClass(u8),
+    /// The value of `EI_DATA` is neither little nor big endian.
+    UnsupportedEndianness(u8),
+    /// The stream ended before the header at the given offset could be read completely.
+    TruncatedHeader { offset: u64 },
+    /// A range referenced by the object lies outside of its bounds.
+    OutOfRange { offset: u64, size: u64, limit: u64 },
+    /// A section that is required for the requested operation is not present.
+    MissingSection(String),
+    /// The null section at index 0 can't be used for the requested operation.
+    NullSection,
+    /// A section doesn't have the type that the requested operation needs.
+    WrongSectionType { name: String, sh_type: u32 },
+    /// A relocation type can't be applied.
+    UnsupportedRelocation(String),
+    /// The value computed for a relocation doesn't fit into the relocated location.
+    RelocationOverflow { name: String, offset: u64 },
+    /// The address of a symbol referenced by a relocation is unknown.
+    UnresolvedSymbol(String),
+    /// A name is not valid UTF-8.
+    InvalidUtf8(FromUtf8Error),
+    /// The object can't be laid out without breaking it.
+    Layout(String),
+    /// A section can't be compressed or decompressed, e.g. because the codec isn't enabled.
+    Compression(String),
+    /// A build ID can't be computed, e.g. because the hash function isn't enabled.
+    BuildId(String),
+    /// The contents of a section don't follow its format.
+    Malformed { name: String, reason: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// Converts an unexpected end of file into a truncated header error at `offset`.
+    pub(crate) fn truncated(self, offset: u64) -> Self {
+        match self {
+            Error::Io(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                Error::TruncatedHeader { offset }
+            }
+            x => x,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::BadMagic(magic) => write!(f, "Bad magic number: {:02x?}", magic),
+            Error::UnsupportedClass(x) => write!(f, "Unsupported class: {}", x),
+            Error::UnsupportedEndianness(x) => write!(f, "Unsupported endianness: {}", x),
+            Error::TruncatedHeader { offset } => {
+                write!(f, "Truncated header at offset {:#x}", offset)
+            }
+            Error::OutOfRange {
+                offset,
+                size,
+                limit,
+            } => write!(
+                f,
+                "Range {:#x}..{:#x} is out of bounds (limit is {:#x})",
+                offset,
+                offset.saturating_add(*size),
+                limit
+            ),
+            Error::MissingSection(name) => write!(f, "Missing section \"{}\"", name),
+            Error::NullSection => write!(f, "The null section can't be used here"),
+            Error::WrongSectionType { name, sh_type } => {
+                write!(f, "Section \"{}\" has unexpected type {:#x}", name, sh_type)
+            }
+            Error::UnsupportedRelocation(name) => write!(f, "Unsupported relocation type {}", name),
+            Error::RelocationOverflow { name, offset } => {
+                write!(f, "Relocation {} at offset {:#x} overflows", name, offset)
+            }
+            Error::UnresolvedSymbol(name) => write!(f, "Unresolved symbol \"{}\"", name),
+            Error::InvalidUtf8(e) => write!(f, "Invalid UTF-8 in name: {}", e),
+            Error::Layout(x) => write!(f, "Unable to lay out object: {}", x),
+            Error::Compression(x) => write!(f, "Compression error: {}", x),
+            Error::BuildId(x) => write!(f, "Build ID error: {}", x),
+            Error::Malformed { name, reason } => {
+                write!(f, "Malformed section \"{}\": {}", name, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl From<FromUtf8Error> for Error {
+    fn from(value: FromUtf8Error) -> Self {
+        Error::InvalidUtf8(value)
+    }
+}
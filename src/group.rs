@@ -0,0 +1,252 @@
+use bitflags::bitflags;
+
+use crate::{
+    error::{Error, Result},
+    object::{Endianness, Object},
+    section::{shtype, SectionFlags, SectionHeader, SectionId},
+    symbol::{Symbol, SymbolBinding, SymbolType},
+    util::{ReadExt, WriteExt},
+};
+
+bitflags! {
+    /// Flags of a section group, stored in the first word of a `SHT_GROUP` section.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct GroupFlags: u32 {
+        /// Only one group with the same signature is kept by the linker
+        const GRP_COMDAT = 0x1;
+        /// OS specific
+        const GRP_MASKOS = 0x0FF00000;
+        /// Processor specific
+        const GRP_MASKPROC = 0xF0000000;
+
+        const _ = !0;
+    }
+}
+
+/// The contents of a `SHT_GROUP` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Group {
+    pub flags: GroupFlags,
+    /// The index of the symbol whose name identifies the group, stored in `sh_info`.
+    pub signature: u32,
+    /// The indices of the member sections.
+    pub members: Vec<u32>,
+}
+
+impl Group {
+    /// Reads the flags and members of a group. The signature is stored in the section header.
+    pub fn read(endian: &Endianness, signature: u32, data: &[u8]) -> Result<Self> {
+        let mut buf = data;
+        let flags = buf.read_u32(endian).map_err(|e| e.truncated(0))?;
+        let mut members = Vec::new();
+        for mut chunk in buf.chunks_exact(4) {
+            members.push(chunk.read_u32(endian)?);
+        }
+        Ok(Self {
+            flags: GroupFlags::from_bits_retain(flags),
+            signature,
+            members,
+        })
+    }
+
+    pub fn write(&self, endian: &Endianness) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        data.write_u32(endian, self.flags.bits())?;
+        for &x in &self.members {
+            data.write_u32(endian, x)?;
+        }
+        Ok(data)
+    }
+
+    pub fn is_comdat(&self) -> bool {
+        self.flags.contains(GroupFlags::GRP_COMDAT)
+    }
+}
+
+impl Object {
+    /// Reads a group section, given by index or name.
+    pub fn group(&self, section: impl SectionId) -> Result<Group> {
+        let idx = section.section_idx(self)?;
+        let section = &self.sections[idx];
+        if section.header.sh_type != shtype::SHT_GROUP {
+            return Err(Error::WrongSectionType {
+                name: section.name.clone(),
+                sh_type: section.header.sh_type,
+            });
+        }
+        Group::read(
+            &self.header.e_ident.ei_data,
+            section.header.sh_info,
+            &section.body,
+        )
+    }
+
+    /// Reads all group sections together with their index.
+    pub fn groups(&self) -> Result<Vec<(usize, Group)>> {
+        (0..self.sections.len())
+            .filter(|&i| self.sections[i].header.sh_type == shtype::SHT_GROUP)
+            .map(|i| Ok((i, self.group(i)?)))
+            .collect()
+    }
+
+    /// Returns the index of the group that a section is a member of, if any.
+    pub fn group_of(&self, section: impl SectionId) -> Result<Option<usize>> {
+        let idx = section.section_idx(self)? as u32;
+        for (i, group) in self.groups()? {
+            if group.members.contains(&idx) {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Looks up the name of the signature symbol of a group section.
+    /// Groups that are signed by a section symbol are named after the section.
+    pub fn group_signature(&self, section: impl SectionId) -> Result<String> {
+        let idx = section.section_idx(self)?;
+        let group = self.group(idx)?;
+        let symtab = self.sections[idx].header.sh_link as usize;
+        let symbols = match self.sections.get(symtab) {
+            Some(x) if symtab != 0 && x.header.sh_type == shtype::SHT_SYMTAB => {
+                self.symbols.clone()
+            }
+            Some(x) if symtab != 0 && x.header.sh_type == shtype::SHT_DYNSYM => {
+                self.read_symbols(symtab)?
+            }
+            _ => return Err(Error::MissingSection(".symtab".into())),
+        };
+        let symbol = symbols
+            .get(group.signature as usize)
+            .ok_or(Error::OutOfRange {
+                offset: group.signature as u64,
+                size: 1,
+                limit: symbols.len() as u64,
+            })?;
+        match (symbol.kind(), self.sections.get(symbol.sym_shndx as usize)) {
+            (SymbolType::Section, Some(x)) => Ok(x.name.clone()),
+            _ => Ok(symbol.name.clone()),
+        }
+    }
+
+    /// Replaces the flags, signature and members of a group section.
+    /// Sections that join the group get the `SHF_GROUP` flag, sections that leave it lose it.
+    pub fn set_group(&mut self, section: impl SectionId, group: &Group) -> Result<()> {
+        let idx = section.section_idx(self)?;
+        let old = self.group(idx)?;
+        for &x in &group.members {
+            if x == 0 || x as usize >= self.sections.len() {
+                return Err(Error::OutOfRange {
+                    offset: x as u64,
+                    size: 1,
+                    limit: self.sections.len() as u64,
+                });
+            }
+        }
+        for &x in old.members.iter().filter(|x| !group.members.contains(x)) {
+            if let Some(member) = self.sections.get_mut(x as usize) {
+                let h = &mut member.header;
+                h.set_flags(h.flags() - SectionFlags::SHF_GROUP);
+            }
+        }
+        for &x in &group.members {
+            let h = &mut self.sections[x as usize].header;
+            h.set_flags(h.flags() | SectionFlags::SHF_GROUP);
+        }
+        self.sections[idx].body = group.write(&self.header.e_ident.ei_data)?;
+        self.sections[idx].header.sh_info = group.signature;
+        Ok(())
+    }
+
+    /// Creates a new group of sections, signed by the symbol with the given name, and returns the index of its
+    /// section. The group section is inserted before its first member, like assemblers do.
+    ///
+    /// Relocation sections that apply to a member join the group as well, since linkers discard them together.
+    /// If there is no symbol with the name of the signature, a local symbol is added to the group section.
+    pub fn add_group(
+        &mut self,
+        signature: &str,
+        flags: GroupFlags,
+        members: &[usize],
+    ) -> Result<usize> {
+        let symtab = (0..self.sections.len())
+            .find(|&i| self.sections[i].header.sh_type == shtype::SHT_SYMTAB)
+            .ok_or_else(|| Error::MissingSection(".symtab".into()))?;
+        let mut all = Vec::new();
+        for &x in members {
+            if x == 0 || x >= self.sections.len() {
+                return Err(Error::OutOfRange {
+                    offset: x as u64,
+                    size: 1,
+                    limit: self.sections.len() as u64,
+                });
+            }
+            all.push(x);
+            all.extend((0..self.sections.len()).filter(|&i| {
+                let h = &self.sections[i].header;
+                matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA) && h.sh_info as usize == x
+            }));
+        }
+        all.sort_unstable();
+        all.dedup();
+        let first = all.first().copied().unwrap_or(self.sections.len());
+
+        let header = SectionHeader {
+            sh_type: shtype::SHT_GROUP,
+            sh_addralign: 4,
+            sh_entsize: 4,
+            ..Default::default()
+        };
+        let idx = self.insert_section(first, ".group", header, vec![0; 4])?;
+        let symtab = if symtab >= idx { symtab + 1 } else { symtab };
+
+        let signature = match self.find_symbol_idx(signature) {
+            Some(x) => x,
+            None => {
+                // Local symbols come before all others, and `sh_info` of the symbol table is the index
+                // of the first non-local one.
+                let first_global = (self.sections[symtab].header.sh_info as usize)
+                    .clamp(1, self.symbols.len().max(1));
+                let mut symbol = Symbol {
+                    name: signature.into(),
+                    sym_shndx: idx as u16,
+                    ..Default::default()
+                };
+                symbol.set_binding(SymbolBinding::Local);
+                symbol.set_kind(SymbolType::NoType);
+                if self.symbols.is_empty() {
+                    self.symbols.push(Symbol::default());
+                }
+                self.symbols.insert(first_global, symbol);
+                let limit = first_global as u32;
+                self.renumber_symbols(symtab, |x| if x >= limit { x + 1 } else { x })?;
+                self.sections[symtab].header.sh_info = first_global as u32 + 1;
+                first_global
+            }
+        };
+
+        self.sections[idx].header.sh_link = symtab as u32;
+        let group = Group {
+            flags,
+            signature: signature as u32,
+            members: all.iter().map(|&x| x as u32 + 1).collect(),
+        };
+        self.set_group(idx, &group)?;
+        Ok(idx)
+    }
+
+    /// Adds a section, and the relocation sections that apply to it, to an existing group.
+    pub fn add_to_group(&mut self, group: impl SectionId, section: impl SectionId) -> Result<()> {
+        let idx = group.section_idx(self)?;
+        let section = section.section_idx(self)?;
+        let mut group = self.group(idx)?;
+        for i in 0..self.sections.len() {
+            let h = &self.sections[i].header;
+            let reloc = matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA)
+                && h.sh_info as usize == section;
+            if (i == section || reloc) && !group.members.contains(&(i as u32)) {
+                group.members.push(i as u32);
+            }
+        }
+        self.set_group(idx, &group)
+    }
+}
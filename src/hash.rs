@@ -0,0 +1,306 @@
+use crate::{
+    error::{Error, Result},
+    object::{Class, Endianness, Object},
+    section::shtype,
+    symbol::Symbol,
+    util::{ReadExt, WriteExt},
+};
+
+/// Computes the hash of a symbol name that is used by `SHT_HASH` sections.
+pub fn sysv_hash(name: &str) -> u32 {
+    let mut h: u32 = 0;
+    for &c in name.as_bytes() {
+        h = (h << 4).wrapping_add(c as u32);
+        let g = h & 0xF000_0000;
+        h ^= g >> 24;
+        h &= !g;
+    }
+    h
+}
+
+/// Computes the hash of a symbol name that is used by `SHT_GNU_HASH` sections.
+pub fn gnu_hash(name: &str) -> u32 {
+    name.bytes()
+        .fold(5381u32, |h, c| h.wrapping_mul(33).wrapping_add(c as u32))
+}
+
+/// Reads `count` 32-bit words, reporting truncation at `offset` into the section.
+fn read_words(buf: &mut &[u8], endian: &Endianness, count: u32, offset: usize) -> Result<Vec<u32>> {
+    let size = count as usize * 4;
+    if buf.len() < size {
+        return Err(Error::TruncatedHeader {
+            offset: (offset + buf.len()) as u64,
+        });
+    }
+    (0..count).map(|_| buf.read_u32(endian)).collect()
+}
+
+/// The contents of a `SHT_HASH` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SysvHashTable {
+    /// The index of the first symbol in each bucket, 0 for empty buckets.
+    pub buckets: Vec<u32>,
+    /// The index of the next symbol in the same bucket, for each symbol. The chains end at symbol 0.
+    pub chains: Vec<u32>,
+}
+
+impl SysvHashTable {
+    /// Builds a table for the given symbol names, in symbol table order.
+    pub fn build(names: &[&str], nbucket: u32) -> Self {
+        let nbucket = nbucket.max(1);
+        let mut buckets = vec![0u32; nbucket as usize];
+        let mut chains = vec![0u32; names.len()];
+        // Symbol 0 is the null symbol and ends every chain.
+        for (i, name) in names.iter().enumerate().skip(1) {
+            let bucket = (sysv_hash(name) % nbucket) as usize;
+            chains[i] = buckets[bucket];
+            buckets[bucket] = i as u32;
+        }
+        Self { buckets, chains }
+    }
+
+    pub fn read(endian: &Endianness, data: &[u8]) -> Result<Self> {
+        let mut buf = data;
+        let nbucket = buf.read_u32(endian).map_err(|e| e.truncated(0))?;
+        let nchain = buf.read_u32(endian).map_err(|e| e.truncated(4))?;
+        let buckets = read_words(&mut buf, endian, nbucket, 8)?;
+        let chains = read_words(&mut buf, endian, nchain, 8 + nbucket as usize * 4)?;
+        Ok(Self { buckets, chains })
+    }
+
+    pub fn write(&self, endian: &Endianness) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        data.write_u32(endian, self.buckets.len() as u32)?;
+        data.write_u32(endian, self.chains.len() as u32)?;
+        for &x in self.buckets.iter().chain(&self.chains) {
+            data.write_u32(endian, x)?;
+        }
+        Ok(data)
+    }
+
+    /// Finds the index of a symbol by walking the chain of its bucket, like the dynamic loader does.
+    pub fn lookup(&self, name: &str, symbols: &[Symbol]) -> Option<usize> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+        let mut idx = self.buckets[sysv_hash(name) as usize % self.buckets.len()] as usize;
+        // Every symbol is visited at most once, even if the chains form a loop.
+        for _ in 0..self.chains.len() {
+            if idx == 0 {
+                break;
+            }
+            if symbols.get(idx)?.name == name {
+                return Some(idx);
+            }
+            idx = *self.chains.get(idx)? as usize;
+        }
+        None
+    }
+}
+
+/// The contents of a `SHT_GNU_HASH` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GnuHashTable {
+    /// The index of the first hashed symbol. Symbols before it can't be looked up.
+    pub symoffset: u32,
+    /// The shift for the second bit that is set in the bloom filter. Less than 32.
+    pub bloom_shift: u32,
+    /// The words of the bloom filter. They have 32 bits in 32-bit objects.
+    pub bloom: Vec<u64>,
+    /// The index of the first symbol in each bucket, 0 for empty buckets.
+    pub buckets: Vec<u32>,
+    /// The hash of each hashed symbol, with the lowest bit set for the last symbol of a bucket.
+    pub chains: Vec<u32>,
+}
+
+impl GnuHashTable {
+    /// Builds a table for the given names of hashed symbols, which start at index `symoffset` of the symbol table
+    /// and have to be sorted by their bucket.
+    pub fn build(
+        names: &[&str],
+        symoffset: u32,
+        nbuckets: u32,
+        bloom_size: u32,
+        bloom_shift: u32,
+        class: &Class,
+    ) -> Self {
+        let nbuckets = nbuckets.max(1);
+        let bloom_size = bloom_size.max(1);
+        let bits = Self::word_bits(class);
+        let hashes: Vec<_> = names.iter().map(|x| gnu_hash(x)).collect();
+
+        let mut bloom = vec![0u64; bloom_size as usize];
+        let mut buckets = vec![0u32; nbuckets as usize];
+        let mut chains = vec![0u32; hashes.len()];
+        for (i, &h) in hashes.iter().enumerate() {
+            let word = ((h / bits) % bloom_size) as usize;
+            bloom[word] |= Self::bloom_mask(h, bloom_shift, bits);
+
+            let bucket = (h % nbuckets) as usize;
+            if buckets[bucket] == 0 {
+                buckets[bucket] = symoffset + i as u32;
+            }
+            // The lowest bit marks the end of a chain.
+            let last = hashes
+                .get(i + 1)
+                .is_none_or(|&x| x % nbuckets != h % nbuckets);
+            chains[i] = (h & !1) | last as u32;
+        }
+        Self {
+            symoffset,
+            bloom_shift,
+            bloom,
+            buckets,
+            chains,
+        }
+    }
+
+    /// Returns the number of bits in a word of the bloom filter.
+    fn word_bits(class: &Class) -> u32 {
+        match class {
+            Class::Bits32 => 32,
+            Class::Bits64 => 64,
+        }
+    }
+
+    /// Returns the two bits that a hash sets in a word of the bloom filter.
+    /// A bloom shift of 32 or more shifts out the whole hash, which `read` rejects, but hand-built tables may have it.
+    fn bloom_mask(hash: u32, bloom_shift: u32, bits: u32) -> u64 {
+        let shifted = hash.checked_shr(bloom_shift).unwrap_or(0);
+        1 << (hash % bits) | 1 << (shifted % bits)
+    }
+
+    /// Reads a table. The number of chains isn't stored, so they extend to the end of `data`.
+    /// Fails if the bloom shift is 32 or more.
+    pub fn read(class: &Class, endian: &Endianness, data: &[u8]) -> Result<Self> {
+        let mut buf = data;
+        let mut header = [0; 4];
+        for (i, x) in header.iter_mut().enumerate() {
+            *x = buf
+                .read_u32(endian)
+                .map_err(|e| e.truncated(i as u64 * 4))?;
+        }
+        let [nbuckets, symoffset, bloom_size, bloom_shift] = header;
+        // The loader shifts 32-bit hashes by this amount.
+        if bloom_shift >= 32 {
+            return Err(Error::Malformed {
+                name: ".gnu.hash".into(),
+                reason: format!("bloom shift {} is too large", bloom_shift),
+            });
+        }
+
+        let word_size = Self::word_bits(class) as usize / 8;
+        if buf.len() < bloom_size as usize * word_size {
+            return Err(Error::TruncatedHeader {
+                offset: data.len() as u64,
+            });
+        }
+        let mut bloom = Vec::new();
+        for _ in 0..bloom_size {
+            bloom.push(match class {
+                Class::Bits32 => buf.read_u32(endian)? as u64,
+                Class::Bits64 => buf.read_u64(endian)?,
+            });
+        }
+        let offset = data.len() - buf.len();
+        let buckets = read_words(&mut buf, endian, nbuckets, offset)?;
+        let nchains = buf.len() as u32 / 4;
+        let offset = data.len() - buf.len();
+        let chains = read_words(&mut buf, endian, nchains, offset)?;
+        Ok(Self {
+            symoffset,
+            bloom_shift,
+            bloom,
+            buckets,
+            chains,
+        })
+    }
+
+    pub fn write(&self, class: &Class, endian: &Endianness) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        data.write_u32(endian, self.buckets.len() as u32)?;
+        data.write_u32(endian, self.symoffset)?;
+        data.write_u32(endian, self.bloom.len() as u32)?;
+        data.write_u32(endian, self.bloom_shift)?;
+        for &word in &self.bloom {
+            match class {
+                Class::Bits32 => data.write_u32(endian, word as u32)?,
+                Class::Bits64 => data.write_u64(endian, word)?,
+            };
+        }
+        for &x in self.buckets.iter().chain(&self.chains) {
+            data.write_u32(endian, x)?;
+        }
+        Ok(data)
+    }
+
+    /// Finds the index of a symbol like the dynamic loader does: the bloom filter rules out most missing
+    /// symbols, then the chain of the bucket is scanned for a matching hash before names are compared.
+    pub fn lookup(&self, name: &str, symbols: &[Symbol], class: &Class) -> Option<usize> {
+        if self.bloom.is_empty() || self.buckets.is_empty() {
+            return None;
+        }
+        let bits = Self::word_bits(class);
+        let h = gnu_hash(name);
+        let word = self.bloom[(h / bits) as usize % self.bloom.len()];
+        let mask = Self::bloom_mask(h, self.bloom_shift, bits);
+        if word & mask != mask {
+            return None;
+        }
+
+        let mut idx = self.buckets[h as usize % self.buckets.len()] as usize;
+        if idx == 0 {
+            return None;
+        }
+        loop {
+            let chain = *self.chains.get(idx.checked_sub(self.symoffset as usize)?)?;
+            if chain | 1 == h | 1 && symbols.get(idx)?.name == name {
+                return Some(idx);
+            }
+            if chain & 1 != 0 {
+                return None;
+            }
+            idx += 1;
+        }
+    }
+}
+
+impl Object {
+    /// Returns the index of the hash section of the given type that belongs to the dynamic symbols.
+    pub(crate) fn hash_section(&self, sh_type: u32) -> Option<usize> {
+        let dynsym = self.dynsym_idx()?;
+        (0..self.sections.len()).find(|&i| {
+            let h = &self.sections[i].header;
+            h.sh_type == sh_type && h.sh_link as usize == dynsym
+        })
+    }
+
+    /// Reads the `SHT_HASH` table of the dynamic symbols, if there is one.
+    pub fn sysv_hash_table(&self) -> Result<Option<SysvHashTable>> {
+        self.hash_section(shtype::SHT_HASH)
+            .map(|i| SysvHashTable::read(&self.header.e_ident.ei_data, &self.sections[i].body))
+            .transpose()
+    }
+
+    /// Reads the `SHT_GNU_HASH` table of the dynamic symbols, if there is one.
+    pub fn gnu_hash_table(&self) -> Result<Option<GnuHashTable>> {
+        self.hash_section(shtype::SHT_GNU_HASH)
+            .map(|i| {
+                let ident = &self.header.e_ident;
+                GnuHashTable::read(&ident.ei_class, &ident.ei_data, &self.sections[i].body)
+            })
+            .transpose()
+    }
+
+    /// Looks up a dynamic symbol through the hash tables and returns its index. The GNU hash table is preferred,
+    /// like the dynamic loader does. Returns `None` if the symbol isn't found or the object has no hash table.
+    pub fn lookup_dynamic_symbol(&self, name: &str) -> Result<Option<usize>> {
+        let symbols = self.dynamic_symbols()?;
+        if let Some(table) = self.gnu_hash_table()? {
+            return Ok(table.lookup(name, &symbols, &self.header.e_ident.ei_class));
+        }
+        Ok(self
+            .sysv_hash_table()?
+            .and_then(|table| table.lookup(name, &symbols)))
+    }
+}
@@ -1,29 +1,42 @@
 use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 
 use crate::{
+    error::{Error, Result},
     object::{Class, Endianness, Header, Ident, Object},
-    section::{Section, SectionHeader},
+    section::{shtype, Section, SectionHeader},
     segment::{ProgramHeader, Segment},
-    symbol::Symbol,
-    util::{ReadExt, Result, WriteExt},
+    util::{read_cstr_at, ReadExt, WriteExt},
 };
 
 impl Header {
-    pub fn read(mut buf: impl Read) -> Result<Self> {
+    /// Reads an Object header from a stream.
+    ///
+    /// # Example
+    /// ```
+    /// use crate::zehn::*;
+    ///
+    /// let result = object::Header::read(&b"MZ\x90\x00"[..]);
+    /// assert!(matches!(result, Err(Error::BadMagic(_))));
+    /// ```
+    pub fn read(buf: impl Read) -> Result<Self> {
+        Self::read_inner(buf).map_err(|e| e.truncated(0))
+    }
+
+    fn read_inner(mut buf: impl Read) -> Result<Self> {
         let ident = Ident {
             ei_magic: match buf.read_bytes()? {
                 [0x7F, 0x45, 0x4C, 0x46] => [0x7F, 0x45, 0x4C, 0x46],
-                _ => todo!("Replace with custom error type."),
+                x => return Err(Error::BadMagic(x)),
             },
             ei_class: match buf.read_u8()? {
                 1 => Class::Bits32,
                 2 => Class::Bits64,
-                _ => todo!("Replace with custom error type."),
+                x => return Err(Error::UnsupportedClass(x)),
             },
             ei_data: match buf.read_u8()? {
                 1 => Endianness::Little,
                 2 => Endianness::Big,
-                _ => todo!("Replace with custom error type."),
+                x => return Err(Error::UnsupportedEndianness(x)),
             },
             ei_version: buf.read_u8()?,
             ei_osabi: buf.read_u8()?,
@@ -118,124 +131,147 @@ impl Header {
 impl Object {
     pub fn read(mut input: impl Read + Seek) -> Result<Self> {
         let mut result = Object::new();
-        let mut old_pos = 0;
+        input.seek(SeekFrom::Start(0))?;
+        input.read_to_end(&mut result.image)?;
+        let file_len = result.image.len() as u64;
 
         // Read header.
-        input.seek(SeekFrom::Start(old_pos))?;
+        input.seek(SeekFrom::Start(0))?;
         result.header = Header::read(&mut input)?;
+        let class = result.header.e_ident.ei_class.clone();
+        let endian = result.header.e_ident.ei_data.clone();
 
         // Read program headers.
-        input.seek(SeekFrom::Start(result.header.e_phoff))?;
-        for _ in 0..result.header.e_phnum {
-            let header = ProgramHeader::read(
-                &result.header.e_ident.ei_class,
-                &result.header.e_ident.ei_data,
-                &mut input,
-            )?;
+        check_range(
+            result.header.e_phoff,
+            result.header.e_phentsize as u64 * result.header.e_phnum as u64,
+            file_len,
+        )?;
+        for i in 0..result.header.e_phnum as u64 {
+            let offset = result.header.e_phoff + i * result.header.e_phentsize as u64;
+            input.seek(SeekFrom::Start(offset))?;
+            let header = ProgramHeader::read(&class, &endian, &mut input)
+                .map_err(|e| e.truncated(offset))?;
             let prog = Segment::new(header);
             result.segments.push(prog);
         }
 
         // Read sections.
+        check_range(
+            result.header.e_shoff,
+            result.header.e_shentsize as u64 * result.header.e_shnum as u64,
+            file_len,
+        )?;
         let mut sections = Vec::new();
-        input.seek(SeekFrom::Start(result.header.e_shoff))?;
-        for _ in 0..result.header.e_shnum {
+        for i in 0..result.header.e_shnum as u64 {
             // Read section header.
-            let section_header = SectionHeader::read(
-                &result.header.e_ident.ei_class,
-                &result.header.e_ident.ei_data,
-                &mut input,
-            )?;
+            let offset = result.header.e_shoff + i * result.header.e_shentsize as u64;
+            input.seek(SeekFrom::Start(offset))?;
+            let section_header = SectionHeader::read(&class, &endian, &mut input)
+                .map_err(|e| e.truncated(offset))?;
 
-            // Read section body.
-            old_pos = input.stream_position()?;
-            let mut section_body = vec![0u8; section_header.sh_size as usize];
-            input.seek(SeekFrom::Start(section_header.sh_offset))?;
-            input.read_exact(&mut section_body)?;
+            // Read section body. NOBITS sections don't occupy any space in the file.
+            let mut section_body = Vec::new();
+            if section_header.sh_type != shtype::SHT_NOBITS {
+                check_range(section_header.sh_offset, section_header.sh_size, file_len)?;
+                section_body.resize(section_header.sh_size as usize, 0);
+                input.seek(SeekFrom::Start(section_header.sh_offset))?;
+                input.read_exact(&mut section_body)?;
+            }
 
             let sect = Section {
+                name: String::new(),
                 header: section_header,
                 body: section_body,
             };
             sections.push(sect);
-            input.seek(SeekFrom::Start(old_pos))?;
         }
 
-        // Read section names.
-        let shstrtab = &sections[result.header.e_shstrndx as usize];
-        for sect in &sections {
-            let mut body = &shstrtab.body[sect.header.sh_name as usize..];
-            let name = &body.read_cstr()?;
-            result.sections.insert(name.clone(), sect.clone());
-        }
-        result.shstrtab = Some(shstrtab.clone());
-
-        // TODO
-        // Read symbols.
-        let symtab = &result.find_section(".symtab").unwrap();
-        let mut cur_symtab = Cursor::new(&symtab.body);
-        let mut symbols = Vec::new();
-        for _ in 0..(symtab.header.sh_size / symtab.header.sh_entsize) {
-            let sym = Symbol::read(
-                &result.header.e_ident.ei_class,
-                &result.header.e_ident.ei_data,
-                &mut cur_symtab,
-            )?;
-            symbols.push(sym);
+        // Read section names. Objects without a section name table have unnamed sections.
+        let shstrtab = match result.header.e_shstrndx {
+            0 => None,
+            x => Some(
+                sections
+                    .get(x as usize)
+                    .ok_or_else(|| Error::MissingSection(".shstrtab".into()))?
+                    .clone(),
+            ),
+        };
+        for mut sect in sections {
+            if let Some(x) = &shstrtab {
+                sect.name = read_cstr_at(&x.body, sect.header.sh_name)?;
+            }
+            result.sections.push(sect);
         }
-        // Read symbol names.
-        let strtab = result.find_section(".strtab").unwrap().clone();
-        for sym in symbols {
-            let mut body = &strtab.body[sym.sym_name as usize..];
-            let name = &body.read_cstr()?;
+        result.shstrtab = shstrtab;
 
-            result.symbols.insert(name.clone(), sym.clone());
+        // Read symbols. Stripped objects don't have a symbol table.
+        let symtab = (0..result.sections.len())
+            .find(|&i| result.sections[i].header.sh_type == shtype::SHT_SYMTAB);
+        if let Some(idx) = symtab {
+            result.symbols = result.read_symbols(idx)?;
+            let link = result.sections[idx].header.sh_link as usize;
+            result.strtab = Some(result.sections[link].clone());
         }
 
-        result.strtab = Some(strtab);
-
         input.seek(SeekFrom::Start(0))?;
 
         // Finalize.
         result.update()?;
-        return Ok(result);
+        Ok(result)
     }
 
     pub fn write(&mut self, mut output: impl Write + Seek) -> Result<()> {
         self.update()?;
 
+        // Start with the original file so that padding and unknown data is preserved.
+        let mut image = Cursor::new(self.image.clone());
+
         // Write header.
-        self.header.write(&mut output)?;
+        self.header.write(&mut image)?;
 
-        // Write ELF body.
-        output.seek(SeekFrom::Start(self.header.e_phoff))?;
         // Write program headers.
+        image.seek(SeekFrom::Start(self.header.e_phoff))?;
         for seg in &self.segments {
             seg.header.write(
                 &self.header.e_ident.ei_class,
                 &self.header.e_ident.ei_data,
-                &mut output,
+                &mut image,
             )?;
         }
 
         // Write section bodies.
-        for (_, sect) in self.sections.iter() {
-            output.write(&sect.body)?;
+        for sect in self.sections.iter() {
+            if sect.file_range().1 == 0 {
+                continue;
+            }
+            image.seek(SeekFrom::Start(sect.header.sh_offset))?;
+            image.write_all(&sect.body)?;
         }
 
         // Write section headers.
-        output.seek(SeekFrom::Start(self.header.e_shoff))?;
-        self.sections.iter().for_each(|(_, sect)| {
-            _ = sect
-                .header
-                .write(
-                    &self.header.e_ident.ei_class,
-                    &self.header.e_ident.ei_data,
-                    &mut output,
-                )
-                .unwrap();
-        });
-
-        return Ok(());
+        image.seek(SeekFrom::Start(self.header.e_shoff))?;
+        for sect in self.sections.iter() {
+            sect.header.write(
+                &self.header.e_ident.ei_class,
+                &self.header.e_ident.ei_data,
+                &mut image,
+            )?;
+        }
+
+        output.write_all(image.get_ref())?;
+        Ok(())
+    }
+}
+
+/// Makes sure that `size` bytes starting at `offset` are within `limit`.
+fn check_range(offset: u64, size: u64, limit: u64) -> Result<()> {
+    match offset.checked_add(size) {
+        Some(end) if end <= limit => Ok(()),
+        _ => Err(Error::OutOfRange {
+            offset,
+            size,
+            limit,
+        }),
     }
 }
@@ -0,0 +1,636 @@
+use crate::{
+    error::{Error, Result},
+    object::{Header, Object},
+    section::{shtype, SectionFlags, SectionHeader},
+    segment::{ptype, ProgramHeader, Segment, SegmentFlags},
+    util::{align_to, overlaps},
+};
+
+/// A range may stay where it is as long as it doesn't collide with anything it didn't collide with before.
+fn collides(a: (u64, u64), old_a: (u64, u64), b: (u64, u64), old_b: (u64, u64)) -> bool {
+    overlaps(a, b) && !overlaps(old_a, old_b)
+}
+
+impl Object {
+    /// Returns the indices of all sections that are mapped by the given segment.
+    pub fn segment_sections(&self, segment: usize) -> Vec<usize> {
+        let seg = &self.segments[segment];
+        (0..self.sections.len())
+            .filter(|&i| seg.contains(&self.sections[i]))
+            .collect()
+    }
+
+    /// Assigns file offsets to the program header table, all sections and the section header table.
+    ///
+    /// Sections that are loaded by a segment keep their address. If they grow, the segment grows with them
+    /// and everything after it in the file is shifted by a multiple of the segment alignment,
+    /// so that `p_offset` and `p_vaddr` stay congruent.
+    pub(crate) fn update_layout(&mut self) -> Result<()> {
+        let class = self.header.e_ident.ei_class.clone();
+        if self.header.e_ehsize == 0 {
+            self.header.e_ehsize = Header::size(&class) as u16;
+        }
+        if self.header.e_phentsize == 0 && !self.segments.is_empty() {
+            self.header.e_phentsize = ProgramHeader::size(&class) as u16;
+        }
+        if self.header.e_shentsize == 0 && !self.sections.is_empty() {
+            self.header.e_shentsize = SectionHeader::size(&class) as u16;
+        }
+        let ehdr = (0, self.header.e_ehsize as u64);
+
+        // Update program header sizes + offsets.
+        let phentsize = self.header.e_phentsize as u64;
+        let mut old_phdr = (self.header.e_phoff, self.header.e_phnum as u64 * phentsize);
+        if self.header.e_phoff == 0 && !self.segments.is_empty() {
+            self.header.e_phoff = ehdr.1;
+        }
+        self.place_program_headers(old_phdr);
+
+        let shentsize = self.header.e_shentsize as u64;
+        let mut old_shdr = (self.header.e_shoff, self.header.e_shnum as u64 * shentsize);
+        self.header.e_shnum = self.sections.len() as u16;
+
+        // Map sections to segments before anything changes. A NOBITS section might have grown beyond its segment already.
+        let mapping: Vec<Vec<_>> = self
+            .segments
+            .iter()
+            .map(|seg| {
+                let x = &seg.header;
+                (0..self.sections.len())
+                    .filter(|&i| {
+                        let section = &self.sections[i];
+                        let (addr, size) = section.mem_range();
+                        seg.contains(section)
+                            || (x.p_type == ptype::PT_LOAD
+                                && section.header.sh_type == shtype::SHT_NOBITS
+                                && size != 0
+                                && addr >= x.p_vaddr
+                                && addr < x.p_vaddr + x.p_memsz)
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut old_mem: Vec<_> = self.sections.iter().map(|x| x.mem_range()).collect();
+        for (k, seg) in self.segments.iter().enumerate() {
+            let end = seg.header.p_vaddr + seg.header.p_memsz;
+            for &i in &mapping[k] {
+                if seg.header.p_type == ptype::PT_LOAD {
+                    old_mem[i].1 = old_mem[i].1.min(end - old_mem[i].0);
+                }
+            }
+        }
+
+        // Ranges occupied by the sections during the last layout.
+        // Sections without an offset haven't been placed yet.
+        let mut old: Vec<_> = self
+            .sections
+            .iter()
+            .map(|x| match x.header.sh_offset {
+                0 => (0, 0),
+                _ => x.file_range(),
+            })
+            .collect();
+        for section in &mut self.sections {
+            if section.header.sh_type != shtype::SHT_NOBITS {
+                section.header.sh_size = section.body.len() as u64;
+            }
+        }
+
+        // Sections in loadable segments have to stay where they are.
+        let mut fixed = vec![false; self.sections.len()];
+        let mut order: Vec<_> = (0..self.sections.len()).collect();
+        order.sort_by_key(|&i| old[i].0);
+        for i in order {
+            let Some(seg) = (0..self.segments.len()).find(|&k| {
+                self.segments[k].header.p_type == ptype::PT_LOAD && mapping[k].contains(&i)
+            }) else {
+                continue;
+            };
+            fixed[i] = true;
+
+            // Make sure the section doesn't grow into other sections or segments in memory.
+            let mem = self.sections[i].mem_range();
+            let mem_conflict = (0..self.sections.len())
+                .any(|j| j != i && collides(mem, old_mem[i], old_mem[j], old_mem[j]))
+                || (0..self.segments.len()).any(|k| {
+                    let x = &self.segments[k].header;
+                    let page = x.p_vaddr - x.p_vaddr % x.p_align.max(1);
+                    let range = (page, align_to(&(x.p_vaddr + x.p_memsz), &x.p_align) - page);
+                    x.p_type == ptype::PT_LOAD
+                        && k != seg
+                        && collides(mem, old_mem[i], range, range)
+                });
+            if mem_conflict {
+                return Err(Error::Layout(format!(
+                    "Section \"{}\" doesn't fit into its segment anymore",
+                    self.sections[i].name
+                )));
+            }
+
+            // Make room in the file if necessary.
+            let new = self.sections[i].file_range();
+            let mut at = u64::MAX;
+            let mut others = vec![ehdr, old_phdr, old_shdr];
+            others.extend(self.segments.iter().enumerate().filter_map(|(k, x)| {
+                (k != seg && x.header.p_type == ptype::PT_LOAD)
+                    .then_some((x.header.p_offset, x.header.p_filesz))
+            }));
+            others.extend((0..self.sections.len()).filter(|&j| j != i).map(|j| old[j]));
+            for x in others {
+                if collides(new, old[i], x, x) {
+                    at = at.min(x.0);
+                }
+            }
+            if at != u64::MAX {
+                if at < old[i].0 + old[i].1 {
+                    return Err(Error::Layout(format!(
+                        "Section \"{}\" overlaps other data in the file",
+                        self.sections[i].name
+                    )));
+                }
+                let align = self
+                    .segments
+                    .iter()
+                    .filter(|x| x.header.p_type == ptype::PT_LOAD)
+                    .map(|x| x.header.p_align)
+                    .max()
+                    .unwrap_or(1)
+                    .max(1);
+                let delta = align_to(&(new.0 + new.1 - at), &align);
+                self.shift_file(at, delta, &mut old, &mut old_phdr, &mut old_shdr);
+            }
+        }
+
+        // Grow segments to cover their sections.
+        for (k, seg) in self.segments.iter_mut().enumerate() {
+            let x = &mut seg.header;
+            for &i in &mapping[k] {
+                let (offset, size) = self.sections[i].file_range();
+                if size != 0 && offset >= x.p_offset {
+                    x.p_filesz = x.p_filesz.max(offset + size - x.p_offset);
+                }
+                let (addr, size) = self.sections[i].mem_range();
+                if addr >= x.p_vaddr {
+                    x.p_memsz = x.p_memsz.max(addr + size - x.p_vaddr);
+                }
+            }
+            x.p_memsz = x.p_memsz.max(x.p_filesz);
+        }
+
+        let phdr = (self.header.e_phoff, self.header.e_phnum as u64 * phentsize);
+        let new: Vec<_> = self.sections.iter().map(|x| x.file_range()).collect();
+        if (0..self.sections.len()).any(|i| fixed[i] && collides(phdr, old_phdr, new[i], old[i])) {
+            return Err(Error::Layout(
+                "The program header table doesn't fit in front of the first section".into(),
+            ));
+        }
+        let segs: Vec<_> = self
+            .segments
+            .iter()
+            .map(|x| (x.header.p_offset, x.header.p_filesz))
+            .collect();
+        // Sections stay in place if they don't overlap anything and their offset still matches their alignment,
+        // which changes e.g. when a section gets compressed.
+        let mut keep = vec![false; self.sections.len()];
+        for i in 0..self.sections.len() {
+            keep[i] = fixed[i]
+                || new[i].1 == 0
+                || (new[i].0 != 0
+                    && new[i].0 % self.sections[i].header.sh_addralign.max(1) == 0
+                    && !collides(new[i], old[i], ehdr, ehdr)
+                    && !collides(new[i], old[i], phdr, old_phdr)
+                    && segs.iter().all(|&x| !collides(new[i], old[i], x, x))
+                    && (0..self.sections.len())
+                        .all(|j| j == i || !collides(new[i], old[i], old[j], old[j]))
+                    && (0..i).all(|j| !keep[j] || !collides(new[i], old[i], new[j], old[j])));
+        }
+
+        let mut end = (self.image.len() as u64).max(ehdr.1).max(phdr.0 + phdr.1);
+        for (i, range) in new.iter().enumerate() {
+            if keep[i] {
+                end = end.max(range.0 + range.1);
+            }
+        }
+        // Segments without contents in the file, like PT_GNU_STACK, may have any offset.
+        for x in segs.iter().filter(|x| x.1 != 0) {
+            end = end.max(x.0 + x.1);
+        }
+
+        // Check if the section header table still fits.
+        let shdr = (self.header.e_shoff, self.header.e_shnum as u64 * shentsize);
+        let keep_shdr = shdr.0 != 0
+            && !collides(shdr, old_shdr, ehdr, ehdr)
+            && !collides(shdr, old_shdr, phdr, old_phdr)
+            && segs.iter().all(|&x| !collides(shdr, old_shdr, x, x))
+            && (0..self.sections.len())
+                .all(|i| !keep[i] || !collides(shdr, old_shdr, new[i], old[i]));
+        if keep_shdr {
+            end = end.max(shdr.0 + shdr.1);
+        }
+
+        // Move everything that doesn't fit anymore to the end of the file.
+        for (i, section) in self.sections.iter_mut().enumerate() {
+            if keep[i] {
+                continue;
+            }
+            end = align_to(&end, &section.header.sh_addralign);
+            section.header.sh_offset = end;
+            end += section.body.len() as u64;
+        }
+        if self.sections.is_empty() {
+            self.header.e_shoff = 0;
+        } else if !keep_shdr {
+            self.header.e_shoff = align_to(&end, &Header::word_size(&class));
+        }
+
+        // Update the PHDR element in the program header table.
+        for seg in &mut self.segments {
+            if seg.header.p_type == ptype::PT_PHDR {
+                seg.header.p_filesz = phdr.1;
+                seg.header.p_memsz = phdr.1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the contents of a section. A loaded section that grows usually doesn't fit into its place in memory
+    /// anymore, so it gets a new place like a new section would.
+    pub(crate) fn replace_body(&mut self, idx: usize, body: Vec<u8>) -> Result<()> {
+        let grows = body.len() > self.sections[idx].body.len();
+        self.sections[idx].body = body;
+        let h = &self.sections[idx].header;
+        if grows && h.flags().contains(SectionFlags::SHF_ALLOC) && !self.segments.is_empty() {
+            self.allocate_section(idx)?;
+        }
+        Ok(())
+    }
+
+    /// Finds a place in memory for a new loadable section. Uses the end of a loadable segment with the same
+    /// permissions if there is room, or a new loadable segment at the end of the file otherwise.
+    pub(crate) fn allocate_section(&mut self, idx: usize) -> Result<()> {
+        let h = &self.sections[idx].header;
+        let nobits = h.sh_type == shtype::SHT_NOBITS;
+        let size = match nobits {
+            true => h.sh_size,
+            false => self.sections[idx].body.len() as u64,
+        };
+        let mut flags = SegmentFlags::PF_R;
+        flags.set(
+            SegmentFlags::PF_W,
+            h.flags().contains(SectionFlags::SHF_WRITE),
+        );
+        flags.set(
+            SegmentFlags::PF_X,
+            h.flags().contains(SectionFlags::SHF_EXECINSTR),
+        );
+        let align = h.sh_addralign.max(1);
+
+        // Pages that are occupied by loadable segments.
+        let loads: Vec<_> = (0..self.segments.len())
+            .filter(|&k| self.segments[k].header.p_type == ptype::PT_LOAD)
+            .collect();
+        let pages: Vec<_> = loads
+            .iter()
+            .map(|&k| {
+                let x = &self.segments[k].header;
+                let page = x.p_vaddr - x.p_vaddr % x.p_align.max(1);
+                (
+                    k,
+                    (page, align_to(&(x.p_vaddr + x.p_memsz), &x.p_align) - page),
+                )
+            })
+            .collect();
+        let mut files: Vec<_> = self
+            .sections
+            .iter()
+            .enumerate()
+            .filter(|&(i, x)| i != idx && x.header.sh_offset != 0)
+            .map(|(_, x)| x.file_range())
+            .collect();
+        files.push((0, self.header.e_ehsize as u64));
+        files.push((
+            self.header.e_phoff,
+            self.header.e_phnum as u64 * self.header.e_phentsize as u64,
+        ));
+        files.push((
+            self.header.e_shoff,
+            self.header.e_shnum as u64 * self.header.e_shentsize as u64,
+        ));
+
+        // Try to append the section to an existing segment.
+        for &k in &loads {
+            let x = &self.segments[k].header;
+            let rwx = SegmentFlags::PF_R | SegmentFlags::PF_W | SegmentFlags::PF_X;
+            if x.flags() & rwx != flags || (!nobits && x.p_memsz != x.p_filesz) {
+                continue;
+            }
+            let addr = align_to(&(x.p_vaddr + x.p_memsz), &align);
+            let offset = x.p_offset + (addr - x.p_vaddr);
+            if pages
+                .iter()
+                .any(|&(j, range)| j != k && overlaps((addr, size.max(1)), range))
+            {
+                continue;
+            }
+            // Anything in the file that starts after the section is moved out of the way later.
+            let padding = (x.p_offset + x.p_filesz, offset - (x.p_offset + x.p_filesz));
+            if !nobits && files.iter().any(|&range| overlaps(padding, range)) {
+                continue;
+            }
+
+            let x = &mut self.segments[k].header;
+            x.p_memsz = addr + size - x.p_vaddr;
+            if !nobits {
+                x.p_filesz = x.p_memsz;
+            }
+            let h = &mut self.sections[idx].header;
+            h.sh_addr = addr;
+            h.sh_offset = offset;
+            if !nobits {
+                // The section grows into its place when the layout is updated.
+                h.sh_size = 0;
+            }
+            return Ok(());
+        }
+
+        // Create a new loadable segment at the end of the file.
+        let page = self.load_align().max(align);
+        let offset = align_to(&self.file_end(), &page);
+        let addr = align_to(&self.vaddr_end(), &page);
+        let h = &mut self.sections[idx].header;
+        h.sh_addr = addr;
+        h.sh_offset = offset;
+        h.sh_size = size;
+        self.insert_segment(Segment::new(ProgramHeader {
+            p_type: ptype::PT_LOAD,
+            p_flags: flags.bits(),
+            p_offset: offset,
+            p_vaddr: addr,
+            p_paddr: addr,
+            p_filesz: if nobits { 0 } else { size },
+            p_memsz: size,
+            p_align: page,
+        }));
+        Ok(())
+    }
+
+    /// Updates the size of the program header table and moves it if it doesn't fit in front of the sections anymore.
+    /// Returns the index of the segment that was added to map the table, if any.
+    pub(crate) fn place_program_headers(&mut self, old_phdr: (u64, u64)) -> Option<usize> {
+        self.header.e_phnum = self.segments.len() as u16;
+        let phdr = (
+            self.header.e_phoff,
+            self.header.e_phnum as u64 * self.header.e_phentsize as u64,
+        );
+        if self.sections.iter().any(|x| {
+            x.header.sh_offset != 0 && collides(phdr, old_phdr, x.file_range(), x.file_range())
+        }) {
+            return self.move_program_headers();
+        }
+        self.grow_program_header_segment(phdr)
+    }
+
+    /// A table that was moved before has a loadable segment of its own, which has to grow with it.
+    /// That only works as long as no loaded data was placed after it, otherwise the table is moved again.
+    fn grow_program_header_segment(&mut self, phdr: (u64, u64)) -> Option<usize> {
+        let k = (0..self.segments.len()).find(|&k| {
+            let x = &self.segments[k].header;
+            x.p_type == ptype::PT_LOAD && x.p_offset == phdr.0 && phdr.0 != 0 && x.p_filesz < phdr.1
+        })?;
+        let x = &self.segments[k].header;
+        let (offset, addr) = (x.p_offset, x.p_vaddr);
+        let after = self.segments.iter().enumerate().any(|(j, seg)| {
+            let y = &seg.header;
+            j != k && y.p_type == ptype::PT_LOAD && (y.p_offset > offset || y.p_vaddr > addr)
+        }) || self.sections.iter().any(|x| {
+            x.header.flags().contains(SectionFlags::SHF_ALLOC) && x.header.sh_offset > offset
+        });
+        if after {
+            return self.move_program_headers();
+        }
+        let x = &mut self.segments[k].header;
+        x.p_filesz = phdr.1;
+        x.p_memsz = phdr.1;
+        None
+    }
+
+    /// Moves the program header table to the end of the file. If the object has loadable segments,
+    /// the table gets mapped by a new one.
+    fn move_program_headers(&mut self) -> Option<usize> {
+        let phentsize = self.header.e_phentsize as u64;
+        let loads: Vec<_> = self
+            .segments
+            .iter()
+            .filter(|x| x.header.p_type == ptype::PT_LOAD)
+            .map(|x| x.header.clone())
+            .collect();
+        if loads.is_empty() {
+            let class = &self.header.e_ident.ei_class;
+            self.header.e_phoff = align_to(&self.file_end(), &Header::word_size(class));
+            return None;
+        }
+
+        // The kernel expects the program headers at the same distance between address and file offset
+        // as the first loadable segment.
+        let page = self.load_align();
+        let first = loads.iter().min_by_key(|x| x.p_vaddr).unwrap();
+        let bias = first.p_vaddr.wrapping_sub(first.p_offset);
+        let offset = align_to(&self.file_end(), &page)
+            .max(align_to(&self.vaddr_end(), &page).wrapping_sub(bias));
+        let addr = offset.wrapping_add(bias);
+        let size = (self.segments.len() as u64 + 1) * phentsize;
+        let idx = self.insert_segment(Segment::new(ProgramHeader {
+            p_type: ptype::PT_LOAD,
+            p_flags: SegmentFlags::PF_R.bits(),
+            p_offset: offset,
+            p_vaddr: addr,
+            p_paddr: addr,
+            p_filesz: size,
+            p_memsz: size,
+            p_align: page,
+        }));
+        for seg in &mut self.segments {
+            if seg.header.p_type == ptype::PT_PHDR {
+                seg.header.p_offset = offset;
+                seg.header.p_vaddr = addr;
+                seg.header.p_paddr = addr;
+            }
+        }
+        self.header.e_phoff = offset;
+        self.header.e_phnum = self.segments.len() as u16;
+        Some(idx)
+    }
+
+    /// Inserts a segment into the program header table and returns its index.
+    /// Loadable segments are kept in ascending order of their addresses.
+    pub(crate) fn insert_segment(&mut self, segment: Segment) -> usize {
+        let idx = match segment.header.p_type {
+            ptype::PT_LOAD => {
+                let loads: Vec<_> = (0..self.segments.len())
+                    .filter(|&k| self.segments[k].header.p_type == ptype::PT_LOAD)
+                    .collect();
+                match loads
+                    .iter()
+                    .rfind(|&&k| self.segments[k].header.p_vaddr <= segment.header.p_vaddr)
+                {
+                    Some(k) => k + 1,
+                    None => loads.first().copied().unwrap_or(self.segments.len()),
+                }
+            }
+            _ => self.segments.len(),
+        };
+        self.segments.insert(idx, segment);
+        idx
+    }
+
+    /// Returns the largest alignment of all loadable segments, or the usual page size if there are none.
+    pub(crate) fn load_align(&self) -> u64 {
+        self.segments
+            .iter()
+            .filter(|x| x.header.p_type == ptype::PT_LOAD)
+            .map(|x| x.header.p_align)
+            .max()
+            .unwrap_or(0x1000)
+            .max(1)
+    }
+
+    /// Returns the end of the highest page that is mapped by a loadable segment.
+    pub(crate) fn vaddr_end(&self) -> u64 {
+        self.segments
+            .iter()
+            .filter(|x| x.header.p_type == ptype::PT_LOAD)
+            .map(|x| align_to(&(x.header.p_vaddr + x.header.p_memsz), &x.header.p_align))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the end of all data in the file.
+    pub(crate) fn file_end(&self) -> u64 {
+        let mut end = (self.image.len() as u64).max(self.header.e_ehsize as u64);
+        end = end
+            .max(self.header.e_phoff + self.header.e_phnum as u64 * self.header.e_phentsize as u64);
+        end = end
+            .max(self.header.e_shoff + self.header.e_shnum as u64 * self.header.e_shentsize as u64);
+        for section in &self.sections {
+            if section.header.sh_offset != 0 {
+                let (offset, size) = section.file_range();
+                end = end.max(offset + size.max(section.body.len() as u64));
+            }
+        }
+        for seg in &self.segments {
+            end = end.max(seg.header.p_offset + seg.header.p_filesz);
+        }
+        end
+    }
+
+    /// Drops all data from the file that no header refers to anymore, e.g. the contents of removed sections.
+    ///
+    /// Segments shrink to the headers and section contents they still cover. Sections outside of segments are
+    /// packed right behind the segments, in the order they appear in the file, and the section header table
+    /// follows them.
+    pub(crate) fn pack(&mut self) {
+        let class = self.header.e_ident.ei_class.clone();
+        let ehdr = (0, Header::size(&class));
+        let phdr = (
+            self.header.e_phoff,
+            self.header.e_phnum as u64 * self.header.e_phentsize as u64,
+        );
+        let ranges: Vec<_> = self
+            .sections
+            .iter()
+            .map(|x| match x.header.sh_offset {
+                0 => (0, 0),
+                _ => x.file_range(),
+            })
+            .collect();
+        let inside = |range: (u64, u64), x: &ProgramHeader| {
+            range.1 != 0 && range.0 >= x.p_offset && range.0 + range.1 <= x.p_offset + x.p_filesz
+        };
+
+        // Sections with contents in a segment stay where they are.
+        let fixed: Vec<_> = (0..self.sections.len())
+            .map(|i| self.segments.iter().any(|x| inside(ranges[i], &x.header)))
+            .collect();
+        for seg in &mut self.segments {
+            let x = &mut seg.header;
+            let mut end = x.p_offset;
+            for range in [ehdr, phdr].iter().chain(&ranges) {
+                if inside(*range, x) {
+                    end = end.max(range.0 + range.1);
+                }
+            }
+            x.p_filesz = end - x.p_offset;
+        }
+
+        let mut live = vec![ehdr, phdr];
+        live.extend(
+            self.segments
+                .iter()
+                .map(|x| (x.header.p_offset, x.header.p_filesz)),
+        );
+        let mut end = live
+            .iter()
+            .filter(|x| x.1 != 0)
+            .map(|x| x.0 + x.1)
+            .max()
+            .unwrap_or(0);
+        let mut image = vec![0; end as usize];
+        for (offset, size) in live.into_iter().filter(|x| x.1 != 0) {
+            let start = (offset as usize).min(self.image.len());
+            let stop = ((offset + size) as usize).min(self.image.len());
+            image[start..stop].copy_from_slice(&self.image[start..stop]);
+        }
+        self.image = image;
+
+        // The null section doesn't have any contents.
+        let mut order: Vec<_> = (1..self.sections.len()).filter(|&i| !fixed[i]).collect();
+        order.sort_by_key(|&i| ranges[i].0);
+        for i in order {
+            let section = &mut self.sections[i];
+            let h = &mut section.header;
+            end = align_to(&end, &h.sh_addralign);
+            h.sh_offset = end;
+            if h.sh_type != shtype::SHT_NOBITS {
+                h.sh_size = section.body.len() as u64;
+                end += h.sh_size;
+            }
+        }
+        // The section header table is placed at the end when the layout is updated.
+        self.header.e_shoff = 0;
+    }
+
+    /// Inserts `delta` bytes at offset `at` of the file and moves everything after it.
+    fn shift_file(
+        &mut self,
+        at: u64,
+        delta: u64,
+        old: &mut [(u64, u64)],
+        old_phdr: &mut (u64, u64),
+        old_shdr: &mut (u64, u64),
+    ) {
+        if (at as usize) < self.image.len() {
+            self.image
+                .splice(at as usize..at as usize, vec![0u8; delta as usize]);
+        }
+        for (i, section) in self.sections.iter_mut().enumerate() {
+            if section.header.sh_offset >= at {
+                section.header.sh_offset += delta;
+                old[i].0 += delta;
+            }
+        }
+        for seg in &mut self.segments {
+            if seg.header.p_offset >= at {
+                seg.header.p_offset += delta;
+            }
+        }
+        if self.header.e_phoff >= at {
+            self.header.e_phoff += delta;
+            old_phdr.0 += delta;
+        }
+        if self.header.e_shoff >= at {
+            self.header.e_shoff += delta;
+            old_shdr.0 += delta;
+        }
+    }
+}
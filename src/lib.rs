@@ -1,10 +1,24 @@
+mod error;
+mod layout;
 mod util;
 
+pub mod build_id;
+pub mod compression;
+pub mod debuglink;
+pub mod dynamic;
+pub mod group;
+pub mod hash;
 pub mod io;
+pub mod note;
 pub mod object;
+pub mod relocation;
 pub mod section;
 pub mod segment;
+pub mod strip;
 pub mod symbol;
+pub mod version;
+
+pub use error::{Error, Result};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,557 @@
+use std::io::Write;
+
+use bitflags::bitflags;
+
+use crate::{
+    error::{Error, Result},
+    object::{Endianness, Header, Machine, Object},
+    section::{shtype, SectionFlags, SectionHeader, SectionId},
+    segment::{ptype, ProgramHeader, Segment, SegmentFlags},
+    util::{align_to, ReadExt, WriteExt},
+};
+
+/// Names of the note owners that zehn knows about.
+pub mod owner {
+    pub const GNU: &str = "GNU";
+    pub const FREEBSD: &str = "FreeBSD";
+    pub const NETBSD: &str = "NetBSD";
+    pub const PAX: &str = "PaX";
+    pub const GO: &str = "Go";
+    /// Used by `.note.package` notes, see <https://systemd.io/ELF_PACKAGE_METADATA/>.
+    pub const FDO: &str = "FDO";
+}
+
+/// Note types, stored in `n_type`. Their meaning depends on the owner of the note.
+pub mod ntype {
+    /// Operating system and ABI version, owner `GNU`.
+    pub const NT_GNU_ABI_TAG: u32 = 1;
+    /// Synthetic hardware capabilities, owner `GNU`.
+    pub const NT_GNU_HWCAP: u32 = 2;
+    /// Unique build ID, owner `GNU`.
+    pub const NT_GNU_BUILD_ID: u32 = 3;
+    /// Version of the gold linker, owner `GNU`.
+    pub const NT_GNU_GOLD_VERSION: u32 = 4;
+    /// Program properties, owner `GNU`.
+    pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+    /// Value of `__FreeBSD_version`, owner `FreeBSD`.
+    pub const NT_FREEBSD_ABI_TAG: u32 = 1;
+    /// The object doesn't need to run `_init`, owner `FreeBSD`.
+    pub const NT_FREEBSD_NOINIT_TAG: u32 = 2;
+    /// Name of the target architecture, owner `FreeBSD`.
+    pub const NT_FREEBSD_ARCH_TAG: u32 = 3;
+    /// Security features to turn off, owner `FreeBSD`.
+    pub const NT_FREEBSD_FEATURE_CTL: u32 = 4;
+
+    /// Value of `__NetBSD_Version__`, owner `NetBSD`.
+    pub const NT_NETBSD_IDENT: u32 = 1;
+    /// Name of the target machine architecture, owner `NetBSD`.
+    pub const NT_NETBSD_MARCH: u32 = 5;
+    /// PaX security flags, owner `PaX`.
+    pub const NT_NETBSD_PAX: u32 = 3;
+
+    /// Build ID of the Go toolchain, owner `Go`.
+    pub const NT_GO_BUILD_ID: u32 = 4;
+
+    /// Package metadata as JSON, owner `FDO`.
+    pub const NT_FDO_PACKAGING_METADATA: u32 = 0xCAFE1A7E;
+}
+
+/// Property types of `NT_GNU_PROPERTY_TYPE_0` notes.
+pub mod prtype {
+    pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+    pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+    pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xC0000000;
+    pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xC0000002;
+    pub const GNU_PROPERTY_X86_ISA_1_NEEDED: u32 = 0xC0008002;
+    pub const GNU_PROPERTY_X86_FEATURE_2_NEEDED: u32 = 0xC0008001;
+    pub const GNU_PROPERTY_X86_FEATURE_2_USED: u32 = 0xC0010001;
+    pub const GNU_PROPERTY_X86_ISA_1_USED: u32 = 0xC0010002;
+}
+
+/// Operating systems in `NT_GNU_ABI_TAG` notes.
+pub mod abi_os {
+    pub const ELF_NOTE_OS_LINUX: u32 = 0;
+    pub const ELF_NOTE_OS_GNU: u32 = 1;
+    pub const ELF_NOTE_OS_SOLARIS2: u32 = 2;
+    pub const ELF_NOTE_OS_FREEBSD: u32 = 3;
+}
+
+bitflags! {
+    /// Control flow protection features of x86 objects.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct X86Feature1: u32 {
+        /// Indirect branch tracking
+        const IBT = 0x1;
+        /// Shadow stack
+        const SHSTK = 0x2;
+
+        const _ = !0;
+    }
+}
+
+bitflags! {
+    /// Micro-architecture levels of x86-64.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct X86Isa1: u32 {
+        const BASELINE = 0x1;
+        const V2 = 0x2;
+        const V3 = 0x4;
+        const V4 = 0x8;
+
+        const _ = !0;
+    }
+}
+
+bitflags! {
+    /// Control flow protection features of AArch64 objects.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct Aarch64Feature1: u32 {
+        /// Branch target identification
+        const BTI = 0x1;
+        /// Pointer authentication
+        const PAC = 0x2;
+        /// Guarded control stack
+        const GCS = 0x4;
+
+        const _ = !0;
+    }
+}
+
+/// A property of a `NT_GNU_PROPERTY_TYPE_0` note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GnuProperty {
+    StackSize(u64),
+    NoCopyOnProtected,
+    X86Feature1And(X86Feature1),
+    X86Feature2Needed(u32),
+    X86Feature2Used(u32),
+    X86IsaNeeded(X86Isa1),
+    X86IsaUsed(X86Isa1),
+    Aarch64Feature1And(Aarch64Feature1),
+    /// Any other property, with its raw data
+    Other {
+        pr_type: u32,
+        data: Vec<u8>,
+    },
+}
+
+/// The decoded description of a note of a known owner and type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownNote {
+    GnuAbiTag {
+        /// One of the `abi_os` constants
+        os: u32,
+        major: u32,
+        minor: u32,
+        patch: u32,
+    },
+    GnuBuildId(Vec<u8>),
+    GnuProperties(Vec<GnuProperty>),
+    GnuGoldVersion(String),
+    FreeBsdAbiTag(u32),
+    FreeBsdNoInit,
+    FreeBsdArch(String),
+    FreeBsdFeatureCtl(u32),
+    NetBsdIdent(u32),
+    NetBsdMarch(String),
+    NetBsdPax(u32),
+    GoBuildId(String),
+    /// The JSON metadata of a `.note.package` note
+    Package(String),
+    Unknown,
+}
+
+/// A single entry of a `SHT_NOTE` section or `PT_NOTE` segment.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Note {
+    /// The owner of the note, without the terminating null byte.
+    pub name: String,
+    pub n_type: u32,
+    /// The description, whose format depends on the owner and the type.
+    pub desc: Vec<u8>,
+}
+
+impl Note {
+    pub fn new(name: &str, n_type: u32, desc: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            n_type,
+            desc,
+        }
+    }
+
+    /// Returns the number of bytes that the note takes up with the given alignment.
+    pub fn size(&self, align: u64) -> u64 {
+        let namesz = self.name.len() as u64 + 1;
+        align_to(
+            &(align_to(&(12 + namesz), &align) + self.desc.len() as u64),
+            &align,
+        )
+    }
+
+    /// Reads a note at the start of `data`, whose name and description are padded to `align`, which is 4 or 8.
+    /// Returns the note and the number of bytes it takes up.
+    pub fn read(endian: &Endianness, data: &[u8], align: u64) -> Result<(Self, usize)> {
+        let mut buf = data;
+        let mut read = || buf.read_u32(endian).map_err(|e| e.truncated(0));
+        let namesz = read()? as u64;
+        let descsz = read()? as u64;
+        let n_type = read()?;
+        let desc_start = align_to(&(12 + namesz), &align);
+        let end = desc_start + descsz;
+        if end > data.len() as u64 {
+            return Err(Error::OutOfRange {
+                offset: 0,
+                size: end,
+                limit: data.len() as u64,
+            });
+        }
+
+        let name = &data[12..12 + namesz as usize];
+        let name = match name.iter().position(|&x| x == 0) {
+            Some(pos) => &name[..pos],
+            None => name,
+        };
+        let note = Self {
+            name: String::from_utf8(name.to_vec()).map_err(Error::InvalidUtf8)?,
+            n_type,
+            desc: data[desc_start as usize..end as usize].to_vec(),
+        };
+        let size = align_to(&end, &align).min(data.len() as u64);
+        Ok((note, size as usize))
+    }
+
+    pub fn write(&self, endian: &Endianness, align: u64, mut buf: impl Write) -> Result<usize> {
+        let namesz = self.name.len() as u64 + 1;
+        let mut data = Vec::new();
+        data.write_u32(endian, namesz as u32)?;
+        data.write_u32(endian, self.desc.len() as u32)?;
+        data.write_u32(endian, self.n_type)?;
+        data.extend_from_slice(self.name.as_bytes());
+        data.resize(align_to(&(12 + namesz), &align) as usize, 0);
+        data.extend_from_slice(&self.desc);
+        data.resize(self.size(align) as usize, 0);
+        buf.write_all(&data)?;
+        Ok(data.len())
+    }
+
+    /// Decodes the description of notes that zehn knows about. Some properties depend on the architecture,
+    /// so the header of the object the note belongs to is needed.
+    pub fn decode(&self, header: &Header) -> Result<KnownNote> {
+        let endian = &header.e_ident.ei_data;
+        let word = |x: &[u8]| (&x[..]).read_u32(endian).map_err(|e| e.truncated(0));
+        let string = || {
+            let end = self.desc.iter().position(|&x| x == 0);
+            String::from_utf8(self.desc[..end.unwrap_or(self.desc.len())].to_vec())
+                .map_err(Error::InvalidUtf8)
+        };
+        let note = match (self.name.as_str(), self.n_type) {
+            (owner::GNU, ntype::NT_GNU_ABI_TAG) => {
+                let mut buf = &self.desc[..];
+                let mut read = || buf.read_u32(endian).map_err(|e| e.truncated(0));
+                KnownNote::GnuAbiTag {
+                    os: read()?,
+                    major: read()?,
+                    minor: read()?,
+                    patch: read()?,
+                }
+            }
+            (owner::GNU, ntype::NT_GNU_BUILD_ID) => KnownNote::GnuBuildId(self.desc.clone()),
+            (owner::GNU, ntype::NT_GNU_PROPERTY_TYPE_0) => {
+                KnownNote::GnuProperties(self.properties(header)?)
+            }
+            (owner::GNU, ntype::NT_GNU_GOLD_VERSION) => KnownNote::GnuGoldVersion(string()?),
+            (owner::FREEBSD, ntype::NT_FREEBSD_ABI_TAG) => {
+                KnownNote::FreeBsdAbiTag(word(&self.desc)?)
+            }
+            (owner::FREEBSD, ntype::NT_FREEBSD_NOINIT_TAG) => KnownNote::FreeBsdNoInit,
+            (owner::FREEBSD, ntype::NT_FREEBSD_ARCH_TAG) => KnownNote::FreeBsdArch(string()?),
+            (owner::FREEBSD, ntype::NT_FREEBSD_FEATURE_CTL) => {
+                KnownNote::FreeBsdFeatureCtl(word(&self.desc)?)
+            }
+            (owner::NETBSD, ntype::NT_NETBSD_IDENT) => KnownNote::NetBsdIdent(word(&self.desc)?),
+            (owner::NETBSD, ntype::NT_NETBSD_MARCH) => KnownNote::NetBsdMarch(string()?),
+            (owner::PAX, ntype::NT_NETBSD_PAX) => KnownNote::NetBsdPax(word(&self.desc)?),
+            (owner::GO, ntype::NT_GO_BUILD_ID) => KnownNote::GoBuildId(string()?),
+            (owner::FDO, ntype::NT_FDO_PACKAGING_METADATA) => KnownNote::Package(string()?),
+            _ => KnownNote::Unknown,
+        };
+        Ok(note)
+    }
+
+    /// Decodes the properties of a `NT_GNU_PROPERTY_TYPE_0` note. They are aligned to the size of an address.
+    fn properties(&self, header: &Header) -> Result<Vec<GnuProperty>> {
+        let endian = &header.e_ident.ei_data;
+        let align = Header::word_size(&header.e_ident.ei_class);
+        let machine = header.machine();
+        let x86 = matches!(machine, Machine::X86 | Machine::X86_64);
+
+        let mut result = Vec::new();
+        let mut pos = 0;
+        while pos < self.desc.len() {
+            let mut buf = &self.desc[pos..];
+            let pr_type = buf.read_u32(endian).map_err(|e| e.truncated(pos as u64))?;
+            let size = buf.read_u32(endian).map_err(|e| e.truncated(pos as u64))? as usize;
+            let data = buf.get(..size).ok_or(Error::TruncatedHeader {
+                offset: self.desc.len() as u64,
+            })?;
+            let word = || {
+                (&data[..])
+                    .read_u32(endian)
+                    .map_err(|e| e.truncated(pos as u64))
+            };
+            let property = match pr_type {
+                prtype::GNU_PROPERTY_STACK_SIZE => GnuProperty::StackSize(match size {
+                    4 => word()? as u64,
+                    _ => (&data[..])
+                        .read_u64(endian)
+                        .map_err(|e| e.truncated(pos as u64))?,
+                }),
+                prtype::GNU_PROPERTY_NO_COPY_ON_PROTECTED => GnuProperty::NoCopyOnProtected,
+                prtype::GNU_PROPERTY_X86_FEATURE_1_AND if x86 => {
+                    GnuProperty::X86Feature1And(X86Feature1::from_bits_retain(word()?))
+                }
+                prtype::GNU_PROPERTY_X86_FEATURE_2_NEEDED if x86 => {
+                    GnuProperty::X86Feature2Needed(word()?)
+                }
+                prtype::GNU_PROPERTY_X86_FEATURE_2_USED if x86 => {
+                    GnuProperty::X86Feature2Used(word()?)
+                }
+                prtype::GNU_PROPERTY_X86_ISA_1_NEEDED if x86 => {
+                    GnuProperty::X86IsaNeeded(X86Isa1::from_bits_retain(word()?))
+                }
+                prtype::GNU_PROPERTY_X86_ISA_1_USED if x86 => {
+                    GnuProperty::X86IsaUsed(X86Isa1::from_bits_retain(word()?))
+                }
+                prtype::GNU_PROPERTY_AARCH64_FEATURE_1_AND if machine == Machine::AArch64 => {
+                    GnuProperty::Aarch64Feature1And(Aarch64Feature1::from_bits_retain(word()?))
+                }
+                _ => GnuProperty::Other {
+                    pr_type,
+                    data: data.to_vec(),
+                },
+            };
+            result.push(property);
+            pos += align_to(&(8 + size as u64), &align) as usize;
+        }
+        Ok(result)
+    }
+}
+
+/// Iterates over the notes in the contents of a note section or segment.
+pub struct NoteIter<'a> {
+    data: &'a [u8],
+    endian: Endianness,
+    align: u64,
+}
+
+impl<'a> NoteIter<'a> {
+    /// Notes are padded to 4 bytes, unless the section or segment is aligned to 8 bytes.
+    pub fn new(data: &'a [u8], endian: &Endianness, align: u64) -> Self {
+        Self {
+            data,
+            endian: endian.clone(),
+            align: if align == 8 { 8 } else { 4 },
+        }
+    }
+}
+
+impl Iterator for NoteIter<'_> {
+    type Item = Result<Note>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        match Note::read(&self.endian, self.data, self.align) {
+            Ok((note, size)) => {
+                self.data = &self.data[size..];
+                Some(Ok(note))
+            }
+            // The rest of the data can't be decoded either.
+            Err(e) => {
+                self.data = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl Object {
+    /// Reads the notes of a note section, given by index or name.
+    pub fn section_notes(&self, section: impl SectionId) -> Result<Vec<Note>> {
+        let idx = section.section_idx(self)?;
+        let section = &self.sections[idx];
+        if section.header.sh_type != shtype::SHT_NOTE {
+            return Err(Error::WrongSectionType {
+                name: section.name.clone(),
+                sh_type: section.header.sh_type,
+            });
+        }
+        let endian = &self.header.e_ident.ei_data;
+        NoteIter::new(&section.body, endian, section.header.sh_addralign).collect()
+    }
+
+    /// Reads the notes of all note sections. Falls back to the `PT_NOTE` segments if the object has no
+    /// note sections, e.g. because its section headers were removed.
+    pub fn notes(&self) -> Result<Vec<Note>> {
+        let endian = &self.header.e_ident.ei_data;
+        let mut result = Vec::new();
+        for section in self.get_sections(shtype::SHT_NOTE) {
+            for note in NoteIter::new(&section.body, endian, section.header.sh_addralign) {
+                result.push(note?);
+            }
+        }
+        if !result.is_empty() {
+            return Ok(result);
+        }
+
+        for segment in &self.segments {
+            let h = &segment.header;
+            if h.p_type != ptype::PT_NOTE {
+                continue;
+            }
+            let start = usize::try_from(h.p_offset).ok();
+            let end = h
+                .p_offset
+                .checked_add(h.p_filesz)
+                .and_then(|x| usize::try_from(x).ok());
+            let data = start
+                .zip(end)
+                .and_then(|(start, end)| self.image.get(start..end))
+                .ok_or(Error::OutOfRange {
+                    offset: h.p_offset,
+                    size: h.p_filesz,
+                    limit: self.image.len() as u64,
+                })?;
+            for note in NoteIter::new(data, endian, h.p_align) {
+                result.push(note?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Appends a note to a note section and returns the index of the section.
+    ///
+    /// If there is no section with that name, a new loaded note section is created, together with a `PT_NOTE`
+    /// segment if the object has segments.
+    pub fn add_note(&mut self, section: &str, note: &Note) -> Result<usize> {
+        let endian = self.header.e_ident.ei_data.clone();
+        let Some(idx) = self.find_section_idx(section).map(|x| x as usize) else {
+            let header = SectionHeader {
+                sh_type: shtype::SHT_NOTE,
+                sh_flags: SectionFlags::SHF_ALLOC.bits(),
+                sh_addralign: 4,
+                ..Default::default()
+            };
+            let mut body = Vec::new();
+            note.write(&endian, 4, &mut body)?;
+            let idx = self.add_section(section, header, body)?;
+            if !self.segments.is_empty() {
+                self.add_note_segment(idx);
+            }
+            return Ok(idx);
+        };
+
+        let h = &self.sections[idx].header;
+        if h.sh_type != shtype::SHT_NOTE {
+            return Err(Error::WrongSectionType {
+                name: self.sections[idx].name.clone(),
+                sh_type: h.sh_type,
+            });
+        }
+        let align = if h.sh_addralign == 8 { 8 } else { 4 };
+        let mut body = self.sections[idx].body.clone();
+        // The last note might not be padded.
+        body.resize(align_to(&(body.len() as u64), &align) as usize, 0);
+        note.write(&endian, align, &mut body)?;
+        self.replace_note_body(idx, body)?;
+        Ok(idx)
+    }
+
+    /// Replaces the contents of a note section. A loaded section that grows is moved, in which case it gets a
+    /// `PT_NOTE` segment of its own and the segments that covered it shrink to the remaining sections.
+    pub(crate) fn replace_note_body(&mut self, idx: usize, body: Vec<u8>) -> Result<()> {
+        let old_addr = self.sections[idx].header.sh_addr;
+        let covering: Vec<_> = (0..self.segments.len())
+            .filter(|&k| {
+                let x = &self.segments[k];
+                x.header.p_type == ptype::PT_NOTE && x.contains(&self.sections[idx])
+            })
+            .map(|k| {
+                let others: Vec<_> = self
+                    .segment_sections(k)
+                    .into_iter()
+                    .filter(|&i| i != idx)
+                    .collect();
+                (k, others)
+            })
+            .collect();
+
+        self.replace_body(idx, body)?;
+        if self.sections[idx].header.sh_addr == old_addr || covering.is_empty() {
+            return Ok(());
+        }
+
+        let mut moved = false;
+        for (k, others) in covering {
+            if others.is_empty() {
+                self.move_note_segment(k, idx);
+                moved = true;
+                continue;
+            }
+            let h = |i: usize| &self.sections[i].header;
+            let start = others
+                .iter()
+                .copied()
+                .min_by_key(|&i| h(i).sh_addr)
+                .unwrap();
+            let end = others
+                .iter()
+                .map(|&i| self.sections[i].mem_range())
+                .map(|(addr, size)| addr + size)
+                .max()
+                .unwrap();
+            let (offset, addr) = (h(start).sh_offset, h(start).sh_addr);
+            let x = &mut self.segments[k].header;
+            x.p_offset = offset;
+            x.p_vaddr = addr;
+            x.p_paddr = addr;
+            x.p_filesz = end - addr;
+            x.p_memsz = end - addr;
+        }
+        if !moved {
+            self.add_note_segment(idx);
+        }
+        Ok(())
+    }
+
+    /// Adds a `PT_NOTE` segment that covers a loaded note section.
+    fn add_note_segment(&mut self, idx: usize) {
+        let old_phdr = (
+            self.header.e_phoff,
+            self.header.e_phnum as u64 * self.header.e_phentsize as u64,
+        );
+        let k = self.insert_segment(Segment::new(ProgramHeader {
+            p_type: ptype::PT_NOTE,
+            p_flags: SegmentFlags::PF_R.bits(),
+            p_align: self.sections[idx].header.sh_addralign,
+            ..Default::default()
+        }));
+        self.move_note_segment(k, idx);
+        self.place_program_headers(old_phdr);
+    }
+
+    /// Points a `PT_NOTE` segment to a section. The file size is filled in when the layout is updated,
+    /// since the section might not have its final size yet.
+    fn move_note_segment(&mut self, segment: usize, idx: usize) {
+        let h = &self.sections[idx].header;
+        let x = &mut self.segments[segment].header;
+        x.p_offset = h.sh_offset;
+        x.p_vaddr = h.sh_addr;
+        x.p_paddr = h.sh_addr;
+        x.p_filesz = 0;
+        x.p_memsz = self.sections[idx].body.len() as u64;
+    }
+}
@@ -1,10 +1,11 @@
 use indexmap::IndexMap;
 
 use crate::{
-    section::Section,
-    segment::Segment,
-    symbol::Symbol,
-    util::{align_to, Result, WriteExt},
+    error::{Error, Result},
+    section::{shn, shtype, Section, SectionFlags, SectionHeader, SectionId},
+    segment::{ptype, ProgramHeader, Segment},
+    symbol::{Symbol, SymbolBinding},
+    util::{align_to, insert_cstr, read_cstr_at, ReadExt, WriteExt},
 };
 
 #[derive(Debug, Clone, Default)]
@@ -21,6 +22,249 @@ pub enum Endianness {
     Big = 2,
 }
 
+/// The type of an object, stored in `e_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectType {
+    /// No file type
+    None,
+    /// Relocatable file
+    Rel,
+    /// Executable file
+    Exec,
+    /// Shared object or position independent executable
+    Dyn,
+    /// Core file
+    Core,
+    /// Operating system specific, between `0xFE00` and `0xFEFF`
+    Os(u16),
+    /// Processor specific, between `0xFF00` and `0xFFFF`
+    Processor(u16),
+    /// Any other value
+    Unknown(u16),
+}
+
+impl From<u16> for ObjectType {
+    fn from(value: u16) -> Self {
+        match value {
+            0 => ObjectType::None,
+            1 => ObjectType::Rel,
+            2 => ObjectType::Exec,
+            3 => ObjectType::Dyn,
+            4 => ObjectType::Core,
+            0xFE00..=0xFEFF => ObjectType::Os(value),
+            0xFF00..=0xFFFF => ObjectType::Processor(value),
+            x => ObjectType::Unknown(x),
+        }
+    }
+}
+
+impl From<ObjectType> for u16 {
+    fn from(value: ObjectType) -> Self {
+        match value {
+            ObjectType::None => 0,
+            ObjectType::Rel => 1,
+            ObjectType::Exec => 2,
+            ObjectType::Dyn => 3,
+            ObjectType::Core => 4,
+            ObjectType::Os(x) | ObjectType::Processor(x) | ObjectType::Unknown(x) => x,
+        }
+    }
+}
+
+/// The target architecture of an object, stored in `e_machine`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    /// No machine
+    None,
+    /// SPARC
+    Sparc,
+    /// Intel 80386
+    X86,
+    /// Motorola 68000
+    M68k,
+    /// MIPS
+    Mips,
+    /// PowerPC
+    Ppc,
+    /// 64-bit PowerPC
+    Ppc64,
+    /// IBM S/390 and z/Architecture
+    S390,
+    /// 32-bit ARM
+    Arm,
+    /// SPARC V9
+    SparcV9,
+    /// Intel Itanium
+    Ia64,
+    /// AMD x86-64
+    X86_64,
+    /// 64-bit ARM
+    AArch64,
+    /// RISC-V
+    RiscV,
+    /// Linux BPF
+    Bpf,
+    /// LoongArch
+    LoongArch,
+    /// Any other value
+    Unknown(u16),
+}
+
+impl From<u16> for Machine {
+    fn from(value: u16) -> Self {
+        match value {
+            0 => Machine::None,
+            2 => Machine::Sparc,
+            3 => Machine::X86,
+            4 => Machine::M68k,
+            8 => Machine::Mips,
+            20 => Machine::Ppc,
+            21 => Machine::Ppc64,
+            22 => Machine::S390,
+            40 => Machine::Arm,
+            43 => Machine::SparcV9,
+            50 => Machine::Ia64,
+            62 => Machine::X86_64,
+            183 => Machine::AArch64,
+            243 => Machine::RiscV,
+            247 => Machine::Bpf,
+            258 => Machine::LoongArch,
+            x => Machine::Unknown(x),
+        }
+    }
+}
+
+impl From<Machine> for u16 {
+    fn from(value: Machine) -> Self {
+        match value {
+            Machine::None => 0,
+            Machine::Sparc => 2,
+            Machine::X86 => 3,
+            Machine::M68k => 4,
+            Machine::Mips => 8,
+            Machine::Ppc => 20,
+            Machine::Ppc64 => 21,
+            Machine::S390 => 22,
+            Machine::Arm => 40,
+            Machine::SparcV9 => 43,
+            Machine::Ia64 => 50,
+            Machine::X86_64 => 62,
+            Machine::AArch64 => 183,
+            Machine::RiscV => 243,
+            Machine::Bpf => 247,
+            Machine::LoongArch => 258,
+            Machine::Unknown(x) => x,
+        }
+    }
+}
+
+/// The operating system and ABI an object targets, stored in `EI_OSABI`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OsAbi {
+    /// UNIX System V, also used by most Linux objects
+    SysV,
+    /// HP-UX
+    HpUx,
+    /// NetBSD
+    NetBsd,
+    /// GNU/Linux, used by objects with GNU extensions
+    Gnu,
+    /// GNU Hurd
+    Hurd,
+    /// Solaris
+    Solaris,
+    /// IBM AIX
+    Aix,
+    /// SGI Irix
+    Irix,
+    /// FreeBSD
+    FreeBsd,
+    /// Compaq TRU64 UNIX
+    Tru64,
+    /// Novell Modesto
+    Modesto,
+    /// OpenBSD
+    OpenBsd,
+    /// OpenVMS
+    OpenVms,
+    /// HP Non-Stop Kernel
+    Nsk,
+    /// Amiga Research OS
+    Aros,
+    /// FenixOS
+    FenixOs,
+    /// Nuxi CloudABI
+    CloudAbi,
+    /// Stratus OpenVOS
+    OpenVos,
+    /// ARM EABI
+    ArmAeabi,
+    /// ARM
+    Arm,
+    /// Standalone (embedded) application
+    Standalone,
+    /// Any other value
+    Unknown(u8),
+}
+
+impl From<u8> for OsAbi {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => OsAbi::SysV,
+            1 => OsAbi::HpUx,
+            2 => OsAbi::NetBsd,
+            3 => OsAbi::Gnu,
+            4 => OsAbi::Hurd,
+            6 => OsAbi::Solaris,
+            7 => OsAbi::Aix,
+            8 => OsAbi::Irix,
+            9 => OsAbi::FreeBsd,
+            10 => OsAbi::Tru64,
+            11 => OsAbi::Modesto,
+            12 => OsAbi::OpenBsd,
+            13 => OsAbi::OpenVms,
+            14 => OsAbi::Nsk,
+            15 => OsAbi::Aros,
+            16 => OsAbi::FenixOs,
+            17 => OsAbi::CloudAbi,
+            18 => OsAbi::OpenVos,
+            64 => OsAbi::ArmAeabi,
+            97 => OsAbi::Arm,
+            255 => OsAbi::Standalone,
+            x => OsAbi::Unknown(x),
+        }
+    }
+}
+
+impl From<OsAbi> for u8 {
+    fn from(value: OsAbi) -> Self {
+        match value {
+            OsAbi::SysV => 0,
+            OsAbi::HpUx => 1,
+            OsAbi::NetBsd => 2,
+            OsAbi::Gnu => 3,
+            OsAbi::Hurd => 4,
+            OsAbi::Solaris => 6,
+            OsAbi::Aix => 7,
+            OsAbi::Irix => 8,
+            OsAbi::FreeBsd => 9,
+            OsAbi::Tru64 => 10,
+            OsAbi::Modesto => 11,
+            OsAbi::OpenBsd => 12,
+            OsAbi::OpenVms => 13,
+            OsAbi::Nsk => 14,
+            OsAbi::Aros => 15,
+            OsAbi::FenixOs => 16,
+            OsAbi::CloudAbi => 17,
+            OsAbi::OpenVos => 18,
+            OsAbi::ArmAeabi => 64,
+            OsAbi::Arm => 97,
+            OsAbi::Standalone => 255,
+            OsAbi::Unknown(x) => x,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Ident {
     pub ei_magic: [u8; 4],
@@ -32,6 +276,13 @@ pub struct Ident {
     pub ei_pad: [u8; 7],
 }
 
+impl Ident {
+    /// Returns the operating system ABI of the object.
+    pub fn osabi(&self) -> OsAbi {
+        self.ei_osabi.into()
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Header {
     pub e_ident: Ident,
@@ -50,15 +301,52 @@ pub struct Header {
     pub e_shstrndx: u16,
 }
 
+impl Header {
+    /// Returns the type of the object.
+    pub fn object_type(&self) -> ObjectType {
+        self.e_type.into()
+    }
+
+    /// Returns the target architecture of the object.
+    pub fn machine(&self) -> Machine {
+        self.e_machine.into()
+    }
+
+    /// Returns the size of the ELF header in bytes.
+    pub fn size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 52,
+            Class::Bits64 => 64,
+        }
+    }
+
+    /// Returns the size of an address in bytes.
+    pub fn word_size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 4,
+            Class::Bits64 => 8,
+        }
+    }
+}
+
 /// A simplified ELF representation.
 #[derive(Debug, Clone)]
 pub struct Object {
     pub header: Header,
     pub segments: Vec<Segment>,
-    pub sections: IndexMap<String, Section>,
-    pub symbols: IndexMap<String, Symbol>,
+    pub sections: Vec<Section>,
+    /// All symbols of the symbol table, in table order.
+    pub symbols: Vec<Symbol>,
     pub shstrtab: Option<Section>,
     pub strtab: Option<Section>,
+    /// The original file contents. Anything not covered by headers or sections is written back from here.
+    pub(crate) image: Vec<u8>,
+}
+
+impl Default for Object {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Object {
@@ -66,17 +354,17 @@ impl Object {
         Self {
             header: Header::default(),
             segments: Vec::new(),
-            sections: IndexMap::new(),
-            symbols: IndexMap::new(),
+            sections: Vec::new(),
+            symbols: Vec::new(),
             shstrtab: None,
             strtab: None,
+            image: Vec::new(),
         }
     }
 
     pub fn get_sections(&self, sh_type: u32) -> Vec<&Section> {
         self.sections
             .iter()
-            .map(|(_, x)| x)
             .filter(|x| x.header.sh_type == sh_type)
             .collect()
     }
@@ -84,121 +372,392 @@ impl Object {
     pub fn get_sections_mut(&mut self, sh_type: u32) -> Vec<&mut Section> {
         self.sections
             .iter_mut()
-            .map(|(_, x)| x)
             .filter(|x| x.header.sh_type == sh_type)
             .collect()
     }
 
-    /// Attempts to find a section by name.
+    /// Attempts to find a section by name. If multiple sections share the name, returns the first one.
     pub fn find_section(&self, name: &str) -> Option<&Section> {
-        self.sections.get(name)
+        self.sections.iter().find(|x| x.name == name)
+    }
+
+    /// Finds all sections with the given name, in the order they appear in the section header table.
+    pub fn find_sections(&self, name: &str) -> Vec<&Section> {
+        self.sections.iter().filter(|x| x.name == name).collect()
     }
 
     /// Attempts to find a section by name and returns its index.
     pub fn find_section_idx(&self, name: &str) -> Option<u16> {
-        if let Some(x) = self.sections.get_index_of(name) {
-            return Some(x as u16);
-        }
-        None
+        self.sections
+            .iter()
+            .position(|x| x.name == name)
+            .map(|x| x as u16)
     }
 
     /// Attempts to find a section by name and gives a mutable reference to it.
     pub fn find_section_mut(&mut self, name: &str) -> Option<&mut Section> {
-        self.sections.get_mut(name)
+        self.sections.iter_mut().find(|x| x.name == name)
     }
 
-    /// Gets a copy of all symbols contained in this object.
-    pub fn get_symbols(&self) -> Vec<&Symbol> {
-        self.symbols.iter().map(|(_, x)| x).collect()
+    /// Adds a new section to the end of the section header table and returns its index.
+    ///
+    /// Sections with the `SHF_ALLOC` flag are placed at the end of a loadable segment with matching
+    /// permissions, or in a new loadable segment if there is no room.
+    pub fn add_section(
+        &mut self,
+        name: &str,
+        header: SectionHeader,
+        body: Vec<u8>,
+    ) -> Result<usize> {
+        self.insert_section(self.sections.len(), name, header, body)
     }
 
-    /// Gets mutable references to all symbols contained in this object.
-    pub fn get_symbols_mut(&mut self) -> Vec<&mut Symbol> {
-        self.symbols.iter_mut().map(|(_, x)| x).collect()
+    /// Adds a new section right after the section at index `after` and returns its index.
+    /// All references to sections after it are updated.
+    pub fn insert_section_after(
+        &mut self,
+        after: usize,
+        name: &str,
+        header: SectionHeader,
+        body: Vec<u8>,
+    ) -> Result<usize> {
+        if after >= self.sections.len() {
+            return Err(Error::OutOfRange {
+                offset: after as u64,
+                size: 1,
+                limit: self.sections.len() as u64,
+            });
+        }
+        self.insert_section(after + 1, name, header, body)
     }
 
-    /// Resolves internal references and offsets.
-    pub(crate) fn update(&mut self) -> Result<()> {
-        // Update program header sizes + offsets.
-        let old_phnum = self.header.e_phnum;
-        self.header.e_phnum = self.segments.len() as u16;
-        // Update symbol table.
-        {
-            let mut symtab_data = Vec::new();
-            for symbol in self.get_symbols() {
-                symbol.write(
-                    &self.header.e_ident.ei_class,
-                    &self.header.e_ident.ei_data,
-                    &mut symtab_data,
-                )?;
+    pub(crate) fn insert_section(
+        &mut self,
+        idx: usize,
+        name: &str,
+        mut header: SectionHeader,
+        body: Vec<u8>,
+    ) -> Result<usize> {
+        self.renumber_sections(|x| Some(if x as usize >= idx { x + 1 } else { x }));
+
+        // The offset is assigned when the layout is updated.
+        header.sh_offset = 0;
+        if header.sh_type != shtype::SHT_NOBITS {
+            header.sh_size = body.len() as u64;
+        }
+        let alloc = header.flags().contains(SectionFlags::SHF_ALLOC);
+        let mut section = Section::new(name, header);
+        section.body = body;
+        self.sections.insert(idx, section);
+        self.header.e_shnum = self.sections.len() as u16;
+
+        if alloc && !self.segments.is_empty() {
+            self.allocate_section(idx)?;
+        }
+        Ok(idx)
+    }
+
+    /// Removes a section, given by index or name, and returns it.
+    ///
+    /// Relocation sections that apply to the removed section are removed as well, and all references to
+    /// later sections are updated. Symbols stay in place so that references into the symbol table remain
+    /// valid: local symbols defined in the section become absolute, all others become undefined.
+    pub fn remove_section(&mut self, section: impl SectionId) -> Result<Section> {
+        let idx = match section.section_idx(self)? {
+            0 => return Err(Error::NullSection),
+            x => x,
+        };
+        let mut removed: Vec<_> = (0..self.sections.len())
+            .filter(|&i| {
+                let h = &self.sections[i].header;
+                i == idx
+                    || (matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA)
+                        && h.sh_info as usize == idx)
+            })
+            .collect();
+        // Groups that would end up empty are removed as well.
+        let endian = self.header.e_ident.ei_data.clone();
+        for (i, section) in self.sections.iter().enumerate() {
+            if section.header.sh_type != shtype::SHT_GROUP || section.body.len() < 8 {
+                continue;
+            }
+            let mut members = section.body[4..].chunks_exact(4);
+            if members.all(|x| {
+                let x = (&*x).read_u32(&endian).unwrap_or(0);
+                removed.contains(&(x as usize))
+            }) {
+                removed.push(i);
             }
-            let symtab = self.find_section_mut(".symtab").unwrap();
-            symtab.header.sh_size = symtab_data.len() as u64;
-            symtab.body = symtab_data;
-        }
-
-        // Update symbol string table.
-        {
-            let mut strtab_data = vec![0u8];
-            let mut strtab_pos = 1; // Leave room for null strings.
-            for (name, symbol) in &mut self.symbols {
-                symbol.sym_name = strtab_pos as u32; // Update the name offset.
-                strtab_pos += strtab_data.write_cstr(name)?;
+        }
+        removed.sort_unstable();
+
+        if self.sections[idx].header.sh_type == shtype::SHT_SYMTAB {
+            self.symbols.clear();
+        }
+        for symbol in &mut self.symbols {
+            if symbol.sym_shndx as usize != idx {
+                continue;
             }
-            let strtab = self.find_section_mut(".strtab").unwrap();
-            strtab.header.sh_size = strtab_data.len() as u64;
-            strtab.body = strtab_data;
-        }
-
-        // Update section string table.
-        {
-            let mut shstr_data = vec![0u8];
-            let mut shstr_pos = 1; // Leave room for null strings.
-            for (name, section) in &mut self.sections {
-                shstr_data.write_cstr(name)?;
-                section.header.sh_name = shstr_pos as u32;
-                shstr_pos += name.len() + 1;
+            if symbol.binding() == SymbolBinding::Local {
+                symbol.sym_shndx = shn::SHN_ABS;
+            } else {
+                symbol.sym_shndx = shn::SHN_UNDEF;
+                symbol.sym_value = 0;
+                symbol.sym_size = 0;
             }
-            // TODO
-            self.header.e_shstrndx = self.find_section_idx(".shstrtab").unwrap() as u16;
-            let shstrtab = self.find_section_mut(".shstrtab").unwrap();
-            shstrtab.body = shstr_data;
-            shstrtab.header.sh_size = shstr_pos as u64;
         }
 
-        // Update section sizes + offsets.
-        // Get the amount of total sections.
+        // Members of removed groups don't belong to a group anymore.
+        for &i in &removed {
+            if self.sections[i].header.sh_type != shtype::SHT_GROUP {
+                continue;
+            }
+            let members: Vec<_> = self.sections[i]
+                .body
+                .chunks_exact(4)
+                .skip(1)
+                .map(|x| (&*x).read_u32(&endian).unwrap_or(0) as usize)
+                .collect();
+            for x in members {
+                if let Some(member) = self.sections.get_mut(x) {
+                    let h = &mut member.header;
+                    h.set_flags(h.flags() - SectionFlags::SHF_GROUP);
+                }
+            }
+        }
+
+        // Drop the removed sections from all groups.
+        for section in &mut self.sections {
+            if section.header.sh_type == shtype::SHT_GROUP && section.body.len() >= 4 {
+                let mut body = section.body[..4].to_vec();
+                for member in section.body[4..].chunks_exact(4) {
+                    let x = (&*member).read_u32(&endian)?;
+                    if !removed.contains(&(x as usize)) {
+                        body.extend_from_slice(member);
+                    }
+                }
+                section.body = body;
+            }
+        }
+
+        self.renumber_sections(|x| match removed.binary_search(&(x as usize)) {
+            Ok(_) => None,
+            Err(n) => Some(x - n as u32),
+        });
+        let mut result = None;
+        for &i in removed.iter().rev() {
+            let section = self.sections.remove(i);
+            if i == idx {
+                result = Some(section);
+            }
+        }
         self.header.e_shnum = self.sections.len() as u16;
-        let mut section_pos = self.header.e_ehsize as u64
-            + (self.header.e_phentsize as u64 * self.header.e_phnum as u64);
-        for (_, section) in &mut self.sections {
-            // Align.
-            section_pos = align_to(&section_pos, &section.header.sh_addralign);
-            section.header.sh_offset = section_pos;
-            // Add the size of this section to the current cursor.
-            section_pos += section.body.len() as u64;
+        Ok(result.unwrap())
+    }
+
+    /// Adds a new segment with the given contents and returns its index in the program header table.
+    ///
+    /// The contents are placed at the end of the file, so `p_offset` and `p_filesz` are set automatically.
+    /// A loadable segment without an address is mapped after all other loadable segments.
+    /// If the program header table doesn't fit in its place anymore, it is moved into a new loadable segment.
+    pub fn add_segment(&mut self, mut header: ProgramHeader, data: Vec<u8>) -> usize {
+        let align = header.p_align.max(1);
+        if header.p_type == ptype::PT_LOAD && header.p_vaddr == 0 {
+            header.p_align = align.max(self.load_align());
+            header.p_vaddr = align_to(&self.vaddr_end(), &header.p_align);
+            header.p_paddr = header.p_vaddr;
         }
 
-        // Start of the symbol table is after all sections.
-        section_pos = align_to(&section_pos, &16);
-        self.header.e_shoff = section_pos;
+        // The offset has to be congruent to the address.
+        let align = header.p_align.max(1);
+        let offset = align_to(&self.file_end(), &align) + header.p_vaddr % align;
+        header.p_offset = offset;
+        header.p_filesz = data.len() as u64;
+        header.p_memsz = header.p_memsz.max(header.p_filesz);
+        let end = offset as usize + data.len();
+        if self.image.len() < end {
+            self.image.resize(end, 0);
+        }
+        self.image[offset as usize..end].copy_from_slice(&data);
 
-        // Byte size of new program header elements.
-        let new_phsize = self.header.e_phentsize * (self.header.e_phnum - old_phnum);
+        let old_phdr = (
+            self.header.e_phoff,
+            self.header.e_phnum as u64 * self.header.e_phentsize as u64,
+        );
+        let idx = self.insert_segment(Segment::new(header));
+        match self.place_program_headers(old_phdr) {
+            Some(x) if x <= idx => idx + 1,
+            _ => idx,
+        }
+    }
 
-        self.segments
-            .iter_mut()
-            .for_each(|x| match x.header.p_type {
-                // Update the (first) PHDR element in the program header table.
-                6 => {
-                    x.header.p_filesz = self.header.e_phnum as u64 * self.header.e_phentsize as u64;
-                    x.header.p_memsz = self.header.e_phnum as u64 * self.header.e_phentsize as u64;
+    /// Updates all references to sections after the section header table changed.
+    /// `map` returns the new index of a section, or `None` if the section was removed.
+    pub(crate) fn renumber_sections(&mut self, map: impl Fn(u32) -> Option<u32>) {
+        let map = |x: u32| map(x).unwrap_or(0);
+        let class = &self.header.e_ident.ei_class;
+        let endian = &self.header.e_ident.ei_data;
+        for section in &mut self.sections {
+            match section.header.sh_type {
+                // Section groups list their members after a flag word.
+                shtype::SHT_GROUP => {
+                    for member in section.body.chunks_exact_mut(4).skip(1) {
+                        let x = (&*member).read_u32(endian).unwrap_or(0);
+                        _ = member.as_mut().write_u32(endian, map(x));
+                    }
                 }
-                // Update the INTERP element in the program header table.
-                3 => x.header.p_offset += new_phsize as u64,
-                _ => (),
-            });
+                // The dynamic symbols are only kept in their section.
+                shtype::SHT_DYNSYM => {
+                    let entsize = match section.header.sh_entsize {
+                        0 => Symbol::size(class),
+                        x => x,
+                    };
+                    for entry in section.body.chunks_exact_mut(entsize as usize) {
+                        let Ok(mut symbol) = Symbol::read(class, endian, &*entry) else {
+                            continue;
+                        };
+                        if symbol.sym_shndx != shn::SHN_UNDEF
+                            && symbol.sym_shndx < shn::SHN_LORESERVE
+                        {
+                            symbol.sym_shndx = map(symbol.sym_shndx as u32) as u16;
+                            _ = symbol.write(class, endian, entry);
+                        }
+                    }
+                }
+                // Extended indices of symbols with `SHN_XINDEX`, 0 for all others.
+                shtype::SHT_SYMTAB_SHNDX => {
+                    for entry in section.body.chunks_exact_mut(4) {
+                        let x = (&*entry).read_u32(endian).unwrap_or(0);
+                        if x != 0 {
+                            _ = entry.as_mut().write_u32(endian, map(x));
+                        }
+                    }
+                }
+                _ => {}
+            }
+            let h = &mut section.header;
+            if h.sh_link != 0 {
+                h.sh_link = map(h.sh_link);
+            }
+            let info_link = matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA)
+                || h.flags().contains(SectionFlags::SHF_INFO_LINK);
+            if info_link && h.sh_info != 0 {
+                h.sh_info = map(h.sh_info);
+            }
+        }
+        if self.header.e_shstrndx != 0 {
+            self.header.e_shstrndx = map(self.header.e_shstrndx as u32) as u16;
+        }
+        for symbol in &mut self.symbols {
+            if symbol.sym_shndx != shn::SHN_UNDEF && symbol.sym_shndx < shn::SHN_LORESERVE {
+                symbol.sym_shndx = map(symbol.sym_shndx as u32) as u16;
+            }
+        }
+    }
 
+    /// Gets a copy of all symbols contained in this object.
+    pub fn get_symbols(&self) -> Vec<&Symbol> {
+        self.symbols.iter().collect()
+    }
+
+    /// Gets mutable references to all symbols contained in this object.
+    pub fn get_symbols_mut(&mut self) -> Vec<&mut Symbol> {
+        self.symbols.iter_mut().collect()
+    }
+
+    /// Attempts to find a symbol by name. If multiple symbols share the name, returns the first one.
+    pub fn find_symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|x| x.name == name)
+    }
+
+    /// Finds all symbols with the given name, in symbol table order.
+    pub fn find_symbols(&self, name: &str) -> Vec<&Symbol> {
+        self.symbols.iter().filter(|x| x.name == name).collect()
+    }
+
+    /// Attempts to find a symbol by name and returns its index in the symbol table.
+    pub fn find_symbol_idx(&self, name: &str) -> Option<usize> {
+        self.symbols.iter().position(|x| x.name == name)
+    }
+
+    /// Maps each symbol name to the indices of all symbols with that name.
+    pub fn symbol_index(&self) -> IndexMap<&str, Vec<usize>> {
+        let mut index: IndexMap<&str, Vec<usize>> = IndexMap::new();
+        for (i, symbol) in self.symbols.iter().enumerate() {
+            index.entry(&symbol.name).or_default().push(i);
+        }
+        index
+    }
+
+    /// Resolves internal references and offsets.
+    ///
+    /// Sections keep their place in the file as long as they still fit, so an unmodified object
+    /// is written back byte for byte. Sections that grew or are new get moved to the end of the file.
+    pub(crate) fn update(&mut self) -> Result<()> {
+        self.update_symbols()?;
+        self.update_section_names()?;
+        self.update_layout()?;
+        self.update_dynamic()
+    }
+
+    /// Writes all symbols back into the symbol table and their names into the string table.
+    fn update_symbols(&mut self) -> Result<()> {
+        let symtab = (0..self.sections.len())
+            .find(|&i| self.sections[i].header.sh_type == shtype::SHT_SYMTAB);
+        let Some(symtab) = symtab else {
+            return match self.symbols.is_empty() {
+                true => Ok(()),
+                false => Err(Error::MissingSection(".symtab".into())),
+            };
+        };
+
+        // Update symbol string table. Names that didn't change keep their offset.
+        let strtab_idx = self.linked_strtab(symtab)?;
+        let strtab = &mut self.sections[strtab_idx].body;
+        for symbol in &mut self.symbols {
+            if read_cstr_at(strtab, symbol.sym_name).ok().as_ref() != Some(&symbol.name) {
+                symbol.sym_name = insert_cstr(strtab, &symbol.name);
+            }
+        }
+
+        let mut symtab_data = Vec::new();
+        for symbol in &self.symbols {
+            symbol.write(
+                &self.header.e_ident.ei_class,
+                &self.header.e_ident.ei_data,
+                &mut symtab_data,
+            )?;
+        }
+        self.sections[symtab].body = symtab_data;
+        Ok(())
+    }
+
+    /// Writes all section names into the section name table.
+    fn update_section_names(&mut self) -> Result<()> {
+        let idx = match self.sections.get(self.header.e_shstrndx as usize) {
+            Some(x) if self.header.e_shstrndx != 0 && x.header.sh_type == shtype::SHT_STRTAB => {
+                Some(self.header.e_shstrndx)
+            }
+            _ => self.find_section_idx(".shstrtab"),
+        };
+        let Some(idx) = idx else {
+            self.header.e_shstrndx = 0;
+            return Ok(());
+        };
+
+        self.header.e_shstrndx = idx;
+        let mut shstr_data = std::mem::take(&mut self.sections[idx as usize].body);
+        for section in &mut self.sections {
+            if read_cstr_at(&shstr_data, section.header.sh_name)
+                .ok()
+                .as_ref()
+                != Some(&section.name)
+            {
+                section.header.sh_name = insert_cstr(&mut shstr_data, &section.name);
+            }
+        }
+        // The name of the section name table itself might have been added.
+        self.sections[idx as usize].body = shstr_data;
         Ok(())
     }
 }
@@ -0,0 +1,562 @@
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Write},
+};
+
+use bitflags::bitflags;
+
+use crate::{
+    error::{Error, Result},
+    object::{Class, Endianness, Header, Machine, Object, ObjectType},
+    section::{shtype, SectionId},
+    util::{ReadExt, WriteExt},
+};
+
+/// Defines an enum of relocation types for one architecture, together with the name, size and kind of each type.
+macro_rules! relocation_types {
+    ($(#[$meta:meta])* $($variant:ident = $value:literal, $name:literal, $size:literal, $kind:expr;)*) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum RelocationType {
+            $(#[doc = $name] $variant,)*
+            /// Any other value
+            Unknown(u32),
+        }
+
+        impl From<u32> for RelocationType {
+            fn from(value: u32) -> Self {
+                match value {
+                    $($value => RelocationType::$variant,)*
+                    x => RelocationType::Unknown(x),
+                }
+            }
+        }
+
+        impl From<RelocationType> for u32 {
+            fn from(value: RelocationType) -> Self {
+                match value {
+                    $(RelocationType::$variant => $value,)*
+                    RelocationType::Unknown(x) => x,
+                }
+            }
+        }
+
+        impl RelocationType {
+            /// Returns the name, size, kind and addend encoding of this type, or `None` if it is unknown.
+            pub fn info(&self) -> Option<super::RelocationInfo> {
+                match self {
+                    $(RelocationType::$variant => Some(super::RelocationInfo {
+                        name: $name,
+                        size: $size,
+                        kind: $kind,
+                        addend: self.addend_encoding(),
+                    }),)*
+                    RelocationType::Unknown(_) => None,
+                }
+            }
+        }
+    };
+}
+
+mod apply;
+
+pub mod aarch64;
+pub mod arm;
+pub mod i386;
+pub mod loongarch;
+pub mod mips;
+pub mod ppc64;
+pub mod riscv;
+pub mod x86_64;
+
+bitflags! {
+    /// Describes how the value of a relocation is computed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct RelocationKind: u8 {
+        /// Relative to the relocated location
+        const PC_RELATIVE = 0x1;
+        /// Refers to the global offset table
+        const GOT = 0x2;
+        /// Refers to the procedure linkage table
+        const PLT = 0x4;
+        /// Refers to thread-local storage
+        const TLS = 0x8;
+        /// Adds the load address of the object, without a symbol
+        const RELATIVE = 0x10;
+    }
+}
+
+/// Describes how the implicit addend of a `SHT_REL` entry is stored at the relocated location.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddendEncoding {
+    /// A signed integer of the given number of bytes.
+    Data(u8),
+    /// A signed integer with the size of an address.
+    Word,
+    /// The 24-bit word offset of an ARM `B` or `BL` instruction.
+    ArmBranch24,
+    /// The low 31 bits of a word, as used by `R_ARM_PREL31`.
+    ArmPrel31,
+    /// The 16-bit immediate of an ARM `MOVW` or `MOVT` instruction.
+    ArmMov16,
+    /// The 25-bit offset of a Thumb-2 `BL` or `B.W` instruction, spread over two halfwords.
+    ThumbBranch24,
+    /// The 16-bit immediate of a Thumb-2 `MOVW` or `MOVT` instruction, spread over two halfwords.
+    ThumbMov16,
+    /// The 26-bit word index of a MIPS `J` or `JAL` instruction.
+    Mips26,
+    /// The 16-bit immediate in the low half of a MIPS instruction. For types like `R_MIPS_HI16` that are paired
+    /// with a `R_MIPS_LO16`, this is only the part of the addend stored at this location.
+    Mips16,
+}
+
+impl AddendEncoding {
+    /// Returns the number of bytes that contain the addend field.
+    pub fn size(&self, class: &Class) -> usize {
+        match self {
+            AddendEncoding::Data(x) => *x as usize,
+            AddendEncoding::Word => Header::word_size(class) as usize,
+            _ => 4,
+        }
+    }
+
+    /// Reads the bytes containing the addend field as one integer.
+    /// Thumb-2 instructions are read as two halfwords, with the first one in the upper bits.
+    fn load(&self, mut data: &[u8], endian: &Endianness) -> Result<u64> {
+        Ok(match (self, data.len()) {
+            (AddendEncoding::ThumbBranch24 | AddendEncoding::ThumbMov16, _) => {
+                (data.read_u16(endian)? as u64) << 16 | data.read_u16(endian)? as u64
+            }
+            (_, 1) => data.read_u8()? as u64,
+            (_, 2) => data.read_u16(endian)? as u64,
+            (_, 4) => data.read_u32(endian)? as u64,
+            _ => data.read_u64(endian)?,
+        })
+    }
+
+    /// Writes an integer read by `load` back.
+    fn store(&self, data: &mut [u8], endian: &Endianness, value: u64) -> Result<()> {
+        let mut cursor = Cursor::new(data);
+        match (self, cursor.get_ref().len()) {
+            (AddendEncoding::ThumbBranch24 | AddendEncoding::ThumbMov16, _) => {
+                cursor.write_u16(endian, (value >> 16) as u16)?;
+                cursor.write_u16(endian, value as u16)?
+            }
+            (_, 1) => cursor.write_u8(value as u8)?,
+            (_, 2) => cursor.write_u16(endian, value as u16)?,
+            (_, 4) => cursor.write_u32(endian, value as u32)?,
+            _ => cursor.write_u64(endian, value)?,
+        };
+        Ok(())
+    }
+
+    /// Extracts the addend from the bytes containing it.
+    fn decode(&self, value: u64) -> i64 {
+        // Sign extends the low `bits` bits of `x`.
+        let sext = |x: u64, bits: u32| ((x << (64 - bits)) as i64) >> (64 - bits);
+        match self {
+            AddendEncoding::Data(x) => sext(value, *x as u32 * 8),
+            AddendEncoding::Word => sext(value, 64),
+            AddendEncoding::ArmBranch24 => sext(value & 0xFF_FFFF, 24) << 2,
+            AddendEncoding::ArmPrel31 => sext(value & 0x7FFF_FFFF, 31),
+            AddendEncoding::ArmMov16 => sext((value >> 4) & 0xF000 | value & 0xFFF, 16),
+            AddendEncoding::ThumbBranch24 => {
+                let (hi, lo) = (value >> 16, value & 0xFFFF);
+                let s = (hi >> 10) & 1;
+                let i1 = !((lo >> 13) ^ s) & 1;
+                let i2 = !((lo >> 11) ^ s) & 1;
+                let imm = s << 24 | i1 << 23 | i2 << 22 | (hi & 0x3FF) << 12 | (lo & 0x7FF) << 1;
+                sext(imm, 25)
+            }
+            AddendEncoding::ThumbMov16 => {
+                let (hi, lo) = (value >> 16, value & 0xFFFF);
+                let imm = (hi & 0xF) << 12 | (hi >> 10 & 1) << 11 | (lo >> 12 & 7) << 8 | lo & 0xFF;
+                sext(imm, 16)
+            }
+            AddendEncoding::Mips26 => ((value & 0x3FF_FFFF) << 2) as i64,
+            AddendEncoding::Mips16 => sext(value & 0xFFFF, 16),
+        }
+    }
+
+    /// Replaces the addend in the bytes containing it and keeps all other bits.
+    /// Bits of the addend that don't fit into the field are dropped.
+    fn encode(&self, value: u64, addend: i64) -> u64 {
+        let a = addend as u64;
+        match self {
+            AddendEncoding::Data(_) | AddendEncoding::Word => a,
+            AddendEncoding::ArmBranch24 => value & !0xFF_FFFF | (a >> 2) & 0xFF_FFFF,
+            AddendEncoding::ArmPrel31 => value & !0x7FFF_FFFF | a & 0x7FFF_FFFF,
+            AddendEncoding::ArmMov16 => value & !0xF_0FFF | (a & 0xF000) << 4 | a & 0xFFF,
+            AddendEncoding::ThumbBranch24 => {
+                let s = (a >> 24) & 1;
+                let j1 = !((a >> 23) ^ s) & 1;
+                let j2 = !((a >> 22) ^ s) & 1;
+                let hi = (value >> 16) & 0xF800 | s << 10 | (a >> 12) & 0x3FF;
+                let lo = value & 0xD000 | j1 << 13 | j2 << 11 | (a >> 1) & 0x7FF;
+                hi << 16 | lo
+            }
+            AddendEncoding::ThumbMov16 => {
+                let hi = (value >> 16) & !0x040F | (a >> 12) & 0xF | (a >> 11 & 1) << 10;
+                let lo = value & !0x70FF & 0xFFFF | (a >> 8 & 7) << 12 | a & 0xFF;
+                hi << 16 | lo
+            }
+            AddendEncoding::Mips26 => value & !0x3FF_FFFF | (a >> 2) & 0x3FF_FFFF,
+            AddendEncoding::Mips16 => value & !0xFFFF | a & 0xFFFF,
+        }
+    }
+}
+
+/// Properties of a relocation type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelocationInfo {
+    /// The name that readelf uses for this type.
+    pub name: &'static str,
+    /// Number of bytes modified at the relocated location, i.e. the width of the whole instruction or data word.
+    /// This is not necessarily the width of the addend, see `addend`. 0 for types that don't modify anything
+    /// by themselves, or whose size depends on the class of the object.
+    pub size: u8,
+    pub kind: RelocationKind,
+    /// How the implicit addend is stored, or `None` if reading and writing it isn't supported for this type.
+    pub addend: Option<AddendEncoding>,
+}
+
+/// Looks up a relocation type of the given architecture.
+pub fn type_info(machine: Machine, r_type: u32) -> Option<RelocationInfo> {
+    match machine {
+        Machine::X86_64 => x86_64::RelocationType::from(r_type).info(),
+        Machine::X86 => i386::RelocationType::from(r_type).info(),
+        Machine::AArch64 => aarch64::RelocationType::from(r_type).info(),
+        Machine::Arm => arm::RelocationType::from(r_type).info(),
+        Machine::RiscV => riscv::RelocationType::from(r_type).info(),
+        Machine::Ppc64 => ppc64::RelocationType::from(r_type).info(),
+        // Only the first of the packed MIPS64 types is considered.
+        Machine::Mips => mips::RelocationType::from(r_type & 0xFF).info(),
+        Machine::LoongArch => loongarch::RelocationType::from(r_type).info(),
+        _ => None,
+    }
+}
+
+/// A single entry of a `SHT_REL` or `SHT_RELA` section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Relocation {
+    /// The location to apply the relocation to. This is an offset into the target section for relocatable objects,
+    /// and a virtual address otherwise.
+    pub offset: u64,
+    /// Index of the referenced symbol in the symbol table given by `sh_link`.
+    pub sym_index: u32,
+    /// The machine specific relocation type. On MIPS64, this packs up to three types and the special symbol
+    /// into one value, starting with the first type in the lowest byte.
+    pub r_type: u32,
+    /// The constant that is added to the symbol value. For `SHT_REL` sections, it is stored at the location itself.
+    pub addend: i64,
+}
+
+impl Relocation {
+    /// Returns the size of a relocation entry in bytes.
+    pub fn size(class: &Class, rela: bool) -> u64 {
+        match (class, rela) {
+            (Class::Bits32, false) => 8,
+            (Class::Bits32, true) => 12,
+            (Class::Bits64, false) => 16,
+            (Class::Bits64, true) => 24,
+        }
+    }
+
+    /// Returns the name, size and kind of the relocation type, if it is known for the given architecture.
+    pub fn type_info(&self, machine: Machine) -> Option<RelocationInfo> {
+        type_info(machine, self.r_type)
+    }
+
+    /// Returns the name of the relocation type, or its value in hex if it is unknown.
+    pub fn type_name(&self, machine: Machine) -> String {
+        match self.type_info(machine) {
+            Some(x) => x.name.to_string(),
+            None => format!("{:#x}", self.r_type),
+        }
+    }
+
+    /// Describes the relocation like `R_X86_64_PLT32 foo-4`, given the name of the referenced symbol.
+    pub fn describe(&self, machine: Machine, symbol: &str) -> String {
+        let mut result = self.type_name(machine);
+        if !symbol.is_empty() || self.addend != 0 {
+            result.push(' ');
+            result.push_str(symbol);
+        }
+        match self.addend {
+            0 => {}
+            x if x < 0 || symbol.is_empty() => result.push_str(&x.to_string()),
+            x => result.push_str(&format!("+{}", x)),
+        }
+        result
+    }
+
+    /// Reads a relocation entry. Entries of `SHT_REL` sections don't contain an addend, so it is set to 0.
+    pub fn read(header: &Header, rela: bool, mut buf: impl Read) -> Result<Self> {
+        let endian = &header.e_ident.ei_data;
+        let (offset, info, addend) = match header.e_ident.ei_class {
+            Class::Bits32 => (
+                buf.read_u32(endian)? as u64,
+                buf.read_u32(endian)? as u64,
+                match rela {
+                    true => buf.read_u32(endian)? as i32 as i64,
+                    false => 0,
+                },
+            ),
+            Class::Bits64 => (
+                buf.read_u64(endian)?,
+                buf.read_u64(endian)?,
+                match rela {
+                    true => buf.read_u64(endian)? as i64,
+                    false => 0,
+                },
+            ),
+        };
+        let (sym_index, r_type) = split_info(header, info);
+        Ok(Self {
+            offset,
+            sym_index,
+            r_type,
+            addend,
+        })
+    }
+
+    /// Writes a relocation entry. The addend is only written for `SHT_RELA` sections.
+    pub fn write(&self, header: &Header, rela: bool, mut buf: impl Write) -> Result<usize> {
+        let endian = &header.e_ident.ei_data;
+        let info = join_info(header, self.sym_index, self.r_type);
+        let mut written = 0;
+        match header.e_ident.ei_class {
+            Class::Bits32 => {
+                written += buf.write_u32(endian, self.offset as u32)?;
+                written += buf.write_u32(endian, info as u32)?;
+                if rela {
+                    written += buf.write_u32(endian, self.addend as u32)?;
+                }
+            }
+            Class::Bits64 => {
+                written += buf.write_u64(endian, self.offset)?;
+                written += buf.write_u64(endian, info)?;
+                if rela {
+                    written += buf.write_u64(endian, self.addend as u64)?;
+                }
+            }
+        }
+        Ok(written)
+    }
+}
+
+/// Little endian MIPS64 stores `r_info` as a 32-bit symbol index followed by four single byte fields,
+/// which doesn't match the usual 64-bit layout.
+fn is_mips64el(header: &Header) -> bool {
+    matches!(header.e_ident.ei_class, Class::Bits64)
+        && matches!(header.e_ident.ei_data, Endianness::Little)
+        && header.machine() == Machine::Mips
+}
+
+/// Splits `r_info` into the symbol index and the relocation type.
+fn split_info(header: &Header, info: u64) -> (u32, u32) {
+    match header.e_ident.ei_class {
+        Class::Bits32 => ((info >> 8) as u32, (info & 0xFF) as u32),
+        Class::Bits64 if is_mips64el(header) => (info as u32, ((info >> 32) as u32).swap_bytes()),
+        Class::Bits64 => ((info >> 32) as u32, info as u32),
+    }
+}
+
+/// Combines the symbol index and the relocation type into `r_info`.
+fn join_info(header: &Header, sym_index: u32, r_type: u32) -> u64 {
+    match header.e_ident.ei_class {
+        Class::Bits32 => ((sym_index as u64) << 8) | (r_type as u64 & 0xFF),
+        Class::Bits64 if is_mips64el(header) => {
+            ((r_type.swap_bytes() as u64) << 32) | sym_index as u64
+        }
+        Class::Bits64 => ((sym_index as u64) << 32) | r_type as u64,
+    }
+}
+
+impl Object {
+    /// Reads all entries of a relocation section.
+    /// For `SHT_REL` sections, the implicit addends are read from the locations they apply to.
+    pub fn relocations(&self, section: impl SectionId) -> Result<Vec<Relocation>> {
+        let idx = section.section_idx(self)?;
+        let section = &self.sections[idx];
+        let rela = match section.header.sh_type {
+            shtype::SHT_RELA => true,
+            shtype::SHT_REL => false,
+            x => {
+                return Err(Error::WrongSectionType {
+                    name: section.name.clone(),
+                    sh_type: x,
+                })
+            }
+        };
+        let entsize = match section.header.sh_entsize {
+            0 => Relocation::size(&self.header.e_ident.ei_class, rela),
+            x => x,
+        };
+
+        let mut result = Vec::new();
+        for entry in section.body.chunks_exact(entsize as usize) {
+            let mut rel = Relocation::read(&self.header, rela, entry)?;
+            if !rela {
+                rel.addend = self.implicit_addend(idx, &rel)?;
+            }
+            result.push(rel);
+        }
+        Ok(result)
+    }
+
+    /// Collects the entries of all relocation sections that apply to the given section, as indicated by their `sh_info`.
+    pub fn relocations_for(&self, section: impl SectionId) -> Result<Vec<Relocation>> {
+        let idx = section.section_idx(self)?;
+        let mut result = Vec::new();
+        for i in 0..self.sections.len() {
+            let h = &self.sections[i].header;
+            if matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA) && h.sh_info as usize == idx
+            {
+                result.extend(self.relocations(i)?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Replaces all entries of a relocation section.
+    /// For `SHT_REL` sections, the addends are written to the locations they apply to.
+    pub fn set_relocations(
+        &mut self,
+        section: impl SectionId,
+        relocations: &[Relocation],
+    ) -> Result<()> {
+        let idx = section.section_idx(self)?;
+        let rela = match self.sections[idx].header.sh_type {
+            shtype::SHT_RELA => true,
+            shtype::SHT_REL => false,
+            x => {
+                return Err(Error::WrongSectionType {
+                    name: self.sections[idx].name.clone(),
+                    sh_type: x,
+                })
+            }
+        };
+
+        // The addends are written to copies of the target sections first, later entries may fail.
+        let mut body = Vec::new();
+        let mut targets = HashMap::new();
+        for rel in relocations {
+            rel.write(&self.header, rela, &mut body)?;
+            if !rela {
+                self.set_implicit_addend(idx, rel, &mut targets)?;
+            }
+        }
+        for (target, target_body) in targets {
+            self.sections[target].body = target_body;
+        }
+        self.sections[idx].body = body;
+        if self.sections[idx].header.sh_entsize == 0 {
+            self.sections[idx].header.sh_entsize =
+                Relocation::size(&self.header.e_ident.ei_class, rela);
+        }
+        Ok(())
+    }
+
+    /// Finds the section and the position in its body that a relocation at `offset` applies to.
+    /// Returns `None` if the location has no contents in the file.
+    pub(crate) fn relocation_target(
+        &self,
+        rel_section: usize,
+        offset: u64,
+    ) -> Option<(usize, usize)> {
+        let target = self.sections[rel_section].header.sh_info as usize;
+        if self.header.object_type() == ObjectType::Rel {
+            return (target != 0 && target < self.sections.len())
+                .then_some((target, offset as usize));
+        }
+
+        // Outside of relocatable objects, the offset is a virtual address.
+        (0..self.sections.len())
+            .filter(|&i| self.sections[i].header.sh_type != shtype::SHT_NOBITS)
+            .find(|&i| {
+                let (addr, size) = self.sections[i].mem_range();
+                size != 0 && offset >= addr && offset < addr + size
+            })
+            .map(|i| (i, (offset - self.sections[i].header.sh_addr) as usize))
+    }
+
+    /// Returns how the implicit addend of a relocation is stored, if that is known.
+    fn addend_encoding(&self, rel: &Relocation) -> Option<AddendEncoding> {
+        rel.type_info(self.header.machine()).and_then(|x| x.addend)
+    }
+
+    /// Reads the addend of a `SHT_REL` relocation from the location it applies to.
+    /// Returns 0 if the location has no contents or the addend encoding of the type is unknown.
+    fn implicit_addend(&self, rel_section: usize, rel: &Relocation) -> Result<i64> {
+        let Some((target, pos)) = self.relocation_target(rel_section, rel.offset) else {
+            return Ok(0);
+        };
+        let Some(encoding) = self.addend_encoding(rel) else {
+            return Ok(0);
+        };
+        let endian = &self.header.e_ident.ei_data;
+        let size = encoding.size(&self.header.e_ident.ei_class);
+        let body = &self.sections[target].body;
+        let data = pos
+            .checked_add(size)
+            .and_then(|end| body.get(pos..end))
+            .ok_or(Error::OutOfRange {
+                offset: rel.offset,
+                size: size as u64,
+                limit: body.len() as u64,
+            })?;
+        Ok(encoding.decode(encoding.load(data, endian)?))
+    }
+
+    /// Stores the addend of a `SHT_REL` relocation at the location it applies to, leaving all other bits as they are.
+    /// The target section is modified in `bodies`, which holds copies of the section bodies by index.
+    /// Fails if the addend is not 0 and its encoding is unknown, or if it doesn't fit into the field.
+    fn set_implicit_addend(
+        &self,
+        rel_section: usize,
+        rel: &Relocation,
+        bodies: &mut HashMap<usize, Vec<u8>>,
+    ) -> Result<()> {
+        let Some((target, pos)) = self.relocation_target(rel_section, rel.offset) else {
+            return Ok(());
+        };
+        let name = rel.type_name(self.header.machine());
+        let encoding = match self.addend_encoding(rel) {
+            Some(x) => x,
+            None if rel.addend == 0 => return Ok(()),
+            None => return Err(Error::UnsupportedRelocation(name)),
+        };
+        let endian = self.header.e_ident.ei_data.clone();
+        let size = encoding.size(&self.header.e_ident.ei_class);
+        let body = bodies
+            .entry(target)
+            .or_insert_with(|| self.sections[target].body.clone());
+        let limit = body.len() as u64;
+        let data = pos
+            .checked_add(size)
+            .and_then(|end| body.get_mut(pos..end))
+            .ok_or(Error::OutOfRange {
+                offset: rel.offset,
+                size: size as u64,
+                limit,
+            })?;
+        let value = encoding.encode(encoding.load(data, &endian)?, rel.addend);
+        let fits = match encoding {
+            // Plain data may hold the addend either signed or unsigned.
+            AddendEncoding::Data(_) | AddendEncoding::Word => {
+                let bits = size as u32 * 8;
+                let sext = ((rel.addend << (64 - bits)) >> (64 - bits)) == rel.addend;
+                sext || bits >= 64 || rel.addend as u64 >> bits == 0
+            }
+            _ => encoding.decode(value) == rel.addend,
+        };
+        if !fits {
+            return Err(Error::RelocationOverflow {
+                name,
+                offset: rel.offset,
+            });
+        }
+        encoding.store(data, &endian, value)
+    }
+}
@@ -0,0 +1,172 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::AArch64`.
+    None = 0, "R_AARCH64_NONE", 0, K::empty();
+    P32Abs32 = 1, "R_AARCH64_P32_ABS32", 4, K::empty();
+    P32Copy = 180, "R_AARCH64_P32_COPY", 0, K::empty();
+    P32GlobDat = 181, "R_AARCH64_P32_GLOB_DAT", 4, K::GOT;
+    P32JumpSlot = 182, "R_AARCH64_P32_JUMP_SLOT", 4, K::PLT;
+    P32Relative = 183, "R_AARCH64_P32_RELATIVE", 4, K::RELATIVE;
+    P32TlsDtpmod = 184, "R_AARCH64_P32_TLS_DTPMOD", 4, K::TLS;
+    P32TlsDtprel = 185, "R_AARCH64_P32_TLS_DTPREL", 4, K::TLS;
+    P32TlsTprel = 186, "R_AARCH64_P32_TLS_TPREL", 4, K::TLS;
+    P32Tlsdesc = 187, "R_AARCH64_P32_TLSDESC", 8, K::GOT | K::TLS;
+    P32Irelative = 188, "R_AARCH64_P32_IRELATIVE", 4, K::RELATIVE;
+    Abs64 = 257, "R_AARCH64_ABS64", 8, K::empty();
+    Abs32 = 258, "R_AARCH64_ABS32", 4, K::empty();
+    Abs16 = 259, "R_AARCH64_ABS16", 2, K::empty();
+    Prel64 = 260, "R_AARCH64_PREL64", 8, K::PC_RELATIVE;
+    Prel32 = 261, "R_AARCH64_PREL32", 4, K::PC_RELATIVE;
+    Prel16 = 262, "R_AARCH64_PREL16", 2, K::PC_RELATIVE;
+    MovwUabsG0 = 263, "R_AARCH64_MOVW_UABS_G0", 4, K::empty();
+    MovwUabsG0Nc = 264, "R_AARCH64_MOVW_UABS_G0_NC", 4, K::empty();
+    MovwUabsG1 = 265, "R_AARCH64_MOVW_UABS_G1", 4, K::empty();
+    MovwUabsG1Nc = 266, "R_AARCH64_MOVW_UABS_G1_NC", 4, K::empty();
+    MovwUabsG2 = 267, "R_AARCH64_MOVW_UABS_G2", 4, K::empty();
+    MovwUabsG2Nc = 268, "R_AARCH64_MOVW_UABS_G2_NC", 4, K::empty();
+    MovwUabsG3 = 269, "R_AARCH64_MOVW_UABS_G3", 4, K::empty();
+    MovwSabsG0 = 270, "R_AARCH64_MOVW_SABS_G0", 4, K::empty();
+    MovwSabsG1 = 271, "R_AARCH64_MOVW_SABS_G1", 4, K::empty();
+    MovwSabsG2 = 272, "R_AARCH64_MOVW_SABS_G2", 4, K::empty();
+    LdPrelLo19 = 273, "R_AARCH64_LD_PREL_LO19", 4, K::PC_RELATIVE;
+    AdrPrelLo21 = 274, "R_AARCH64_ADR_PREL_LO21", 4, K::PC_RELATIVE;
+    AdrPrelPgHi21 = 275, "R_AARCH64_ADR_PREL_PG_HI21", 4, K::PC_RELATIVE;
+    AdrPrelPgHi21Nc = 276, "R_AARCH64_ADR_PREL_PG_HI21_NC", 4, K::PC_RELATIVE;
+    AddAbsLo12Nc = 277, "R_AARCH64_ADD_ABS_LO12_NC", 4, K::empty();
+    Ldst8AbsLo12Nc = 278, "R_AARCH64_LDST8_ABS_LO12_NC", 4, K::empty();
+    Tstbr14 = 279, "R_AARCH64_TSTBR14", 4, K::PC_RELATIVE;
+    Condbr19 = 280, "R_AARCH64_CONDBR19", 4, K::PC_RELATIVE;
+    Jump26 = 282, "R_AARCH64_JUMP26", 4, K::PC_RELATIVE;
+    Call26 = 283, "R_AARCH64_CALL26", 4, K::PC_RELATIVE;
+    Ldst16AbsLo12Nc = 284, "R_AARCH64_LDST16_ABS_LO12_NC", 4, K::empty();
+    Ldst32AbsLo12Nc = 285, "R_AARCH64_LDST32_ABS_LO12_NC", 4, K::empty();
+    Ldst64AbsLo12Nc = 286, "R_AARCH64_LDST64_ABS_LO12_NC", 4, K::empty();
+    MovwPrelG0 = 287, "R_AARCH64_MOVW_PREL_G0", 4, K::PC_RELATIVE;
+    MovwPrelG0Nc = 288, "R_AARCH64_MOVW_PREL_G0_NC", 4, K::PC_RELATIVE;
+    MovwPrelG1 = 289, "R_AARCH64_MOVW_PREL_G1", 4, K::PC_RELATIVE;
+    MovwPrelG1Nc = 290, "R_AARCH64_MOVW_PREL_G1_NC", 4, K::PC_RELATIVE;
+    MovwPrelG2 = 291, "R_AARCH64_MOVW_PREL_G2", 4, K::PC_RELATIVE;
+    MovwPrelG2Nc = 292, "R_AARCH64_MOVW_PREL_G2_NC", 4, K::PC_RELATIVE;
+    MovwPrelG3 = 293, "R_AARCH64_MOVW_PREL_G3", 4, K::PC_RELATIVE;
+    Ldst128AbsLo12Nc = 299, "R_AARCH64_LDST128_ABS_LO12_NC", 4, K::empty();
+    MovwGotoffG0 = 300, "R_AARCH64_MOVW_GOTOFF_G0", 4, K::GOT;
+    MovwGotoffG0Nc = 301, "R_AARCH64_MOVW_GOTOFF_G0_NC", 4, K::GOT;
+    MovwGotoffG1 = 302, "R_AARCH64_MOVW_GOTOFF_G1", 4, K::GOT;
+    MovwGotoffG1Nc = 303, "R_AARCH64_MOVW_GOTOFF_G1_NC", 4, K::GOT;
+    MovwGotoffG2 = 304, "R_AARCH64_MOVW_GOTOFF_G2", 4, K::GOT;
+    MovwGotoffG2Nc = 305, "R_AARCH64_MOVW_GOTOFF_G2_NC", 4, K::GOT;
+    MovwGotoffG3 = 306, "R_AARCH64_MOVW_GOTOFF_G3", 4, K::GOT;
+    Gotrel64 = 307, "R_AARCH64_GOTREL64", 8, K::GOT;
+    Gotrel32 = 308, "R_AARCH64_GOTREL32", 4, K::GOT;
+    GotLdPrel19 = 309, "R_AARCH64_GOT_LD_PREL19", 4, K::PC_RELATIVE | K::GOT;
+    Ld64GotoffLo15 = 310, "R_AARCH64_LD64_GOTOFF_LO15", 4, K::GOT;
+    AdrGotPage = 311, "R_AARCH64_ADR_GOT_PAGE", 4, K::PC_RELATIVE | K::GOT;
+    Ld64GotLo12Nc = 312, "R_AARCH64_LD64_GOT_LO12_NC", 4, K::GOT;
+    Ld64GotpageLo15 = 313, "R_AARCH64_LD64_GOTPAGE_LO15", 4, K::GOT;
+    TlsgdAdrPrel21 = 512, "R_AARCH64_TLSGD_ADR_PREL21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsgdAdrPage21 = 513, "R_AARCH64_TLSGD_ADR_PAGE21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsgdAddLo12Nc = 514, "R_AARCH64_TLSGD_ADD_LO12_NC", 4, K::GOT | K::TLS;
+    TlsgdMovwG1 = 515, "R_AARCH64_TLSGD_MOVW_G1", 4, K::GOT | K::TLS;
+    TlsgdMovwG0Nc = 516, "R_AARCH64_TLSGD_MOVW_G0_NC", 4, K::GOT | K::TLS;
+    TlsldAdrPrel21 = 517, "R_AARCH64_TLSLD_ADR_PREL21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsldAdrPage21 = 518, "R_AARCH64_TLSLD_ADR_PAGE21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsldAddLo12Nc = 519, "R_AARCH64_TLSLD_ADD_LO12_NC", 4, K::GOT | K::TLS;
+    TlsldMovwG1 = 520, "R_AARCH64_TLSLD_MOVW_G1", 4, K::GOT | K::TLS;
+    TlsldMovwG0Nc = 521, "R_AARCH64_TLSLD_MOVW_G0_NC", 4, K::GOT | K::TLS;
+    TlsldLdPrel19 = 522, "R_AARCH64_TLSLD_LD_PREL19", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsldMovwDtprelG2 = 523, "R_AARCH64_TLSLD_MOVW_DTPREL_G2", 4, K::TLS;
+    TlsldMovwDtprelG1 = 524, "R_AARCH64_TLSLD_MOVW_DTPREL_G1", 4, K::TLS;
+    TlsldMovwDtprelG1Nc = 525, "R_AARCH64_TLSLD_MOVW_DTPREL_G1_NC", 4, K::TLS;
+    TlsldMovwDtprelG0 = 526, "R_AARCH64_TLSLD_MOVW_DTPREL_G0", 4, K::TLS;
+    TlsldMovwDtprelG0Nc = 527, "R_AARCH64_TLSLD_MOVW_DTPREL_G0_NC", 4, K::TLS;
+    TlsldAddDtprelHi12 = 528, "R_AARCH64_TLSLD_ADD_DTPREL_HI12", 4, K::TLS;
+    TlsldAddDtprelLo12 = 529, "R_AARCH64_TLSLD_ADD_DTPREL_LO12", 4, K::TLS;
+    TlsldAddDtprelLo12Nc = 530, "R_AARCH64_TLSLD_ADD_DTPREL_LO12_NC", 4, K::TLS;
+    TlsldLdst8DtprelLo12 = 531, "R_AARCH64_TLSLD_LDST8_DTPREL_LO12", 4, K::TLS;
+    TlsldLdst8DtprelLo12Nc = 532, "R_AARCH64_TLSLD_LDST8_DTPREL_LO12_NC", 4, K::TLS;
+    TlsldLdst16DtprelLo12 = 533, "R_AARCH64_TLSLD_LDST16_DTPREL_LO12", 4, K::TLS;
+    TlsldLdst16DtprelLo12Nc = 534, "R_AARCH64_TLSLD_LDST16_DTPREL_LO12_NC", 4, K::TLS;
+    TlsldLdst32DtprelLo12 = 535, "R_AARCH64_TLSLD_LDST32_DTPREL_LO12", 4, K::TLS;
+    TlsldLdst32DtprelLo12Nc = 536, "R_AARCH64_TLSLD_LDST32_DTPREL_LO12_NC", 4, K::TLS;
+    TlsldLdst64DtprelLo12 = 537, "R_AARCH64_TLSLD_LDST64_DTPREL_LO12", 4, K::TLS;
+    TlsldLdst64DtprelLo12Nc = 538, "R_AARCH64_TLSLD_LDST64_DTPREL_LO12_NC", 4, K::TLS;
+    TlsieMovwGottprelG1 = 539, "R_AARCH64_TLSIE_MOVW_GOTTPREL_G1", 4, K::GOT | K::TLS;
+    TlsieMovwGottprelG0Nc = 540, "R_AARCH64_TLSIE_MOVW_GOTTPREL_G0_NC", 4, K::GOT | K::TLS;
+    TlsieAdrGottprelPage21 = 541, "R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsieLd64GottprelLo12Nc = 542, "R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC", 4, K::GOT | K::TLS;
+    TlsieLdGottprelPrel19 = 543, "R_AARCH64_TLSIE_LD_GOTTPREL_PREL19", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsleMovwTprelG2 = 544, "R_AARCH64_TLSLE_MOVW_TPREL_G2", 4, K::TLS;
+    TlsleMovwTprelG1 = 545, "R_AARCH64_TLSLE_MOVW_TPREL_G1", 4, K::TLS;
+    TlsleMovwTprelG1Nc = 546, "R_AARCH64_TLSLE_MOVW_TPREL_G1_NC", 4, K::TLS;
+    TlsleMovwTprelG0 = 547, "R_AARCH64_TLSLE_MOVW_TPREL_G0", 4, K::TLS;
+    TlsleMovwTprelG0Nc = 548, "R_AARCH64_TLSLE_MOVW_TPREL_G0_NC", 4, K::TLS;
+    TlsleAddTprelHi12 = 549, "R_AARCH64_TLSLE_ADD_TPREL_HI12", 4, K::TLS;
+    TlsleAddTprelLo12 = 550, "R_AARCH64_TLSLE_ADD_TPREL_LO12", 4, K::TLS;
+    TlsleAddTprelLo12Nc = 551, "R_AARCH64_TLSLE_ADD_TPREL_LO12_NC", 4, K::TLS;
+    TlsleLdst8TprelLo12 = 552, "R_AARCH64_TLSLE_LDST8_TPREL_LO12", 4, K::TLS;
+    TlsleLdst8TprelLo12Nc = 553, "R_AARCH64_TLSLE_LDST8_TPREL_LO12_NC", 4, K::TLS;
+    TlsleLdst16TprelLo12 = 554, "R_AARCH64_TLSLE_LDST16_TPREL_LO12", 4, K::TLS;
+    TlsleLdst16TprelLo12Nc = 555, "R_AARCH64_TLSLE_LDST16_TPREL_LO12_NC", 4, K::TLS;
+    TlsleLdst32TprelLo12 = 556, "R_AARCH64_TLSLE_LDST32_TPREL_LO12", 4, K::TLS;
+    TlsleLdst32TprelLo12Nc = 557, "R_AARCH64_TLSLE_LDST32_TPREL_LO12_NC", 4, K::TLS;
+    TlsleLdst64TprelLo12 = 558, "R_AARCH64_TLSLE_LDST64_TPREL_LO12", 4, K::TLS;
+    TlsleLdst64TprelLo12Nc = 559, "R_AARCH64_TLSLE_LDST64_TPREL_LO12_NC", 4, K::TLS;
+    TlsdescLdPrel19 = 560, "R_AARCH64_TLSDESC_LD_PREL19", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescAdrPrel21 = 561, "R_AARCH64_TLSDESC_ADR_PREL21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescAdrPage21 = 562, "R_AARCH64_TLSDESC_ADR_PAGE21", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescLd64Lo12 = 563, "R_AARCH64_TLSDESC_LD64_LO12", 4, K::GOT | K::TLS;
+    TlsdescAddLo12 = 564, "R_AARCH64_TLSDESC_ADD_LO12", 4, K::GOT | K::TLS;
+    TlsdescOffG1 = 565, "R_AARCH64_TLSDESC_OFF_G1", 4, K::GOT | K::TLS;
+    TlsdescOffG0Nc = 566, "R_AARCH64_TLSDESC_OFF_G0_NC", 4, K::GOT | K::TLS;
+    TlsdescLdr = 567, "R_AARCH64_TLSDESC_LDR", 0, K::GOT | K::TLS;
+    TlsdescAdd = 568, "R_AARCH64_TLSDESC_ADD", 0, K::GOT | K::TLS;
+    TlsdescCall = 569, "R_AARCH64_TLSDESC_CALL", 0, K::GOT | K::TLS;
+    TlsleLdst128TprelLo12 = 570, "R_AARCH64_TLSLE_LDST128_TPREL_LO12", 4, K::TLS;
+    TlsleLdst128TprelLo12Nc = 571, "R_AARCH64_TLSLE_LDST128_TPREL_LO12_NC", 4, K::TLS;
+    TlsldLdst128DtprelLo12 = 572, "R_AARCH64_TLSLD_LDST128_DTPREL_LO12", 4, K::TLS;
+    TlsldLdst128DtprelLo12Nc = 573, "R_AARCH64_TLSLD_LDST128_DTPREL_LO12_NC", 4, K::TLS;
+    Copy = 1024, "R_AARCH64_COPY", 0, K::empty();
+    GlobDat = 1025, "R_AARCH64_GLOB_DAT", 8, K::GOT;
+    JumpSlot = 1026, "R_AARCH64_JUMP_SLOT", 8, K::PLT;
+    Relative = 1027, "R_AARCH64_RELATIVE", 8, K::RELATIVE;
+    TlsDtpmod = 1028, "R_AARCH64_TLS_DTPMOD", 8, K::TLS;
+    TlsDtprel = 1029, "R_AARCH64_TLS_DTPREL", 8, K::TLS;
+    TlsTprel = 1030, "R_AARCH64_TLS_TPREL", 8, K::TLS;
+    Tlsdesc = 1031, "R_AARCH64_TLSDESC", 16, K::GOT | K::TLS;
+    Irelative = 1032, "R_AARCH64_IRELATIVE", 8, K::RELATIVE;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    /// Only data relocations are supported, AArch64 objects use explicit addends for instructions.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::Abs16 | R::Prel16 => Some(AddendEncoding::Data(2)),
+            R::P32Abs32
+            | R::P32GlobDat
+            | R::P32JumpSlot
+            | R::P32Relative
+            | R::P32TlsDtpmod
+            | R::P32TlsDtprel
+            | R::P32TlsTprel
+            | R::P32Irelative
+            | R::Abs32
+            | R::Prel32
+            | R::Gotrel32 => Some(AddendEncoding::Data(4)),
+            R::Abs64
+            | R::Prel64
+            | R::Gotrel64
+            | R::GlobDat
+            | R::JumpSlot
+            | R::Relative
+            | R::TlsDtpmod
+            | R::TlsDtprel
+            | R::TlsTprel
+            | R::Irelative => Some(AddendEncoding::Data(8)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,492 @@
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use super::{aarch64, riscv, x86_64};
+use crate::error::{Error, Result};
+use crate::object::{Endianness, Machine, Object};
+use crate::section::{shtype, SectionId};
+use crate::symbol::Symbol;
+use crate::util::{ReadExt, WriteExt};
+
+/// Reasons why a single relocation can't be applied.
+enum Fault {
+    Unsupported,
+    Overflow,
+}
+
+type Patch<T = ()> = std::result::Result<T, Fault>;
+
+/// The inputs of a relocation calculation, named like in the psABI documents.
+struct Values {
+    /// Address of the symbol.
+    s: u64,
+    /// Addend.
+    a: i64,
+    /// Address of the relocated location.
+    p: u64,
+    /// Size of the symbol.
+    z: u64,
+}
+
+impl Values {
+    /// S + A
+    fn abs(&self) -> u64 {
+        self.s.wrapping_add(self.a as u64)
+    }
+
+    /// S + A - P
+    fn rel(&self) -> i64 {
+        self.abs().wrapping_sub(self.p) as i64
+    }
+}
+
+/// The bytes modified by a relocation.
+struct Place<'a> {
+    data: &'a mut [u8],
+    endian: Endianness,
+}
+
+impl Place<'_> {
+    fn read(&self) -> u64 {
+        let mut data = &*self.data;
+        let value = match self.data.len() {
+            1 => data.read_u8().map(u64::from),
+            2 => data.read_u16(&self.endian).map(u64::from),
+            4 => data.read_u32(&self.endian).map(u64::from),
+            _ => data.read_u64(&self.endian),
+        };
+        value.unwrap_or(0)
+    }
+
+    fn write(&mut self, value: u64) {
+        let endian = self.endian.clone();
+        let mut cursor = Cursor::new(&mut *self.data);
+        // The place always has the size of the relocation, so writing can't fail.
+        let _ = match cursor.get_ref().len() {
+            1 => cursor.write_u8(value as u8),
+            2 => cursor.write_u16(&endian, value as u16),
+            4 => cursor.write_u32(&endian, value as u32),
+            _ => cursor.write_u64(&endian, value),
+        };
+    }
+
+    /// Reads an instruction. Instructions are little endian on all supported architectures.
+    fn insn(&self) -> u32 {
+        match self.data.len() {
+            2 => u16::from_le_bytes([self.data[0], self.data[1]]) as u32,
+            _ => u32::from_le_bytes([self.data[0], self.data[1], self.data[2], self.data[3]]),
+        }
+    }
+
+    fn set_insn(&mut self, insn: u32) {
+        match self.data.len() {
+            2 => self.data.copy_from_slice(&(insn as u16).to_le_bytes()),
+            _ => self.data[..4].copy_from_slice(&insn.to_le_bytes()),
+        }
+    }
+
+    /// Replaces the bits of the instruction at `mask` with `bits`.
+    fn patch_insn(&mut self, mask: u32, bits: u32) {
+        self.set_insn((self.insn() & !mask) | (bits & mask));
+    }
+
+    /// Reads the ULEB128 value that fills the place.
+    fn uleb(&self) -> u64 {
+        (self.data.iter().rev()).fold(0, |acc, x| acc << 7 | (x & 0x7F) as u64)
+    }
+
+    /// Writes a ULEB128 value, padded to the size of the place.
+    fn set_uleb(&mut self, value: u64) -> Patch {
+        unsigned(value, 7 * self.data.len() as u32)?;
+        let last = self.data.len() - 1;
+        for (i, x) in self.data.iter_mut().enumerate() {
+            let bits = value.checked_shr(7 * i as u32).unwrap_or(0) as u8 & 0x7F;
+            *x = if i < last { bits | 0x80 } else { bits };
+        }
+        Ok(())
+    }
+}
+
+/// Returns the length of the ULEB128 value at the start of `data`,
+/// or a length past its end if the value isn't terminated.
+fn uleb_len(data: &[u8]) -> usize {
+    match data.iter().position(|x| x & 0x80 == 0) {
+        Some(x) => x + 1,
+        None => data.len() + 1,
+    }
+}
+
+/// Checks that `value` fits into a signed integer of `bits` bits.
+fn signed(value: i64, bits: u32) -> Patch {
+    let min = -1i64 << (bits - 1);
+    let max = (1i64 << (bits - 1)) - 1;
+    match value >= min && value <= max {
+        true => Ok(()),
+        false => Err(Fault::Overflow),
+    }
+}
+
+/// Checks that `value` fits into an unsigned integer of `bits` bits.
+fn unsigned(value: u64, bits: u32) -> Patch {
+    match bits >= 64 || value >> bits == 0 {
+        true => Ok(()),
+        false => Err(Fault::Overflow),
+    }
+}
+
+/// Checks that `value` fits into an integer of `bits` bits, either signed or unsigned.
+fn truncated(value: u64, bits: u32) -> Patch {
+    signed(value as i64, bits).or_else(|_| unsigned(value, bits))
+}
+
+fn apply_x86_64(r_type: u32, v: &Values, place: &mut Place) -> Patch {
+    use x86_64::RelocationType as R;
+    match R::from(r_type) {
+        R::None => return Ok(()),
+        // The address of the GOT entry is unknown.
+        R::Gotpcrel | R::Gotpcrelx | R::RexGotpcrelx => return Err(Fault::Unsupported),
+        R::R64 | R::Pc64 | R::Size64 => {}
+        R::Pc32 | R::Plt32 | R::Pc16 | R::Pc8 => signed(v.rel(), place.data.len() as u32 * 8)?,
+        R::R32 => unsigned(v.abs(), 32)?,
+        R::Size32 => unsigned(v.z.wrapping_add(v.a as u64), 32)?,
+        R::R32s => signed(v.abs() as i64, 32)?,
+        R::R16 | R::R8 => truncated(v.abs(), place.data.len() as u32 * 8)?,
+        _ => return Err(Fault::Unsupported),
+    }
+    let value = match R::from(r_type) {
+        R::Pc64 | R::Pc32 | R::Plt32 | R::Pc16 | R::Pc8 => v.rel() as u64,
+        R::Size32 | R::Size64 => v.z.wrapping_add(v.a as u64),
+        _ => v.abs(),
+    };
+    place.write(value);
+    Ok(())
+}
+
+fn apply_aarch64(r_type: u32, v: &Values, place: &mut Place) -> Patch {
+    use aarch64::RelocationType as R;
+    let page = |x: u64| x & !0xFFF;
+    match R::from(r_type) {
+        R::None => {}
+        R::Abs64 => place.write(v.abs()),
+        R::Abs32 | R::Abs16 => {
+            truncated(v.abs(), place.data.len() as u32 * 8)?;
+            place.write(v.abs());
+        }
+        R::Prel64 => place.write(v.rel() as u64),
+        R::Prel32 | R::Prel16 => {
+            truncated(v.rel() as u64, place.data.len() as u32 * 8)?;
+            place.write(v.rel() as u64);
+        }
+        R::Call26 | R::Jump26 => {
+            signed(v.rel(), 28)?;
+            place.patch_insn(0x03FF_FFFF, (v.rel() >> 2) as u32);
+        }
+        R::Condbr19 | R::LdPrelLo19 => {
+            signed(v.rel(), 21)?;
+            place.patch_insn(0x7FFFF << 5, ((v.rel() >> 2) as u32) << 5);
+        }
+        R::Tstbr14 => {
+            signed(v.rel(), 16)?;
+            place.patch_insn(0x3FFF << 5, ((v.rel() >> 2) as u32) << 5);
+        }
+        R::AdrPrelLo21 | R::AdrPrelPgHi21 | R::AdrPrelPgHi21Nc => {
+            let imm = match R::from(r_type) {
+                R::AdrPrelLo21 => {
+                    signed(v.rel(), 21)?;
+                    v.rel()
+                }
+                x => {
+                    let delta = page(v.abs()).wrapping_sub(page(v.p)) as i64;
+                    if x == R::AdrPrelPgHi21 {
+                        signed(delta, 33)?;
+                    }
+                    delta >> 12
+                }
+            } as u32;
+            // The immediate is split into 2 low bits at 29 and 19 high bits at 5.
+            place.patch_insn(
+                0x6000_0000 | 0x7FFFF << 5,
+                (imm & 3) << 29 | (imm >> 2) << 5,
+            );
+        }
+        R::AddAbsLo12Nc
+        | R::Ldst8AbsLo12Nc
+        | R::Ldst16AbsLo12Nc
+        | R::Ldst32AbsLo12Nc
+        | R::Ldst64AbsLo12Nc
+        | R::Ldst128AbsLo12Nc => {
+            // Loads and stores scale the offset by the access size.
+            let shift = match R::from(r_type) {
+                R::Ldst16AbsLo12Nc => 1,
+                R::Ldst32AbsLo12Nc => 2,
+                R::Ldst64AbsLo12Nc => 3,
+                R::Ldst128AbsLo12Nc => 4,
+                _ => 0,
+            };
+            place.patch_insn(0xFFF << 10, (((v.abs() & 0xFFF) >> shift) as u32) << 10);
+        }
+        R::MovwUabsG0
+        | R::MovwUabsG0Nc
+        | R::MovwUabsG1
+        | R::MovwUabsG1Nc
+        | R::MovwUabsG2
+        | R::MovwUabsG2Nc
+        | R::MovwUabsG3 => {
+            let (group, check) = match R::from(r_type) {
+                R::MovwUabsG0 => (0, true),
+                R::MovwUabsG0Nc => (0, false),
+                R::MovwUabsG1 => (1, true),
+                R::MovwUabsG1Nc => (1, false),
+                R::MovwUabsG2 => (2, true),
+                R::MovwUabsG2Nc => (2, false),
+                _ => (3, false),
+            };
+            if check {
+                unsigned(v.abs(), 16 * (group + 1))?;
+            }
+            place.patch_insn(
+                0xFFFF << 5,
+                (((v.abs() >> (16 * group)) & 0xFFFF) as u32) << 5,
+            );
+        }
+        // The address of the GOT entry is unknown.
+        R::AdrGotPage | R::Ld64GotLo12Nc => return Err(Fault::Unsupported),
+        _ => return Err(Fault::Unsupported),
+    }
+    Ok(())
+}
+
+/// Encodes the immediate of a RISC-V I-type instruction.
+fn riscv_itype(imm: u32) -> u32 {
+    (imm & 0xFFF) << 20
+}
+
+/// Encodes the immediate of a RISC-V S-type instruction.
+fn riscv_stype(imm: u32) -> u32 {
+    (imm & 0xFE0) << 20 | (imm & 0x1F) << 7
+}
+
+/// Encodes the immediate of a RISC-V U-type instruction, rounded so that adding the low 12 bits as a signed
+/// value gives the full immediate.
+fn riscv_utype(imm: i64) -> Patch<u32> {
+    let imm = imm.wrapping_add(0x800);
+    signed(imm, 32)?;
+    Ok(imm as u32 & 0xFFFF_F000)
+}
+
+/// `pcrel_hi` is the value computed for the `R_RISCV_PCREL_HI20` at the symbol address, if there is one.
+fn apply_riscv(r_type: u32, v: &Values, place: &mut Place, pcrel_hi: Option<i64>) -> Patch {
+    use riscv::RelocationType as R;
+    const ITYPE: u32 = 0xFFF0_0000;
+    const STYPE: u32 = 0xFE00_0F80;
+    const UTYPE: u32 = 0xFFFF_F000;
+    match R::from(r_type) {
+        R::None | R::Relax | R::Align => {}
+        R::R64 | R::Set8 | R::Set16 | R::Set32 => place.write(v.abs()),
+        R::R32 => {
+            truncated(v.abs(), 32)?;
+            place.write(v.abs());
+        }
+        R::R32Pcrel => {
+            signed(v.rel(), 32)?;
+            place.write(v.rel() as u64);
+        }
+        R::Add8 | R::Add16 | R::Add32 | R::Add64 => place.write(place.read().wrapping_add(v.abs())),
+        R::Sub8 | R::Sub16 | R::Sub32 | R::Sub64 => place.write(place.read().wrapping_sub(v.abs())),
+        R::Set6 => place.write(place.read() & 0xC0 | v.abs() & 0x3F),
+        R::Sub6 => place.write(place.read() & 0xC0 | place.read().wrapping_sub(v.abs()) & 0x3F),
+        // The value keeps the length of the one that is already there.
+        R::SetUleb128 => place.set_uleb(v.abs())?,
+        R::SubUleb128 => {
+            let bits = 7 * place.data.len() as u32;
+            let mask = u64::MAX.checked_shr(64 - bits.min(64)).unwrap_or(0);
+            place.set_uleb(place.uleb().wrapping_sub(v.abs()) & mask)?;
+        }
+        R::Branch => {
+            signed(v.rel(), 13)?;
+            let imm = v.rel() as u32;
+            let bits =
+                (imm & 0x1000) << 19 | (imm & 0x7E0) << 20 | (imm & 0x1E) << 7 | (imm & 0x800) >> 4;
+            place.patch_insn(STYPE, bits);
+        }
+        R::Jal => {
+            signed(v.rel(), 21)?;
+            let imm = v.rel() as u32;
+            let bits =
+                (imm & 0x10_0000) << 11 | (imm & 0x7FE) << 20 | (imm & 0x800) << 9 | imm & 0xF_F000;
+            place.patch_insn(UTYPE, bits);
+        }
+        R::Call | R::CallPlt => {
+            // An auipc followed by a jalr.
+            let hi = riscv_utype(v.rel())?;
+            let lo = riscv_itype(v.rel() as u32);
+            let mut auipc = Place {
+                data: &mut place.data[..4],
+                endian: Endianness::Little,
+            };
+            auipc.patch_insn(UTYPE, hi);
+            let mut jalr = Place {
+                data: &mut place.data[4..],
+                endian: Endianness::Little,
+            };
+            jalr.patch_insn(ITYPE, lo);
+        }
+        R::PcrelHi20 => place.patch_insn(UTYPE, riscv_utype(v.rel())?),
+        R::PcrelLo12I | R::PcrelLo12S => {
+            // The symbol is the location of the matching PCREL_HI20, which provides the value.
+            let value = pcrel_hi.ok_or(Fault::Unsupported)? as u32;
+            match R::from(r_type) {
+                R::PcrelLo12I => place.patch_insn(ITYPE, riscv_itype(value)),
+                _ => place.patch_insn(STYPE, riscv_stype(value)),
+            }
+        }
+        R::Hi20 => place.patch_insn(UTYPE, riscv_utype(v.abs() as i64)?),
+        R::Lo12I => place.patch_insn(ITYPE, riscv_itype(v.abs() as u32)),
+        R::Lo12S => place.patch_insn(STYPE, riscv_stype(v.abs() as u32)),
+        R::RvcBranch => {
+            signed(v.rel(), 9)?;
+            let imm = v.rel() as u32;
+            let bits = (imm & 0x100) << 4
+                | (imm & 0x18) << 7
+                | (imm & 0xC0) >> 1
+                | (imm & 0x6) << 2
+                | (imm & 0x20) >> 3;
+            place.patch_insn(0x1C7C, bits);
+        }
+        R::RvcJump => {
+            signed(v.rel(), 12)?;
+            let imm = v.rel() as u32;
+            let bits = (imm & 0x800) << 1
+                | (imm & 0x10) << 7
+                | (imm & 0x300) << 1
+                | (imm & 0x400) >> 2
+                | (imm & 0x40) << 1
+                | (imm & 0x80) >> 1
+                | (imm & 0xE) << 2
+                | (imm & 0x20) >> 3;
+            place.patch_insn(0x1FFC, bits);
+        }
+        _ => return Err(Fault::Unsupported),
+    }
+    Ok(())
+}
+
+impl Object {
+    /// Computes all relocations that apply to a section and writes the results into its body.
+    ///
+    /// The section is expected to be loaded at its `sh_addr`. `resolve` is called with the index and the entry of
+    /// every referenced symbol and returns its address, or `None` if it is unknown. The relocation sections
+    /// themselves are left as they are. If any relocation fails, the section isn't modified at all.
+    ///
+    /// Supports the common static relocation types of x86-64, AArch64 and RISC-V, including
+    /// `R_RISCV_SET_ULEB128` and `R_RISCV_SUB_ULEB128`. Types that refer to an entry in the global offset table,
+    /// like `R_X86_64_GOTPCREL(X)`, `R_X86_64_REX_GOTPCRELX`, `R_AARCH64_ADR_GOT_PAGE` and
+    /// `R_AARCH64_LD64_GOT_LO12_NC`, fail with `Error::UnsupportedRelocation`, since the object doesn't have a
+    /// table to point them to.
+    pub fn apply_relocations(
+        &mut self,
+        section: impl SectionId,
+        mut resolve: impl FnMut(usize, &Symbol) -> Option<u64>,
+    ) -> Result<()> {
+        let idx = section.section_idx(self)?;
+        let machine = self.header.machine();
+
+        // Resolve everything first, the RISC-V low parts need the values of the high parts they refer to.
+        let mut pending = Vec::new();
+        for i in 0..self.sections.len() {
+            let h = &self.sections[i].header;
+            if !matches!(h.sh_type, shtype::SHT_REL | shtype::SHT_RELA) || h.sh_info as usize != idx
+            {
+                continue;
+            }
+            for rel in self.relocations(i)? {
+                let Some((target, pos)) = self.relocation_target(i, rel.offset) else {
+                    continue;
+                };
+                let (s, z) = match rel.sym_index as usize {
+                    0 => (0, 0),
+                    x => {
+                        let sym = self.symbols.get(x).ok_or(Error::OutOfRange {
+                            offset: x as u64,
+                            size: 1,
+                            limit: self.symbols.len() as u64,
+                        })?;
+                        let s = resolve(x, sym)
+                            .ok_or_else(|| Error::UnresolvedSymbol(sym.name.clone()))?;
+                        (s, sym.sym_size)
+                    }
+                };
+                let p = self.sections[target].header.sh_addr + pos as u64;
+                let values = Values {
+                    s,
+                    a: rel.addend,
+                    p,
+                    z,
+                };
+                pending.push((rel, target, pos, values));
+            }
+        }
+
+        let pcrel_hi: HashMap<u64, i64> = pending
+            .iter()
+            .filter(|(rel, ..)| {
+                machine == Machine::RiscV
+                    && riscv::RelocationType::from(rel.r_type) == riscv::RelocationType::PcrelHi20
+            })
+            .map(|(_, _, _, v)| (v.p, v.rel()))
+            .collect();
+
+        // Everything is computed on copies of the sections first, later relocations may fail.
+        let endian = self.header.e_ident.ei_data.clone();
+        let mut bodies: HashMap<usize, Vec<u8>> = HashMap::new();
+        for (rel, target, pos, values) in pending {
+            let name = rel.type_name(machine);
+            let body = bodies
+                .entry(target)
+                .or_insert_with(|| self.sections[target].body.clone());
+            let size = match (machine, riscv::RelocationType::from(rel.r_type)) {
+                (
+                    Machine::RiscV,
+                    riscv::RelocationType::SetUleb128 | riscv::RelocationType::SubUleb128,
+                ) => uleb_len(body.get(pos..).unwrap_or_default()),
+                _ => rel.type_info(machine).map(|x| x.size as usize).unwrap_or(0),
+            };
+            let limit = body.len() as u64;
+            let data = pos
+                .checked_add(size)
+                .and_then(|end| body.get_mut(pos..end))
+                .ok_or(Error::OutOfRange {
+                    offset: rel.offset,
+                    size: size as u64,
+                    limit,
+                })?;
+            let mut place = Place {
+                data,
+                endian: endian.clone(),
+            };
+            let result = match machine {
+                Machine::X86_64 => apply_x86_64(rel.r_type, &values, &mut place),
+                Machine::AArch64 => apply_aarch64(rel.r_type, &values, &mut place),
+                Machine::RiscV => {
+                    let hi = pcrel_hi.get(&values.s).copied();
+                    apply_riscv(rel.r_type, &values, &mut place, hi)
+                }
+                _ => Err(Fault::Unsupported),
+            };
+            match result {
+                Ok(()) => {}
+                Err(Fault::Unsupported) => return Err(Error::UnsupportedRelocation(name)),
+                Err(Fault::Overflow) => {
+                    return Err(Error::RelocationOverflow {
+                        name,
+                        offset: rel.offset,
+                    })
+                }
+            }
+        }
+        for (target, body) in bodies {
+            self.sections[target].body = body;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::Arm`.
+    None = 0, "R_ARM_NONE", 0, K::empty();
+    Pc24 = 1, "R_ARM_PC24", 4, K::PC_RELATIVE;
+    Abs32 = 2, "R_ARM_ABS32", 4, K::empty();
+    Rel32 = 3, "R_ARM_REL32", 4, K::PC_RELATIVE;
+    Pc13 = 4, "R_ARM_PC13", 4, K::PC_RELATIVE;
+    Abs16 = 5, "R_ARM_ABS16", 2, K::empty();
+    Abs12 = 6, "R_ARM_ABS12", 4, K::empty();
+    ThmAbs5 = 7, "R_ARM_THM_ABS5", 2, K::empty();
+    Abs8 = 8, "R_ARM_ABS8", 1, K::empty();
+    Sbrel32 = 9, "R_ARM_SBREL32", 4, K::empty();
+    ThmCall = 10, "R_ARM_THM_CALL", 4, K::PC_RELATIVE;
+    ThmPc8 = 11, "R_ARM_THM_PC8", 2, K::PC_RELATIVE;
+    AmpVcall9 = 12, "R_ARM_AMP_VCALL9", 4, K::empty();
+    TlsDesc = 13, "R_ARM_TLS_DESC", 4, K::GOT | K::TLS;
+    ThmSwi8 = 14, "R_ARM_THM_SWI8", 2, K::empty();
+    Xpc25 = 15, "R_ARM_XPC25", 4, K::PC_RELATIVE;
+    ThmXpc22 = 16, "R_ARM_THM_XPC22", 4, K::PC_RELATIVE;
+    TlsDtpmod32 = 17, "R_ARM_TLS_DTPMOD32", 4, K::TLS;
+    TlsDtpoff32 = 18, "R_ARM_TLS_DTPOFF32", 4, K::TLS;
+    TlsTpoff32 = 19, "R_ARM_TLS_TPOFF32", 4, K::TLS;
+    Copy = 20, "R_ARM_COPY", 0, K::empty();
+    GlobDat = 21, "R_ARM_GLOB_DAT", 4, K::GOT;
+    JumpSlot = 22, "R_ARM_JUMP_SLOT", 4, K::PLT;
+    Relative = 23, "R_ARM_RELATIVE", 4, K::RELATIVE;
+    Gotoff = 24, "R_ARM_GOTOFF", 4, K::GOT;
+    Gotpc = 25, "R_ARM_GOTPC", 4, K::PC_RELATIVE | K::GOT;
+    Got32 = 26, "R_ARM_GOT32", 4, K::GOT;
+    Plt32 = 27, "R_ARM_PLT32", 4, K::PC_RELATIVE | K::PLT;
+    Call = 28, "R_ARM_CALL", 4, K::PC_RELATIVE;
+    Jump24 = 29, "R_ARM_JUMP24", 4, K::PC_RELATIVE;
+    ThmJump24 = 30, "R_ARM_THM_JUMP24", 4, K::PC_RELATIVE;
+    BaseAbs = 31, "R_ARM_BASE_ABS", 4, K::empty();
+    AluPcrel70 = 32, "R_ARM_ALU_PCREL_7_0", 4, K::PC_RELATIVE;
+    AluPcrel158 = 33, "R_ARM_ALU_PCREL_15_8", 4, K::PC_RELATIVE;
+    AluPcrel2315 = 34, "R_ARM_ALU_PCREL_23_15", 4, K::PC_RELATIVE;
+    LdrSbrel110 = 35, "R_ARM_LDR_SBREL_11_0", 4, K::empty();
+    AluSbrel1912 = 36, "R_ARM_ALU_SBREL_19_12", 4, K::empty();
+    AluSbrel2720 = 37, "R_ARM_ALU_SBREL_27_20", 4, K::empty();
+    Target1 = 38, "R_ARM_TARGET1", 4, K::empty();
+    Sbrel31 = 39, "R_ARM_SBREL31", 4, K::empty();
+    V4bx = 40, "R_ARM_V4BX", 4, K::empty();
+    Target2 = 41, "R_ARM_TARGET2", 4, K::empty();
+    Prel31 = 42, "R_ARM_PREL31", 4, K::PC_RELATIVE;
+    MovwAbsNc = 43, "R_ARM_MOVW_ABS_NC", 4, K::empty();
+    MovtAbs = 44, "R_ARM_MOVT_ABS", 4, K::empty();
+    MovwPrelNc = 45, "R_ARM_MOVW_PREL_NC", 4, K::PC_RELATIVE;
+    MovtPrel = 46, "R_ARM_MOVT_PREL", 4, K::PC_RELATIVE;
+    ThmMovwAbsNc = 47, "R_ARM_THM_MOVW_ABS_NC", 4, K::empty();
+    ThmMovtAbs = 48, "R_ARM_THM_MOVT_ABS", 4, K::empty();
+    ThmMovwPrelNc = 49, "R_ARM_THM_MOVW_PREL_NC", 4, K::PC_RELATIVE;
+    ThmMovtPrel = 50, "R_ARM_THM_MOVT_PREL", 4, K::PC_RELATIVE;
+    ThmJump19 = 51, "R_ARM_THM_JUMP19", 4, K::PC_RELATIVE;
+    ThmJump6 = 52, "R_ARM_THM_JUMP6", 2, K::PC_RELATIVE;
+    ThmAluPrel110 = 53, "R_ARM_THM_ALU_PREL_11_0", 4, K::PC_RELATIVE;
+    ThmPc12 = 54, "R_ARM_THM_PC12", 4, K::PC_RELATIVE;
+    Abs32Noi = 55, "R_ARM_ABS32_NOI", 4, K::empty();
+    Rel32Noi = 56, "R_ARM_REL32_NOI", 4, K::PC_RELATIVE;
+    AluPcG0Nc = 57, "R_ARM_ALU_PC_G0_NC", 4, K::PC_RELATIVE;
+    AluPcG0 = 58, "R_ARM_ALU_PC_G0", 4, K::PC_RELATIVE;
+    AluPcG1Nc = 59, "R_ARM_ALU_PC_G1_NC", 4, K::PC_RELATIVE;
+    AluPcG1 = 60, "R_ARM_ALU_PC_G1", 4, K::PC_RELATIVE;
+    AluPcG2 = 61, "R_ARM_ALU_PC_G2", 4, K::PC_RELATIVE;
+    LdrPcG1 = 62, "R_ARM_LDR_PC_G1", 4, K::PC_RELATIVE;
+    LdrPcG2 = 63, "R_ARM_LDR_PC_G2", 4, K::PC_RELATIVE;
+    LdrsPcG0 = 64, "R_ARM_LDRS_PC_G0", 4, K::PC_RELATIVE;
+    LdrsPcG1 = 65, "R_ARM_LDRS_PC_G1", 4, K::PC_RELATIVE;
+    LdrsPcG2 = 66, "R_ARM_LDRS_PC_G2", 4, K::PC_RELATIVE;
+    LdcPcG0 = 67, "R_ARM_LDC_PC_G0", 4, K::PC_RELATIVE;
+    LdcPcG1 = 68, "R_ARM_LDC_PC_G1", 4, K::PC_RELATIVE;
+    LdcPcG2 = 69, "R_ARM_LDC_PC_G2", 4, K::PC_RELATIVE;
+    AluSbG0Nc = 70, "R_ARM_ALU_SB_G0_NC", 4, K::empty();
+    AluSbG0 = 71, "R_ARM_ALU_SB_G0", 4, K::empty();
+    AluSbG1Nc = 72, "R_ARM_ALU_SB_G1_NC", 4, K::empty();
+    AluSbG1 = 73, "R_ARM_ALU_SB_G1", 4, K::empty();
+    AluSbG2 = 74, "R_ARM_ALU_SB_G2", 4, K::empty();
+    LdrSbG0 = 75, "R_ARM_LDR_SB_G0", 4, K::empty();
+    LdrSbG1 = 76, "R_ARM_LDR_SB_G1", 4, K::empty();
+    LdrSbG2 = 77, "R_ARM_LDR_SB_G2", 4, K::empty();
+    LdrsSbG0 = 78, "R_ARM_LDRS_SB_G0", 4, K::empty();
+    LdrsSbG1 = 79, "R_ARM_LDRS_SB_G1", 4, K::empty();
+    LdrsSbG2 = 80, "R_ARM_LDRS_SB_G2", 4, K::empty();
+    LdcSbG0 = 81, "R_ARM_LDC_SB_G0", 4, K::empty();
+    LdcSbG1 = 82, "R_ARM_LDC_SB_G1", 4, K::empty();
+    LdcSbG2 = 83, "R_ARM_LDC_SB_G2", 4, K::empty();
+    MovwBrelNc = 84, "R_ARM_MOVW_BREL_NC", 4, K::empty();
+    MovtBrel = 85, "R_ARM_MOVT_BREL", 4, K::empty();
+    MovwBrel = 86, "R_ARM_MOVW_BREL", 4, K::empty();
+    ThmMovwBrelNc = 87, "R_ARM_THM_MOVW_BREL_NC", 4, K::empty();
+    ThmMovtBrel = 88, "R_ARM_THM_MOVT_BREL", 4, K::empty();
+    ThmMovwBrel = 89, "R_ARM_THM_MOVW_BREL", 4, K::empty();
+    TlsGotdesc = 90, "R_ARM_TLS_GOTDESC", 4, K::GOT | K::TLS;
+    TlsCall = 91, "R_ARM_TLS_CALL", 4, K::TLS;
+    TlsDescseq = 92, "R_ARM_TLS_DESCSEQ", 4, K::GOT | K::TLS;
+    ThmTlsCall = 93, "R_ARM_THM_TLS_CALL", 4, K::TLS;
+    Plt32Abs = 94, "R_ARM_PLT32_ABS", 4, K::PLT;
+    GotAbs = 95, "R_ARM_GOT_ABS", 4, K::GOT;
+    GotPrel = 96, "R_ARM_GOT_PREL", 4, K::PC_RELATIVE | K::GOT;
+    GotBrel12 = 97, "R_ARM_GOT_BREL12", 4, K::GOT;
+    Gotoff12 = 98, "R_ARM_GOTOFF12", 4, K::GOT;
+    Gotrelax = 99, "R_ARM_GOTRELAX", 4, K::GOT;
+    GnuVtentry = 100, "R_ARM_GNU_VTENTRY", 0, K::empty();
+    GnuVtinherit = 101, "R_ARM_GNU_VTINHERIT", 0, K::empty();
+    ThmJump11 = 102, "R_ARM_THM_JUMP11", 2, K::PC_RELATIVE;
+    ThmJump8 = 103, "R_ARM_THM_JUMP8", 2, K::PC_RELATIVE;
+    TlsGd32 = 104, "R_ARM_TLS_GD32", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsLdm32 = 105, "R_ARM_TLS_LDM32", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsLdo32 = 106, "R_ARM_TLS_LDO32", 4, K::TLS;
+    TlsIe32 = 107, "R_ARM_TLS_IE32", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsLe32 = 108, "R_ARM_TLS_LE32", 4, K::TLS;
+    TlsLdo12 = 109, "R_ARM_TLS_LDO12", 4, K::TLS;
+    TlsLe12 = 110, "R_ARM_TLS_LE12", 4, K::TLS;
+    TlsIe12gp = 111, "R_ARM_TLS_IE12GP", 4, K::GOT | K::TLS;
+    MeToo = 128, "R_ARM_ME_TOO", 0, K::empty();
+    ThmTlsDescseq16 = 129, "R_ARM_THM_TLS_DESCSEQ16", 2, K::GOT | K::TLS;
+    ThmTlsDescseq32 = 130, "R_ARM_THM_TLS_DESCSEQ32", 4, K::GOT | K::TLS;
+    ThmGotBrel12 = 131, "R_ARM_THM_GOT_BREL12", 4, K::GOT;
+    Irelative = 160, "R_ARM_IRELATIVE", 4, K::RELATIVE;
+    Rxpc25 = 249, "R_ARM_RXPC25", 4, K::PC_RELATIVE;
+    Rsbrel32 = 250, "R_ARM_RSBREL32", 4, K::empty();
+    ThmRpc22 = 251, "R_ARM_THM_RPC22", 4, K::PC_RELATIVE;
+    Rrel32 = 252, "R_ARM_RREL32", 4, K::PC_RELATIVE;
+    Rabs22 = 253, "R_ARM_RABS22", 4, K::empty();
+    Rpc24 = 254, "R_ARM_RPC24", 4, K::PC_RELATIVE;
+    Rbase = 255, "R_ARM_RBASE", 4, K::empty();
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::Abs8 => Some(AddendEncoding::Data(1)),
+            R::Abs16 => Some(AddendEncoding::Data(2)),
+            R::Abs32
+            | R::Rel32
+            | R::Sbrel32
+            | R::TlsDesc
+            | R::TlsDtpmod32
+            | R::TlsDtpoff32
+            | R::TlsTpoff32
+            | R::GlobDat
+            | R::JumpSlot
+            | R::Relative
+            | R::Gotoff
+            | R::Gotpc
+            | R::Got32
+            | R::BaseAbs
+            | R::Target1
+            | R::Target2
+            | R::Abs32Noi
+            | R::Rel32Noi
+            | R::Plt32Abs
+            | R::GotAbs
+            | R::GotPrel
+            | R::TlsGd32
+            | R::TlsLdm32
+            | R::TlsLdo32
+            | R::TlsIe32
+            | R::TlsLe32
+            | R::Irelative => Some(AddendEncoding::Data(4)),
+            R::Prel31 => Some(AddendEncoding::ArmPrel31),
+            R::Pc24 | R::Plt32 | R::Call | R::Jump24 => Some(AddendEncoding::ArmBranch24),
+            R::MovwAbsNc
+            | R::MovtAbs
+            | R::MovwPrelNc
+            | R::MovtPrel
+            | R::MovwBrelNc
+            | R::MovtBrel
+            | R::MovwBrel => Some(AddendEncoding::ArmMov16),
+            R::ThmCall | R::ThmJump24 => Some(AddendEncoding::ThumbBranch24),
+            R::ThmMovwAbsNc
+            | R::ThmMovtAbs
+            | R::ThmMovwPrelNc
+            | R::ThmMovtPrel
+            | R::ThmMovwBrelNc
+            | R::ThmMovtBrel
+            | R::ThmMovwBrel => Some(AddendEncoding::ThumbMov16),
+            _ => None,
+        }
+    }
+}
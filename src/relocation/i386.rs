@@ -0,0 +1,62 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::X86`.
+    None = 0, "R_386_NONE", 0, K::empty();
+    R32 = 1, "R_386_32", 4, K::empty();
+    Pc32 = 2, "R_386_PC32", 4, K::PC_RELATIVE;
+    Got32 = 3, "R_386_GOT32", 4, K::GOT;
+    Plt32 = 4, "R_386_PLT32", 4, K::PC_RELATIVE | K::PLT;
+    Copy = 5, "R_386_COPY", 0, K::empty();
+    GlobDat = 6, "R_386_GLOB_DAT", 4, K::GOT;
+    JumpSlot = 7, "R_386_JUMP_SLOT", 4, K::PLT;
+    Relative = 8, "R_386_RELATIVE", 4, K::RELATIVE;
+    Gotoff = 9, "R_386_GOTOFF", 4, K::GOT;
+    Gotpc = 10, "R_386_GOTPC", 4, K::PC_RELATIVE | K::GOT;
+    R32plt = 11, "R_386_32PLT", 4, K::PLT;
+    TlsTpoff = 14, "R_386_TLS_TPOFF", 4, K::TLS;
+    TlsIe = 15, "R_386_TLS_IE", 4, K::GOT | K::TLS;
+    TlsGotie = 16, "R_386_TLS_GOTIE", 4, K::GOT | K::TLS;
+    TlsLe = 17, "R_386_TLS_LE", 4, K::TLS;
+    TlsGd = 18, "R_386_TLS_GD", 4, K::GOT | K::TLS;
+    TlsLdm = 19, "R_386_TLS_LDM", 4, K::GOT | K::TLS;
+    R16 = 20, "R_386_16", 2, K::empty();
+    Pc16 = 21, "R_386_PC16", 2, K::PC_RELATIVE;
+    R8 = 22, "R_386_8", 1, K::empty();
+    Pc8 = 23, "R_386_PC8", 1, K::PC_RELATIVE;
+    TlsGd32 = 24, "R_386_TLS_GD_32", 4, K::GOT | K::TLS;
+    TlsGdPush = 25, "R_386_TLS_GD_PUSH", 4, K::GOT | K::TLS;
+    TlsGdCall = 26, "R_386_TLS_GD_CALL", 4, K::GOT | K::TLS;
+    TlsGdPop = 27, "R_386_TLS_GD_POP", 4, K::GOT | K::TLS;
+    TlsLdm32 = 28, "R_386_TLS_LDM_32", 4, K::GOT | K::TLS;
+    TlsLdmPush = 29, "R_386_TLS_LDM_PUSH", 4, K::GOT | K::TLS;
+    TlsLdmCall = 30, "R_386_TLS_LDM_CALL", 4, K::GOT | K::TLS;
+    TlsLdmPop = 31, "R_386_TLS_LDM_POP", 4, K::GOT | K::TLS;
+    TlsLdo32 = 32, "R_386_TLS_LDO_32", 4, K::TLS;
+    TlsIe32 = 33, "R_386_TLS_IE_32", 4, K::GOT | K::TLS;
+    TlsLe32 = 34, "R_386_TLS_LE_32", 4, K::TLS;
+    TlsDtpmod32 = 35, "R_386_TLS_DTPMOD32", 4, K::TLS;
+    TlsDtpoff32 = 36, "R_386_TLS_DTPOFF32", 4, K::TLS;
+    TlsTpoff32 = 37, "R_386_TLS_TPOFF32", 4, K::TLS;
+    Size32 = 38, "R_386_SIZE32", 4, K::empty();
+    TlsGotdesc = 39, "R_386_TLS_GOTDESC", 4, K::GOT | K::TLS;
+    TlsDescCall = 40, "R_386_TLS_DESC_CALL", 0, K::GOT | K::TLS;
+    TlsDesc = 41, "R_386_TLS_DESC", 8, K::GOT | K::TLS;
+    Irelative = 42, "R_386_IRELATIVE", 4, K::RELATIVE;
+    Got32x = 43, "R_386_GOT32X", 4, K::GOT;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            // The addend of R_386_TLS_DESC is stored in the second word of the descriptor.
+            R::None | R::Copy | R::TlsDescCall | R::TlsDesc | R::Unknown(_) => None,
+            R::R8 | R::Pc8 => Some(AddendEncoding::Data(1)),
+            R::R16 | R::Pc16 => Some(AddendEncoding::Data(2)),
+            _ => Some(AddendEncoding::Data(4)),
+        }
+    }
+}
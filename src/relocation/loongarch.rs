@@ -0,0 +1,131 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::LoongArch`.
+    None = 0, "R_LARCH_NONE", 0, K::empty();
+    R32 = 1, "R_LARCH_32", 4, K::empty();
+    R64 = 2, "R_LARCH_64", 8, K::empty();
+    Relative = 3, "R_LARCH_RELATIVE", 0, K::RELATIVE;
+    Copy = 4, "R_LARCH_COPY", 0, K::empty();
+    JumpSlot = 5, "R_LARCH_JUMP_SLOT", 0, K::PLT;
+    TlsDtpmod32 = 6, "R_LARCH_TLS_DTPMOD32", 4, K::TLS;
+    TlsDtpmod64 = 7, "R_LARCH_TLS_DTPMOD64", 8, K::TLS;
+    TlsDtprel32 = 8, "R_LARCH_TLS_DTPREL32", 4, K::TLS;
+    TlsDtprel64 = 9, "R_LARCH_TLS_DTPREL64", 8, K::TLS;
+    TlsTprel32 = 10, "R_LARCH_TLS_TPREL32", 4, K::TLS;
+    TlsTprel64 = 11, "R_LARCH_TLS_TPREL64", 8, K::TLS;
+    Irelative = 12, "R_LARCH_IRELATIVE", 0, K::RELATIVE;
+    MarkLa = 20, "R_LARCH_MARK_LA", 0, K::empty();
+    MarkPcrel = 21, "R_LARCH_MARK_PCREL", 0, K::PC_RELATIVE;
+    SopPushPcrel = 22, "R_LARCH_SOP_PUSH_PCREL", 0, K::PC_RELATIVE;
+    SopPushAbsolute = 23, "R_LARCH_SOP_PUSH_ABSOLUTE", 0, K::empty();
+    SopPushDup = 24, "R_LARCH_SOP_PUSH_DUP", 0, K::empty();
+    SopPushGprel = 25, "R_LARCH_SOP_PUSH_GPREL", 0, K::empty();
+    SopPushTlsTprel = 26, "R_LARCH_SOP_PUSH_TLS_TPREL", 0, K::TLS;
+    SopPushTlsGot = 27, "R_LARCH_SOP_PUSH_TLS_GOT", 0, K::GOT | K::TLS;
+    SopPushTlsGd = 28, "R_LARCH_SOP_PUSH_TLS_GD", 0, K::GOT | K::TLS;
+    SopPushPltPcrel = 29, "R_LARCH_SOP_PUSH_PLT_PCREL", 0, K::PC_RELATIVE | K::PLT;
+    SopAssert = 30, "R_LARCH_SOP_ASSERT", 0, K::empty();
+    SopNot = 31, "R_LARCH_SOP_NOT", 0, K::empty();
+    SopSub = 32, "R_LARCH_SOP_SUB", 0, K::empty();
+    SopSl = 33, "R_LARCH_SOP_SL", 0, K::empty();
+    SopSr = 34, "R_LARCH_SOP_SR", 0, K::empty();
+    SopAdd = 35, "R_LARCH_SOP_ADD", 0, K::empty();
+    SopAnd = 36, "R_LARCH_SOP_AND", 0, K::empty();
+    SopIfElse = 37, "R_LARCH_SOP_IF_ELSE", 0, K::empty();
+    SopPop32S105 = 38, "R_LARCH_SOP_POP_32_S_10_5", 4, K::empty();
+    SopPop32U1012 = 39, "R_LARCH_SOP_POP_32_U_10_12", 4, K::empty();
+    SopPop32S1012 = 40, "R_LARCH_SOP_POP_32_S_10_12", 4, K::empty();
+    SopPop32S1016 = 41, "R_LARCH_SOP_POP_32_S_10_16", 4, K::empty();
+    SopPop32S1016S2 = 42, "R_LARCH_SOP_POP_32_S_10_16_S2", 4, K::empty();
+    SopPop32S520 = 43, "R_LARCH_SOP_POP_32_S_5_20", 4, K::empty();
+    SopPop32S051016S2 = 44, "R_LARCH_SOP_POP_32_S_0_5_10_16_S2", 4, K::empty();
+    SopPop32S0101016S2 = 45, "R_LARCH_SOP_POP_32_S_0_10_10_16_S2", 4, K::empty();
+    SopPop32U = 46, "R_LARCH_SOP_POP_32_U", 4, K::empty();
+    Add8 = 47, "R_LARCH_ADD8", 1, K::empty();
+    Add16 = 48, "R_LARCH_ADD16", 2, K::empty();
+    Add24 = 49, "R_LARCH_ADD24", 3, K::empty();
+    Add32 = 50, "R_LARCH_ADD32", 4, K::empty();
+    Add64 = 51, "R_LARCH_ADD64", 8, K::empty();
+    Sub8 = 52, "R_LARCH_SUB8", 1, K::empty();
+    Sub16 = 53, "R_LARCH_SUB16", 2, K::empty();
+    Sub24 = 54, "R_LARCH_SUB24", 3, K::empty();
+    Sub32 = 55, "R_LARCH_SUB32", 4, K::empty();
+    Sub64 = 56, "R_LARCH_SUB64", 8, K::empty();
+    GnuVtinherit = 57, "R_LARCH_GNU_VTINHERIT", 0, K::empty();
+    GnuVtentry = 58, "R_LARCH_GNU_VTENTRY", 0, K::empty();
+    B16 = 64, "R_LARCH_B16", 4, K::PC_RELATIVE;
+    B21 = 65, "R_LARCH_B21", 4, K::PC_RELATIVE;
+    B26 = 66, "R_LARCH_B26", 4, K::PC_RELATIVE;
+    AbsHi20 = 67, "R_LARCH_ABS_HI20", 4, K::empty();
+    AbsLo12 = 68, "R_LARCH_ABS_LO12", 4, K::empty();
+    Abs64Lo20 = 69, "R_LARCH_ABS64_LO20", 4, K::empty();
+    Abs64Hi12 = 70, "R_LARCH_ABS64_HI12", 4, K::empty();
+    PcalaHi20 = 71, "R_LARCH_PCALA_HI20", 4, K::PC_RELATIVE;
+    PcalaLo12 = 72, "R_LARCH_PCALA_LO12", 4, K::PC_RELATIVE;
+    Pcala64Lo20 = 73, "R_LARCH_PCALA64_LO20", 4, K::PC_RELATIVE;
+    Pcala64Hi12 = 74, "R_LARCH_PCALA64_HI12", 4, K::PC_RELATIVE;
+    GotPcHi20 = 75, "R_LARCH_GOT_PC_HI20", 4, K::PC_RELATIVE | K::GOT;
+    GotPcLo12 = 76, "R_LARCH_GOT_PC_LO12", 4, K::PC_RELATIVE | K::GOT;
+    Got64PcLo20 = 77, "R_LARCH_GOT64_PC_LO20", 4, K::PC_RELATIVE | K::GOT;
+    Got64PcHi12 = 78, "R_LARCH_GOT64_PC_HI12", 4, K::PC_RELATIVE | K::GOT;
+    GotHi20 = 79, "R_LARCH_GOT_HI20", 4, K::GOT;
+    GotLo12 = 80, "R_LARCH_GOT_LO12", 4, K::GOT;
+    Got64Lo20 = 81, "R_LARCH_GOT64_LO20", 4, K::GOT;
+    Got64Hi12 = 82, "R_LARCH_GOT64_HI12", 4, K::GOT;
+    TlsLeHi20 = 83, "R_LARCH_TLS_LE_HI20", 4, K::TLS;
+    TlsLeLo12 = 84, "R_LARCH_TLS_LE_LO12", 4, K::TLS;
+    TlsLe64Lo20 = 85, "R_LARCH_TLS_LE64_LO20", 4, K::TLS;
+    TlsLe64Hi12 = 86, "R_LARCH_TLS_LE64_HI12", 4, K::TLS;
+    TlsIePcHi20 = 87, "R_LARCH_TLS_IE_PC_HI20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsIePcLo12 = 88, "R_LARCH_TLS_IE_PC_LO12", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsIe64PcLo20 = 89, "R_LARCH_TLS_IE64_PC_LO20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsIe64PcHi12 = 90, "R_LARCH_TLS_IE64_PC_HI12", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsIeHi20 = 91, "R_LARCH_TLS_IE_HI20", 4, K::GOT | K::TLS;
+    TlsIeLo12 = 92, "R_LARCH_TLS_IE_LO12", 4, K::GOT | K::TLS;
+    TlsIe64Lo20 = 93, "R_LARCH_TLS_IE64_LO20", 4, K::GOT | K::TLS;
+    TlsIe64Hi12 = 94, "R_LARCH_TLS_IE64_HI12", 4, K::GOT | K::TLS;
+    TlsLdPcHi20 = 95, "R_LARCH_TLS_LD_PC_HI20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsLdHi20 = 96, "R_LARCH_TLS_LD_HI20", 4, K::GOT | K::TLS;
+    TlsGdPcHi20 = 97, "R_LARCH_TLS_GD_PC_HI20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsGdHi20 = 98, "R_LARCH_TLS_GD_HI20", 4, K::GOT | K::TLS;
+    R32Pcrel = 99, "R_LARCH_32_PCREL", 4, K::PC_RELATIVE;
+    Relax = 100, "R_LARCH_RELAX", 0, K::empty();
+    Align = 102, "R_LARCH_ALIGN", 0, K::empty();
+    Pcrel20S2 = 103, "R_LARCH_PCREL20_S2", 4, K::PC_RELATIVE;
+    Add6 = 105, "R_LARCH_ADD6", 1, K::empty();
+    Sub6 = 106, "R_LARCH_SUB6", 1, K::empty();
+    AddUleb128 = 107, "R_LARCH_ADD_ULEB128", 0, K::empty();
+    SubUleb128 = 108, "R_LARCH_SUB_ULEB128", 0, K::empty();
+    R64Pcrel = 109, "R_LARCH_64_PCREL", 8, K::PC_RELATIVE;
+    Call36 = 110, "R_LARCH_CALL36", 8, K::PC_RELATIVE;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    /// Only data relocations are supported, LoongArch objects use explicit addends for instructions.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::Add8 | R::Sub8 => Some(AddendEncoding::Data(1)),
+            R::Add16 | R::Sub16 => Some(AddendEncoding::Data(2)),
+            R::R32
+            | R::TlsDtpmod32
+            | R::TlsDtprel32
+            | R::TlsTprel32
+            | R::Add32
+            | R::Sub32
+            | R::R32Pcrel => Some(AddendEncoding::Data(4)),
+            R::R64
+            | R::TlsDtpmod64
+            | R::TlsDtprel64
+            | R::TlsTprel64
+            | R::Add64
+            | R::Sub64
+            | R::R64Pcrel => Some(AddendEncoding::Data(8)),
+            R::Relative | R::JumpSlot | R::Irelative => Some(AddendEncoding::Word),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::Mips`.
+    None = 0, "R_MIPS_NONE", 0, K::empty();
+    R16 = 1, "R_MIPS_16", 2, K::empty();
+    R32 = 2, "R_MIPS_32", 4, K::empty();
+    Rel32 = 3, "R_MIPS_REL32", 0, K::RELATIVE;
+    R26 = 4, "R_MIPS_26", 4, K::empty();
+    Hi16 = 5, "R_MIPS_HI16", 4, K::empty();
+    Lo16 = 6, "R_MIPS_LO16", 4, K::empty();
+    Gprel16 = 7, "R_MIPS_GPREL16", 4, K::empty();
+    Literal = 8, "R_MIPS_LITERAL", 4, K::empty();
+    Got16 = 9, "R_MIPS_GOT16", 4, K::GOT;
+    Pc16 = 10, "R_MIPS_PC16", 4, K::PC_RELATIVE;
+    Call16 = 11, "R_MIPS_CALL16", 4, K::GOT;
+    Gprel32 = 12, "R_MIPS_GPREL32", 4, K::empty();
+    Shift5 = 16, "R_MIPS_SHIFT5", 4, K::empty();
+    Shift6 = 17, "R_MIPS_SHIFT6", 4, K::empty();
+    R64 = 18, "R_MIPS_64", 8, K::empty();
+    GotDisp = 19, "R_MIPS_GOT_DISP", 4, K::GOT;
+    GotPage = 20, "R_MIPS_GOT_PAGE", 4, K::GOT;
+    GotOfst = 21, "R_MIPS_GOT_OFST", 4, K::GOT;
+    GotHi16 = 22, "R_MIPS_GOT_HI16", 4, K::GOT;
+    GotLo16 = 23, "R_MIPS_GOT_LO16", 4, K::GOT;
+    Sub = 24, "R_MIPS_SUB", 8, K::empty();
+    InsertA = 25, "R_MIPS_INSERT_A", 4, K::empty();
+    InsertB = 26, "R_MIPS_INSERT_B", 4, K::empty();
+    Delete = 27, "R_MIPS_DELETE", 4, K::empty();
+    Higher = 28, "R_MIPS_HIGHER", 4, K::empty();
+    Highest = 29, "R_MIPS_HIGHEST", 4, K::empty();
+    CallHi16 = 30, "R_MIPS_CALL_HI16", 4, K::GOT;
+    CallLo16 = 31, "R_MIPS_CALL_LO16", 4, K::GOT;
+    ScnDisp = 32, "R_MIPS_SCN_DISP", 4, K::empty();
+    Rel16 = 33, "R_MIPS_REL16", 2, K::empty();
+    AddImmediate = 34, "R_MIPS_ADD_IMMEDIATE", 4, K::empty();
+    Pjump = 35, "R_MIPS_PJUMP", 4, K::empty();
+    Relgot = 36, "R_MIPS_RELGOT", 4, K::GOT;
+    Jalr = 37, "R_MIPS_JALR", 4, K::empty();
+    TlsDtpmod32 = 38, "R_MIPS_TLS_DTPMOD32", 4, K::TLS;
+    TlsDtprel32 = 39, "R_MIPS_TLS_DTPREL32", 4, K::TLS;
+    TlsDtpmod64 = 40, "R_MIPS_TLS_DTPMOD64", 8, K::TLS;
+    TlsDtprel64 = 41, "R_MIPS_TLS_DTPREL64", 8, K::TLS;
+    TlsGd = 42, "R_MIPS_TLS_GD", 4, K::GOT | K::TLS;
+    TlsLdm = 43, "R_MIPS_TLS_LDM", 4, K::GOT | K::TLS;
+    TlsDtprelHi16 = 44, "R_MIPS_TLS_DTPREL_HI16", 4, K::TLS;
+    TlsDtprelLo16 = 45, "R_MIPS_TLS_DTPREL_LO16", 4, K::TLS;
+    TlsGottprel = 46, "R_MIPS_TLS_GOTTPREL", 4, K::GOT | K::TLS;
+    TlsTprel32 = 47, "R_MIPS_TLS_TPREL32", 4, K::TLS;
+    TlsTprel64 = 48, "R_MIPS_TLS_TPREL64", 8, K::TLS;
+    TlsTprelHi16 = 49, "R_MIPS_TLS_TPREL_HI16", 4, K::TLS;
+    TlsTprelLo16 = 50, "R_MIPS_TLS_TPREL_LO16", 4, K::TLS;
+    GlobDat = 51, "R_MIPS_GLOB_DAT", 0, K::GOT;
+    Copy = 126, "R_MIPS_COPY", 0, K::empty();
+    JumpSlot = 127, "R_MIPS_JUMP_SLOT", 0, K::PLT;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::R16 => Some(AddendEncoding::Data(2)),
+            R::R32 | R::Gprel32 | R::TlsDtpmod32 | R::TlsDtprel32 | R::TlsTprel32 => {
+                Some(AddendEncoding::Data(4))
+            }
+            R::R64 | R::Sub | R::TlsDtpmod64 | R::TlsDtprel64 | R::TlsTprel64 => {
+                Some(AddendEncoding::Data(8))
+            }
+            R::Rel32 | R::GlobDat | R::JumpSlot => Some(AddendEncoding::Word),
+            R::R26 => Some(AddendEncoding::Mips26),
+            R::Hi16
+            | R::Lo16
+            | R::Gprel16
+            | R::Literal
+            | R::Got16
+            | R::Call16
+            | R::GotDisp
+            | R::GotPage
+            | R::GotOfst
+            | R::GotHi16
+            | R::GotLo16
+            | R::Higher
+            | R::Highest
+            | R::CallHi16
+            | R::CallLo16
+            | R::TlsGd
+            | R::TlsLdm
+            | R::TlsDtprelHi16
+            | R::TlsDtprelLo16
+            | R::TlsGottprel
+            | R::TlsTprelHi16
+            | R::TlsTprelLo16 => Some(AddendEncoding::Mips16),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::Ppc64`.
+    None = 0, "R_PPC64_NONE", 0, K::empty();
+    Addr32 = 1, "R_PPC64_ADDR32", 4, K::empty();
+    Addr24 = 2, "R_PPC64_ADDR24", 4, K::empty();
+    Addr16 = 3, "R_PPC64_ADDR16", 2, K::empty();
+    Addr16Lo = 4, "R_PPC64_ADDR16_LO", 2, K::empty();
+    Addr16Hi = 5, "R_PPC64_ADDR16_HI", 2, K::empty();
+    Addr16Ha = 6, "R_PPC64_ADDR16_HA", 2, K::empty();
+    Addr14 = 7, "R_PPC64_ADDR14", 4, K::empty();
+    Addr14Brtaken = 8, "R_PPC64_ADDR14_BRTAKEN", 4, K::empty();
+    Addr14Brntaken = 9, "R_PPC64_ADDR14_BRNTAKEN", 4, K::empty();
+    Rel24 = 10, "R_PPC64_REL24", 4, K::PC_RELATIVE;
+    Rel14 = 11, "R_PPC64_REL14", 4, K::PC_RELATIVE;
+    Rel14Brtaken = 12, "R_PPC64_REL14_BRTAKEN", 4, K::PC_RELATIVE;
+    Rel14Brntaken = 13, "R_PPC64_REL14_BRNTAKEN", 4, K::PC_RELATIVE;
+    Got16 = 14, "R_PPC64_GOT16", 2, K::GOT;
+    Got16Lo = 15, "R_PPC64_GOT16_LO", 2, K::GOT;
+    Got16Hi = 16, "R_PPC64_GOT16_HI", 2, K::GOT;
+    Got16Ha = 17, "R_PPC64_GOT16_HA", 2, K::GOT;
+    Copy = 19, "R_PPC64_COPY", 0, K::empty();
+    GlobDat = 20, "R_PPC64_GLOB_DAT", 8, K::GOT;
+    JmpSlot = 21, "R_PPC64_JMP_SLOT", 8, K::PLT;
+    Relative = 22, "R_PPC64_RELATIVE", 8, K::RELATIVE;
+    Uaddr32 = 24, "R_PPC64_UADDR32", 4, K::empty();
+    Uaddr16 = 25, "R_PPC64_UADDR16", 2, K::empty();
+    Rel32 = 26, "R_PPC64_REL32", 4, K::PC_RELATIVE;
+    Plt32 = 27, "R_PPC64_PLT32", 4, K::PLT;
+    Pltrel32 = 28, "R_PPC64_PLTREL32", 4, K::PC_RELATIVE | K::PLT;
+    Plt16Lo = 29, "R_PPC64_PLT16_LO", 2, K::PLT;
+    Plt16Hi = 30, "R_PPC64_PLT16_HI", 2, K::PLT;
+    Plt16Ha = 31, "R_PPC64_PLT16_HA", 2, K::PLT;
+    Sectoff = 33, "R_PPC64_SECTOFF", 2, K::empty();
+    SectoffLo = 34, "R_PPC64_SECTOFF_LO", 2, K::empty();
+    SectoffHi = 35, "R_PPC64_SECTOFF_HI", 2, K::empty();
+    SectoffHa = 36, "R_PPC64_SECTOFF_HA", 2, K::empty();
+    Addr30 = 37, "R_PPC64_ADDR30", 4, K::empty();
+    Addr64 = 38, "R_PPC64_ADDR64", 8, K::empty();
+    Addr16Higher = 39, "R_PPC64_ADDR16_HIGHER", 2, K::empty();
+    Addr16Highera = 40, "R_PPC64_ADDR16_HIGHERA", 2, K::empty();
+    Addr16Highest = 41, "R_PPC64_ADDR16_HIGHEST", 2, K::empty();
+    Addr16Highesta = 42, "R_PPC64_ADDR16_HIGHESTA", 2, K::empty();
+    Uaddr64 = 43, "R_PPC64_UADDR64", 8, K::empty();
+    Rel64 = 44, "R_PPC64_REL64", 8, K::PC_RELATIVE;
+    Plt64 = 45, "R_PPC64_PLT64", 8, K::PLT;
+    Pltrel64 = 46, "R_PPC64_PLTREL64", 8, K::PC_RELATIVE | K::PLT;
+    Toc16 = 47, "R_PPC64_TOC16", 2, K::GOT;
+    Toc16Lo = 48, "R_PPC64_TOC16_LO", 2, K::GOT;
+    Toc16Hi = 49, "R_PPC64_TOC16_HI", 2, K::GOT;
+    Toc16Ha = 50, "R_PPC64_TOC16_HA", 2, K::GOT;
+    Toc = 51, "R_PPC64_TOC", 8, K::GOT;
+    Pltgot16 = 52, "R_PPC64_PLTGOT16", 2, K::GOT | K::PLT;
+    Pltgot16Lo = 53, "R_PPC64_PLTGOT16_LO", 2, K::GOT | K::PLT;
+    Pltgot16Hi = 54, "R_PPC64_PLTGOT16_HI", 2, K::GOT | K::PLT;
+    Pltgot16Ha = 55, "R_PPC64_PLTGOT16_HA", 2, K::GOT | K::PLT;
+    Addr16Ds = 56, "R_PPC64_ADDR16_DS", 2, K::empty();
+    Addr16LoDs = 57, "R_PPC64_ADDR16_LO_DS", 2, K::empty();
+    Got16Ds = 58, "R_PPC64_GOT16_DS", 2, K::GOT;
+    Got16LoDs = 59, "R_PPC64_GOT16_LO_DS", 2, K::GOT;
+    Plt16LoDs = 60, "R_PPC64_PLT16_LO_DS", 2, K::PLT;
+    SectoffDs = 61, "R_PPC64_SECTOFF_DS", 2, K::empty();
+    SectoffLoDs = 62, "R_PPC64_SECTOFF_LO_DS", 2, K::empty();
+    Toc16Ds = 63, "R_PPC64_TOC16_DS", 2, K::GOT;
+    Toc16LoDs = 64, "R_PPC64_TOC16_LO_DS", 2, K::GOT;
+    Pltgot16Ds = 65, "R_PPC64_PLTGOT16_DS", 2, K::GOT | K::PLT;
+    Pltgot16LoDs = 66, "R_PPC64_PLTGOT16_LO_DS", 2, K::GOT | K::PLT;
+    Tls = 67, "R_PPC64_TLS", 0, K::TLS;
+    Dtpmod64 = 68, "R_PPC64_DTPMOD64", 8, K::TLS;
+    Tprel16 = 69, "R_PPC64_TPREL16", 2, K::TLS;
+    Tprel16Lo = 70, "R_PPC64_TPREL16_LO", 2, K::TLS;
+    Tprel16Hi = 71, "R_PPC64_TPREL16_HI", 2, K::TLS;
+    Tprel16Ha = 72, "R_PPC64_TPREL16_HA", 2, K::TLS;
+    Tprel64 = 73, "R_PPC64_TPREL64", 8, K::TLS;
+    Dtprel16 = 74, "R_PPC64_DTPREL16", 2, K::TLS;
+    Dtprel16Lo = 75, "R_PPC64_DTPREL16_LO", 2, K::TLS;
+    Dtprel16Hi = 76, "R_PPC64_DTPREL16_HI", 2, K::TLS;
+    Dtprel16Ha = 77, "R_PPC64_DTPREL16_HA", 2, K::TLS;
+    Dtprel64 = 78, "R_PPC64_DTPREL64", 8, K::TLS;
+    GotTlsgd16 = 79, "R_PPC64_GOT_TLSGD16", 2, K::GOT | K::TLS;
+    GotTlsgd16Lo = 80, "R_PPC64_GOT_TLSGD16_LO", 2, K::GOT | K::TLS;
+    GotTlsgd16Hi = 81, "R_PPC64_GOT_TLSGD16_HI", 2, K::GOT | K::TLS;
+    GotTlsgd16Ha = 82, "R_PPC64_GOT_TLSGD16_HA", 2, K::GOT | K::TLS;
+    GotTlsld16 = 83, "R_PPC64_GOT_TLSLD16", 2, K::GOT | K::TLS;
+    GotTlsld16Lo = 84, "R_PPC64_GOT_TLSLD16_LO", 2, K::GOT | K::TLS;
+    GotTlsld16Hi = 85, "R_PPC64_GOT_TLSLD16_HI", 2, K::GOT | K::TLS;
+    GotTlsld16Ha = 86, "R_PPC64_GOT_TLSLD16_HA", 2, K::GOT | K::TLS;
+    GotTprel16Ds = 87, "R_PPC64_GOT_TPREL16_DS", 2, K::GOT | K::TLS;
+    GotTprel16LoDs = 88, "R_PPC64_GOT_TPREL16_LO_DS", 2, K::GOT | K::TLS;
+    GotTprel16Hi = 89, "R_PPC64_GOT_TPREL16_HI", 2, K::GOT | K::TLS;
+    GotTprel16Ha = 90, "R_PPC64_GOT_TPREL16_HA", 2, K::GOT | K::TLS;
+    GotDtprel16Ds = 91, "R_PPC64_GOT_DTPREL16_DS", 2, K::GOT | K::TLS;
+    GotDtprel16LoDs = 92, "R_PPC64_GOT_DTPREL16_LO_DS", 2, K::GOT | K::TLS;
+    GotDtprel16Hi = 93, "R_PPC64_GOT_DTPREL16_HI", 2, K::GOT | K::TLS;
+    GotDtprel16Ha = 94, "R_PPC64_GOT_DTPREL16_HA", 2, K::GOT | K::TLS;
+    Tprel16Ds = 95, "R_PPC64_TPREL16_DS", 2, K::TLS;
+    Tprel16LoDs = 96, "R_PPC64_TPREL16_LO_DS", 2, K::TLS;
+    Tprel16Higher = 97, "R_PPC64_TPREL16_HIGHER", 2, K::TLS;
+    Tprel16Highera = 98, "R_PPC64_TPREL16_HIGHERA", 2, K::TLS;
+    Tprel16Highest = 99, "R_PPC64_TPREL16_HIGHEST", 2, K::TLS;
+    Tprel16Highesta = 100, "R_PPC64_TPREL16_HIGHESTA", 2, K::TLS;
+    Dtprel16Ds = 101, "R_PPC64_DTPREL16_DS", 2, K::TLS;
+    Dtprel16LoDs = 102, "R_PPC64_DTPREL16_LO_DS", 2, K::TLS;
+    Dtprel16Higher = 103, "R_PPC64_DTPREL16_HIGHER", 2, K::TLS;
+    Dtprel16Highera = 104, "R_PPC64_DTPREL16_HIGHERA", 2, K::TLS;
+    Dtprel16Highest = 105, "R_PPC64_DTPREL16_HIGHEST", 2, K::TLS;
+    Dtprel16Highesta = 106, "R_PPC64_DTPREL16_HIGHESTA", 2, K::TLS;
+    Tlsgd = 107, "R_PPC64_TLSGD", 0, K::GOT | K::TLS;
+    Tlsld = 108, "R_PPC64_TLSLD", 0, K::GOT | K::TLS;
+    Tocsave = 109, "R_PPC64_TOCSAVE", 0, K::empty();
+    Addr16High = 110, "R_PPC64_ADDR16_HIGH", 2, K::empty();
+    Addr16Higha = 111, "R_PPC64_ADDR16_HIGHA", 2, K::empty();
+    Tprel16High = 112, "R_PPC64_TPREL16_HIGH", 2, K::TLS;
+    Tprel16Higha = 113, "R_PPC64_TPREL16_HIGHA", 2, K::TLS;
+    Dtprel16High = 114, "R_PPC64_DTPREL16_HIGH", 2, K::TLS;
+    Dtprel16Higha = 115, "R_PPC64_DTPREL16_HIGHA", 2, K::TLS;
+    Rel24Notoc = 116, "R_PPC64_REL24_NOTOC", 4, K::PC_RELATIVE | K::GOT;
+    Addr64Local = 117, "R_PPC64_ADDR64_LOCAL", 8, K::empty();
+    Entry = 118, "R_PPC64_ENTRY", 0, K::empty();
+    Pltseq = 119, "R_PPC64_PLTSEQ", 0, K::PLT;
+    Pltcall = 120, "R_PPC64_PLTCALL", 0, K::PLT;
+    PltseqNotoc = 121, "R_PPC64_PLTSEQ_NOTOC", 0, K::GOT | K::PLT;
+    PltcallNotoc = 122, "R_PPC64_PLTCALL_NOTOC", 0, K::GOT | K::PLT;
+    PcrelOpt = 123, "R_PPC64_PCREL_OPT", 0, K::PC_RELATIVE;
+    D34 = 128, "R_PPC64_D34", 8, K::empty();
+    D34Lo = 129, "R_PPC64_D34_LO", 8, K::empty();
+    D34Hi30 = 130, "R_PPC64_D34_HI30", 8, K::empty();
+    D34Ha30 = 131, "R_PPC64_D34_HA30", 8, K::empty();
+    Pcrel34 = 132, "R_PPC64_PCREL34", 8, K::PC_RELATIVE;
+    GotPcrel34 = 133, "R_PPC64_GOT_PCREL34", 8, K::PC_RELATIVE | K::GOT;
+    PltPcrel34 = 134, "R_PPC64_PLT_PCREL34", 8, K::PC_RELATIVE | K::PLT;
+    PltPcrel34Notoc = 135, "R_PPC64_PLT_PCREL34_NOTOC", 8, K::PC_RELATIVE | K::GOT | K::PLT;
+    Tprel34 = 146, "R_PPC64_TPREL34", 8, K::TLS;
+    Dtprel34 = 147, "R_PPC64_DTPREL34", 8, K::TLS;
+    GotTlsgdPcrel34 = 148, "R_PPC64_GOT_TLSGD_PCREL34", 8, K::PC_RELATIVE | K::GOT | K::TLS;
+    GotTlsldPcrel34 = 149, "R_PPC64_GOT_TLSLD_PCREL34", 8, K::PC_RELATIVE | K::GOT | K::TLS;
+    GotTprelPcrel34 = 150, "R_PPC64_GOT_TPREL_PCREL34", 8, K::PC_RELATIVE | K::GOT | K::TLS;
+    GotDtprelPcrel34 = 151, "R_PPC64_GOT_DTPREL_PCREL34", 8, K::PC_RELATIVE | K::GOT | K::TLS;
+    JmpIrel = 247, "R_PPC64_JMP_IREL", 8, K::PLT | K::RELATIVE;
+    Irelative = 248, "R_PPC64_IRELATIVE", 8, K::RELATIVE;
+    Rel16 = 249, "R_PPC64_REL16", 2, K::PC_RELATIVE;
+    Rel16Lo = 250, "R_PPC64_REL16_LO", 2, K::PC_RELATIVE;
+    Rel16Hi = 251, "R_PPC64_REL16_HI", 2, K::PC_RELATIVE;
+    Rel16Ha = 252, "R_PPC64_REL16_HA", 2, K::PC_RELATIVE;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    /// Only data relocations are supported, PowerPC objects use explicit addends for instructions.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::Addr16 | R::Uaddr16 => Some(AddendEncoding::Data(2)),
+            R::Addr32 | R::Uaddr32 | R::Rel32 | R::Plt32 | R::Pltrel32 => {
+                Some(AddendEncoding::Data(4))
+            }
+            R::GlobDat
+            | R::JmpSlot
+            | R::Relative
+            | R::Addr64
+            | R::Uaddr64
+            | R::Rel64
+            | R::Plt64
+            | R::Pltrel64
+            | R::Toc
+            | R::Dtpmod64
+            | R::Tprel64
+            | R::Dtprel64
+            | R::Addr64Local
+            | R::Irelative => Some(AddendEncoding::Data(8)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::RiscV`.
+    None = 0, "R_RISCV_NONE", 0, K::empty();
+    R32 = 1, "R_RISCV_32", 4, K::empty();
+    R64 = 2, "R_RISCV_64", 8, K::empty();
+    Relative = 3, "R_RISCV_RELATIVE", 0, K::RELATIVE;
+    Copy = 4, "R_RISCV_COPY", 0, K::empty();
+    JumpSlot = 5, "R_RISCV_JUMP_SLOT", 0, K::PLT;
+    TlsDtpmod32 = 6, "R_RISCV_TLS_DTPMOD32", 4, K::TLS;
+    TlsDtpmod64 = 7, "R_RISCV_TLS_DTPMOD64", 8, K::TLS;
+    TlsDtprel32 = 8, "R_RISCV_TLS_DTPREL32", 4, K::TLS;
+    TlsDtprel64 = 9, "R_RISCV_TLS_DTPREL64", 8, K::TLS;
+    TlsTprel32 = 10, "R_RISCV_TLS_TPREL32", 4, K::TLS;
+    TlsTprel64 = 11, "R_RISCV_TLS_TPREL64", 8, K::TLS;
+    Tlsdesc = 12, "R_RISCV_TLSDESC", 0, K::GOT | K::TLS;
+    Branch = 16, "R_RISCV_BRANCH", 4, K::PC_RELATIVE;
+    Jal = 17, "R_RISCV_JAL", 4, K::PC_RELATIVE;
+    Call = 18, "R_RISCV_CALL", 8, K::PC_RELATIVE;
+    CallPlt = 19, "R_RISCV_CALL_PLT", 8, K::PC_RELATIVE | K::PLT;
+    GotHi20 = 20, "R_RISCV_GOT_HI20", 4, K::PC_RELATIVE | K::GOT;
+    TlsGotHi20 = 21, "R_RISCV_TLS_GOT_HI20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsGdHi20 = 22, "R_RISCV_TLS_GD_HI20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    PcrelHi20 = 23, "R_RISCV_PCREL_HI20", 4, K::PC_RELATIVE;
+    PcrelLo12I = 24, "R_RISCV_PCREL_LO12_I", 4, K::PC_RELATIVE;
+    PcrelLo12S = 25, "R_RISCV_PCREL_LO12_S", 4, K::PC_RELATIVE;
+    Hi20 = 26, "R_RISCV_HI20", 4, K::empty();
+    Lo12I = 27, "R_RISCV_LO12_I", 4, K::empty();
+    Lo12S = 28, "R_RISCV_LO12_S", 4, K::empty();
+    TprelHi20 = 29, "R_RISCV_TPREL_HI20", 4, K::TLS;
+    TprelLo12I = 30, "R_RISCV_TPREL_LO12_I", 4, K::TLS;
+    TprelLo12S = 31, "R_RISCV_TPREL_LO12_S", 4, K::TLS;
+    TprelAdd = 32, "R_RISCV_TPREL_ADD", 0, K::TLS;
+    Add8 = 33, "R_RISCV_ADD8", 1, K::empty();
+    Add16 = 34, "R_RISCV_ADD16", 2, K::empty();
+    Add32 = 35, "R_RISCV_ADD32", 4, K::empty();
+    Add64 = 36, "R_RISCV_ADD64", 8, K::empty();
+    Sub8 = 37, "R_RISCV_SUB8", 1, K::empty();
+    Sub16 = 38, "R_RISCV_SUB16", 2, K::empty();
+    Sub32 = 39, "R_RISCV_SUB32", 4, K::empty();
+    Sub64 = 40, "R_RISCV_SUB64", 8, K::empty();
+    GnuVtinherit = 41, "R_RISCV_GNU_VTINHERIT", 0, K::empty();
+    GnuVtentry = 42, "R_RISCV_GNU_VTENTRY", 0, K::empty();
+    Align = 43, "R_RISCV_ALIGN", 0, K::empty();
+    RvcBranch = 44, "R_RISCV_RVC_BRANCH", 2, K::PC_RELATIVE;
+    RvcJump = 45, "R_RISCV_RVC_JUMP", 2, K::PC_RELATIVE;
+    RvcLui = 46, "R_RISCV_RVC_LUI", 2, K::empty();
+    GprelI = 47, "R_RISCV_GPREL_I", 4, K::empty();
+    GprelS = 48, "R_RISCV_GPREL_S", 4, K::empty();
+    TprelI = 49, "R_RISCV_TPREL_I", 4, K::TLS;
+    TprelS = 50, "R_RISCV_TPREL_S", 4, K::TLS;
+    Relax = 51, "R_RISCV_RELAX", 0, K::empty();
+    Sub6 = 52, "R_RISCV_SUB6", 1, K::empty();
+    Set6 = 53, "R_RISCV_SET6", 1, K::empty();
+    Set8 = 54, "R_RISCV_SET8", 1, K::empty();
+    Set16 = 55, "R_RISCV_SET16", 2, K::empty();
+    Set32 = 56, "R_RISCV_SET32", 4, K::empty();
+    R32Pcrel = 57, "R_RISCV_32_PCREL", 4, K::PC_RELATIVE;
+    Irelative = 58, "R_RISCV_IRELATIVE", 0, K::RELATIVE;
+    Plt32 = 59, "R_RISCV_PLT32", 4, K::PC_RELATIVE | K::PLT;
+    SetUleb128 = 60, "R_RISCV_SET_ULEB128", 0, K::empty();
+    SubUleb128 = 61, "R_RISCV_SUB_ULEB128", 0, K::empty();
+    TlsdescHi20 = 62, "R_RISCV_TLSDESC_HI20", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescLoadLo12 = 63, "R_RISCV_TLSDESC_LOAD_LO12", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescAddLo12 = 64, "R_RISCV_TLSDESC_ADD_LO12", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescCall = 65, "R_RISCV_TLSDESC_CALL", 0, K::GOT | K::TLS;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    /// Only data relocations are supported, RISC-V objects use explicit addends for instructions.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::Add8 | R::Sub8 | R::Set8 => Some(AddendEncoding::Data(1)),
+            R::Add16 | R::Sub16 | R::Set16 => Some(AddendEncoding::Data(2)),
+            R::R32
+            | R::TlsDtpmod32
+            | R::TlsDtprel32
+            | R::TlsTprel32
+            | R::Add32
+            | R::Sub32
+            | R::Set32
+            | R::R32Pcrel
+            | R::Plt32 => Some(AddendEncoding::Data(4)),
+            R::R64 | R::TlsDtpmod64 | R::TlsDtprel64 | R::TlsTprel64 | R::Add64 | R::Sub64 => {
+                Some(AddendEncoding::Data(8))
+            }
+            R::Relative | R::JumpSlot | R::Irelative => Some(AddendEncoding::Word),
+            _ => None,
+        }
+    }
+}
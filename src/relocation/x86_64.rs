@@ -0,0 +1,77 @@
+use super::{AddendEncoding, RelocationKind as K};
+
+relocation_types! {
+    /// Relocation types for `Machine::X86_64`.
+    None = 0, "R_X86_64_NONE", 0, K::empty();
+    R64 = 1, "R_X86_64_64", 8, K::empty();
+    Pc32 = 2, "R_X86_64_PC32", 4, K::PC_RELATIVE;
+    Got32 = 3, "R_X86_64_GOT32", 4, K::GOT;
+    Plt32 = 4, "R_X86_64_PLT32", 4, K::PC_RELATIVE | K::PLT;
+    Copy = 5, "R_X86_64_COPY", 0, K::empty();
+    GlobDat = 6, "R_X86_64_GLOB_DAT", 8, K::GOT;
+    JumpSlot = 7, "R_X86_64_JUMP_SLOT", 8, K::PLT;
+    Relative = 8, "R_X86_64_RELATIVE", 8, K::RELATIVE;
+    Gotpcrel = 9, "R_X86_64_GOTPCREL", 4, K::PC_RELATIVE | K::GOT;
+    R32 = 10, "R_X86_64_32", 4, K::empty();
+    R32s = 11, "R_X86_64_32S", 4, K::empty();
+    R16 = 12, "R_X86_64_16", 2, K::empty();
+    Pc16 = 13, "R_X86_64_PC16", 2, K::PC_RELATIVE;
+    R8 = 14, "R_X86_64_8", 1, K::empty();
+    Pc8 = 15, "R_X86_64_PC8", 1, K::PC_RELATIVE;
+    Dtpmod64 = 16, "R_X86_64_DTPMOD64", 8, K::TLS;
+    Dtpoff64 = 17, "R_X86_64_DTPOFF64", 8, K::TLS;
+    Tpoff64 = 18, "R_X86_64_TPOFF64", 8, K::TLS;
+    Tlsgd = 19, "R_X86_64_TLSGD", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    Tlsld = 20, "R_X86_64_TLSLD", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    Dtpoff32 = 21, "R_X86_64_DTPOFF32", 4, K::TLS;
+    Gottpoff = 22, "R_X86_64_GOTTPOFF", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    Tpoff32 = 23, "R_X86_64_TPOFF32", 4, K::TLS;
+    Pc64 = 24, "R_X86_64_PC64", 8, K::PC_RELATIVE;
+    Gotoff64 = 25, "R_X86_64_GOTOFF64", 8, K::GOT;
+    Gotpc32 = 26, "R_X86_64_GOTPC32", 4, K::PC_RELATIVE | K::GOT;
+    Got64 = 27, "R_X86_64_GOT64", 8, K::GOT;
+    Gotpcrel64 = 28, "R_X86_64_GOTPCREL64", 8, K::PC_RELATIVE | K::GOT;
+    Gotpc64 = 29, "R_X86_64_GOTPC64", 8, K::PC_RELATIVE | K::GOT;
+    Gotplt64 = 30, "R_X86_64_GOTPLT64", 8, K::GOT | K::PLT;
+    Pltoff64 = 31, "R_X86_64_PLTOFF64", 8, K::PLT;
+    Size32 = 32, "R_X86_64_SIZE32", 4, K::empty();
+    Size64 = 33, "R_X86_64_SIZE64", 8, K::empty();
+    Gotpc32Tlsdesc = 34, "R_X86_64_GOTPC32_TLSDESC", 4, K::PC_RELATIVE | K::GOT | K::TLS;
+    TlsdescCall = 35, "R_X86_64_TLSDESC_CALL", 0, K::GOT | K::TLS;
+    Tlsdesc = 36, "R_X86_64_TLSDESC", 16, K::GOT | K::TLS;
+    Irelative = 37, "R_X86_64_IRELATIVE", 8, K::RELATIVE;
+    Relative64 = 38, "R_X86_64_RELATIVE64", 8, K::RELATIVE;
+    Gotpcrelx = 41, "R_X86_64_GOTPCRELX", 4, K::PC_RELATIVE | K::GOT;
+    RexGotpcrelx = 42, "R_X86_64_REX_GOTPCRELX", 4, K::PC_RELATIVE | K::GOT;
+}
+
+impl RelocationType {
+    /// Returns how the implicit addend of this type is stored at the relocated location, or `None` if that isn't
+    /// supported.
+    pub fn addend_encoding(&self) -> Option<AddendEncoding> {
+        use RelocationType as R;
+        match self {
+            R::None | R::Copy | R::TlsdescCall | R::Tlsdesc | R::Unknown(_) => None,
+            R::R8 | R::Pc8 => Some(AddendEncoding::Data(1)),
+            R::R16 | R::Pc16 => Some(AddendEncoding::Data(2)),
+            R::R64
+            | R::GlobDat
+            | R::JumpSlot
+            | R::Relative
+            | R::Dtpmod64
+            | R::Dtpoff64
+            | R::Tpoff64
+            | R::Pc64
+            | R::Gotoff64
+            | R::Got64
+            | R::Gotpcrel64
+            | R::Gotpc64
+            | R::Gotplt64
+            | R::Pltoff64
+            | R::Size64
+            | R::Irelative
+            | R::Relative64 => Some(AddendEncoding::Data(8)),
+            _ => Some(AddendEncoding::Data(4)),
+        }
+    }
+}
@@ -1,8 +1,11 @@
+use std::fmt;
 use std::io::{Read, Write};
 
-use crate::object::{Class, Endianness};
+use bitflags::bitflags;
+
+use crate::error::{Error, Result};
+use crate::object::{Class, Endianness, Object};
 use crate::util::ReadExt;
-use crate::util::Result;
 use crate::util::WriteExt;
 
 pub mod shtype {
@@ -44,9 +47,126 @@ pub mod shtype {
     pub const SHT_NUM: u32 = 0x13;
     /// Start OS-specific.
     pub const SHT_LOOS: u32 = 0x60000000;
+    /// Object attributes
+    pub const SHT_GNU_ATTRIBUTES: u32 = 0x6FFFFFF5;
+    /// GNU-style hash table
+    pub const SHT_GNU_HASH: u32 = 0x6FFFFFF6;
+    /// Prelink library list
+    pub const SHT_GNU_LIBLIST: u32 = 0x6FFFFFF7;
+    /// Checksum for DSO content
+    pub const SHT_CHECKSUM: u32 = 0x6FFFFFF8;
+    /// Version definitions
+    pub const SHT_GNU_VERDEF: u32 = 0x6FFFFFFD;
+    /// Version needs
+    pub const SHT_GNU_VERNEED: u32 = 0x6FFFFFFE;
+    /// Version of each dynamic symbol
+    pub const SHT_GNU_VERSYM: u32 = 0x6FFFFFFF;
+    /// End OS-specific.
+    pub const SHT_HIOS: u32 = 0x6FFFFFFF;
 }
 
-#[derive(Debug, Clone)]
+/// Special section indices.
+pub mod shn {
+    /// Undefined, missing or irrelevant section reference
+    pub const SHN_UNDEF: u16 = 0x0;
+    /// Start of the reserved range
+    pub const SHN_LORESERVE: u16 = 0xFF00;
+    /// Start of processor-specific indices
+    pub const SHN_LOPROC: u16 = 0xFF00;
+    /// End of processor-specific indices
+    pub const SHN_HIPROC: u16 = 0xFF1F;
+    /// Start of OS-specific indices
+    pub const SHN_LOOS: u16 = 0xFF20;
+    /// End of OS-specific indices
+    pub const SHN_HIOS: u16 = 0xFF3F;
+    /// Absolute values that are not affected by relocation
+    pub const SHN_ABS: u16 = 0xFFF1;
+    /// Common symbols that are not allocated yet
+    pub const SHN_COMMON: u16 = 0xFFF2;
+    /// The actual index is stored in the `SHT_SYMTAB_SHNDX` section
+    pub const SHN_XINDEX: u16 = 0xFFFF;
+    /// End of the reserved range
+    pub const SHN_HIRESERVE: u16 = 0xFFFF;
+}
+
+bitflags! {
+    /// Attributes of a section, stored in `sh_flags`. Unknown bits are kept as they are.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct SectionFlags: u64 {
+        /// Writable at runtime
+        const SHF_WRITE = 0x1;
+        /// Occupies memory during execution
+        const SHF_ALLOC = 0x2;
+        /// Contains executable instructions
+        const SHF_EXECINSTR = 0x4;
+        /// Might be merged
+        const SHF_MERGE = 0x10;
+        /// Contains null-terminated strings
+        const SHF_STRINGS = 0x20;
+        /// `sh_info` contains a section index
+        const SHF_INFO_LINK = 0x40;
+        /// Preserve order after combining
+        const SHF_LINK_ORDER = 0x80;
+        /// Non-standard OS specific handling required
+        const SHF_OS_NONCONFORMING = 0x100;
+        /// Member of a section group
+        const SHF_GROUP = 0x200;
+        /// Contains thread-local data
+        const SHF_TLS = 0x400;
+        /// Contains compressed data
+        const SHF_COMPRESSED = 0x800;
+        /// Must not be garbage collected by the linker (GNU extension)
+        const SHF_GNU_RETAIN = 0x200000;
+        /// Excluded from the output unless referenced or allocated
+        const SHF_EXCLUDE = 0x80000000;
+        /// OS specific
+        const SHF_MASKOS = 0x0FF00000;
+        /// Processor specific
+        const SHF_MASKPROC = 0xF0000000;
+
+        const _ = !0;
+    }
+}
+
+impl fmt::Display for SectionFlags {
+    /// Formats the flags like readelf does, e.g. `WAX`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letters = [
+            (Self::SHF_WRITE, 'W'),
+            (Self::SHF_ALLOC, 'A'),
+            (Self::SHF_EXECINSTR, 'X'),
+            (Self::SHF_MERGE, 'M'),
+            (Self::SHF_STRINGS, 'S'),
+            (Self::SHF_INFO_LINK, 'I'),
+            (Self::SHF_LINK_ORDER, 'L'),
+            (Self::SHF_OS_NONCONFORMING, 'O'),
+            (Self::SHF_GROUP, 'G'),
+            (Self::SHF_TLS, 'T'),
+            (Self::SHF_COMPRESSED, 'C'),
+            (Self::SHF_GNU_RETAIN, 'R'),
+            (Self::SHF_EXCLUDE, 'E'),
+        ];
+        let mut rest = *self;
+        for (flag, letter) in letters {
+            if self.contains(flag) {
+                write!(f, "{}", letter)?;
+                rest.remove(flag);
+            }
+        }
+        if rest.intersects(Self::SHF_MASKOS) {
+            write!(f, "o")?;
+        }
+        if rest.intersects(Self::SHF_MASKPROC) {
+            write!(f, "p")?;
+        }
+        if !(rest - Self::SHF_MASKOS - Self::SHF_MASKPROC).is_empty() {
+            write!(f, "x")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct SectionHeader {
     /// An offset to a string in the .shstrtab section that represents the name of this section.
     pub sh_name: u32,
@@ -71,6 +191,24 @@ pub struct SectionHeader {
 }
 
 impl SectionHeader {
+    /// Returns the attributes of the section.
+    pub fn flags(&self) -> SectionFlags {
+        SectionFlags::from_bits_retain(self.sh_flags)
+    }
+
+    /// Sets the attributes of the section.
+    pub fn set_flags(&mut self, flags: SectionFlags) {
+        self.sh_flags = flags.bits();
+    }
+
+    /// Returns the size of a section header in bytes.
+    pub fn size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 40,
+            Class::Bits64 => 64,
+        }
+    }
+
     pub fn read(class: &Class, endian: &Endianness, mut buf: impl Read) -> Result<Self> {
         let prog = match class {
             Class::Bits32 => Self {
@@ -135,15 +273,72 @@ impl SectionHeader {
 
 #[derive(Debug, Clone)]
 pub struct Section {
+    /// The name of this section. Names don't have to be unique.
+    pub name: String,
     pub header: SectionHeader,
     pub body: Vec<u8>,
 }
 
 impl Section {
-    pub fn new(header: SectionHeader) -> Self {
+    pub fn new(name: &str, header: SectionHeader) -> Self {
         Self {
-            header: header,
+            name: name.to_string(),
+            header,
             body: vec![],
         }
     }
+
+    /// Returns the range (offset, size) this section occupies in the file.
+    pub fn file_range(&self) -> (u64, u64) {
+        match self.header.sh_type {
+            shtype::SHT_NOBITS | shtype::SHT_NULL => (self.header.sh_offset, 0),
+            _ => (self.header.sh_offset, self.header.sh_size),
+        }
+    }
+
+    /// Returns the range (address, size) this section occupies in memory.
+    /// Sections that aren't loaded and TLS sections without data don't occupy any memory.
+    pub fn mem_range(&self) -> (u64, u64) {
+        let h = &self.header;
+        let tbss = h.sh_type == shtype::SHT_NOBITS && h.flags().contains(SectionFlags::SHF_TLS);
+        match h.flags().contains(SectionFlags::SHF_ALLOC) && !tbss {
+            true => (h.sh_addr, h.sh_size),
+            false => (h.sh_addr, 0),
+        }
+    }
+
+    /// Checks if the section holds debug information that isn't needed at runtime, like `.debug_info`.
+    pub fn is_debug(&self) -> bool {
+        const PREFIXES: [&str; 4] = [".debug", ".zdebug", ".gnu.debuglto_", ".stab"];
+        !self.header.flags().contains(SectionFlags::SHF_ALLOC)
+            && (PREFIXES.iter().any(|x| self.name.starts_with(x))
+                || matches!(self.name.as_str(), ".line" | ".gdb_index"))
+    }
+}
+
+/// Identifies a section either by its index in the section header table or by its name.
+pub trait SectionId {
+    /// Returns the index of the section in `obj`.
+    fn section_idx(&self, obj: &Object) -> Result<usize>;
+}
+
+impl SectionId for usize {
+    fn section_idx(&self, obj: &Object) -> Result<usize> {
+        match *self < obj.sections.len() {
+            true => Ok(*self),
+            false => Err(Error::OutOfRange {
+                offset: *self as u64,
+                size: 1,
+                limit: obj.sections.len() as u64,
+            }),
+        }
+    }
+}
+
+impl SectionId for &str {
+    fn section_idx(&self, obj: &Object) -> Result<usize> {
+        obj.find_section_idx(self)
+            .map(|x| x as usize)
+            .ok_or_else(|| Error::MissingSection(self.to_string()))
+    }
 }
@@ -1,10 +1,14 @@
+use std::fmt;
 use std::io::Read;
 use std::io::Write;
 
+use bitflags::bitflags;
+
+use crate::error::Result;
 use crate::object::Class;
 use crate::object::Endianness;
+use crate::section::{shtype, Section, SectionFlags};
 use crate::util::ReadExt;
-use crate::util::Result;
 use crate::util::WriteExt;
 
 pub mod ptype {
@@ -32,7 +36,40 @@ pub mod ptype {
     pub const PT_HIPROC: u32 = 0x7FFFFFFF;
 }
 
-#[derive(Debug, Clone)]
+bitflags! {
+    /// Permissions of a segment, stored in `p_flags`. Unknown bits are kept as they are.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct SegmentFlags: u32 {
+        /// Executable
+        const PF_X = 0x1;
+        /// Writable
+        const PF_W = 0x2;
+        /// Readable
+        const PF_R = 0x4;
+        /// OS specific
+        const PF_MASKOS = 0x0FF00000;
+        /// Processor specific
+        const PF_MASKPROC = 0xF0000000;
+
+        const _ = !0;
+    }
+}
+
+impl fmt::Display for SegmentFlags {
+    /// Formats the flags like readelf does, e.g. `R E`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let letter = |flag, x| if self.contains(flag) { x } else { ' ' };
+        write!(
+            f,
+            "{}{}{}",
+            letter(Self::PF_R, 'R'),
+            letter(Self::PF_W, 'W'),
+            letter(Self::PF_X, 'E')
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct ProgramHeader {
     pub p_type: u32,
     pub p_flags: u32,
@@ -45,6 +82,24 @@ pub struct ProgramHeader {
 }
 
 impl ProgramHeader {
+    /// Returns the permissions of the segment.
+    pub fn flags(&self) -> SegmentFlags {
+        SegmentFlags::from_bits_retain(self.p_flags)
+    }
+
+    /// Sets the permissions of the segment.
+    pub fn set_flags(&mut self, flags: SegmentFlags) {
+        self.p_flags = flags.bits();
+    }
+
+    /// Returns the size of a program header in bytes.
+    pub fn size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 32,
+            Class::Bits64 => 56,
+        }
+    }
+
     pub fn read(class: &Class, endian: &Endianness, mut buf: impl Read) -> Result<Self> {
         let prog = match class {
             Class::Bits32 => Self {
@@ -106,6 +161,27 @@ pub struct Segment {
 
 impl Segment {
     pub fn new(header: ProgramHeader) -> Self {
-        Self { header: header }
+        Self { header }
+    }
+
+    /// Checks if a section is mapped by this segment.
+    pub fn contains(&self, section: &Section) -> bool {
+        let h = &section.header;
+        if !h.flags().contains(SectionFlags::SHF_ALLOC) {
+            return false;
+        }
+        // TLS sections without data only take up space in the TLS template.
+        let size = match h.sh_type == shtype::SHT_NOBITS
+            && h.flags().contains(SectionFlags::SHF_TLS)
+            && self.header.p_type != ptype::PT_TLS
+        {
+            true => 0,
+            false => h.sh_size,
+        };
+        let start = self.header.p_vaddr;
+        let end = start + self.header.p_memsz;
+        h.sh_addr >= start
+            && h.sh_addr + size <= end
+            && (size != 0 || h.sh_addr < end || (self.header.p_memsz == 0 && h.sh_addr == start))
     }
 }
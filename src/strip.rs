@@ -0,0 +1,171 @@
+use std::collections::HashSet;
+
+use crate::{
+    error::Result,
+    object::{Object, ObjectType},
+    section::{shn, shtype, Section},
+    symbol::{Symbol, SymbolBinding, SymbolType},
+};
+
+/// How much [`Object::strip`] removes, like the modes of GNU strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StripMode {
+    /// Removes all symbols and debug sections, like `--strip-all`.
+    #[default]
+    All,
+    /// Removes debug sections and the symbols that only debuggers need, like `--strip-debug`.
+    Debug,
+    /// Removes debug sections and all symbols that linkers don't need, like `--strip-unneeded`.
+    Unneeded,
+    /// Only removes the sections and symbols that are named in the remove lists.
+    Listed,
+}
+
+/// Selects what [`Object::strip`] removes. The lists name sections and symbols.
+#[derive(Debug, Clone, Default)]
+pub struct StripOptions {
+    pub mode: StripMode,
+    /// Symbols that are kept regardless of the mode, like `--keep-symbol`.
+    pub keep_symbols: Vec<String>,
+    /// Symbols that are removed in addition to the mode, like `--strip-symbol`.
+    pub remove_symbols: Vec<String>,
+    /// Sections that are kept regardless of the mode, like `--keep-section`.
+    pub keep_sections: Vec<String>,
+    /// Sections that are removed in addition to the mode, like `--remove-section`.
+    pub remove_sections: Vec<String>,
+}
+
+impl StripOptions {
+    pub fn new(mode: StripMode) -> Self {
+        Self {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    fn removes_section(&self, section: &Section) -> bool {
+        if self.keep_sections.contains(&section.name) {
+            return false;
+        }
+        self.remove_sections.contains(&section.name)
+            || (self.mode != StripMode::Listed && section.is_debug())
+    }
+
+    fn removes_symbol(&self, symbol: &Symbol, relocatable: bool) -> bool {
+        if self.keep_symbols.contains(&symbol.name) {
+            return false;
+        }
+        if self.remove_symbols.contains(&symbol.name) {
+            return true;
+        }
+        match self.mode {
+            StripMode::All => true,
+            StripMode::Debug => symbol.kind() == SymbolType::File,
+            // Only relocatable objects are linked against their symbol table.
+            StripMode::Unneeded => !relocatable || symbol.binding() == SymbolBinding::Local,
+            StripMode::Listed => false,
+        }
+    }
+}
+
+impl Object {
+    /// Removes symbols and sections like `strip` does, and drops their contents from the file.
+    ///
+    /// Symbols that relocations or section groups refer to are always kept. Symbols defined in removed sections
+    /// are removed with them. Local symbols stay ahead of all others, and the string tables are rebuilt so that
+    /// the names of removed symbols and sections disappear as well. If no symbols are left after `--strip-all`
+    /// or `--strip-unneeded`, the symbol table is removed.
+    pub fn strip(&mut self, options: &StripOptions) -> Result<()> {
+        let in_removed: Vec<_> = self
+            .symbols
+            .iter()
+            .map(|x| {
+                x.sym_shndx != shn::SHN_UNDEF
+                    && x.sym_shndx < shn::SHN_LORESERVE
+                    && self
+                        .sections
+                        .get(x.sym_shndx as usize)
+                        .is_some_and(|x| options.removes_section(x))
+            })
+            .collect();
+        while let Some(idx) =
+            (1..self.sections.len()).find(|&i| options.removes_section(&self.sections[i]))
+        {
+            self.remove_section(idx)?;
+        }
+
+        // The symbols are gone already if the symbol table was removed.
+        if self.symbols.len() == in_removed.len() {
+            let needed = self.needed_symbols()?;
+            let relocatable = self.header.object_type() == ObjectType::Rel;
+            let mut order: Vec<_> = (0..self.symbols.len())
+                .filter(|&i| {
+                    i == 0
+                        || needed.contains(&i)
+                        || (!in_removed[i]
+                            && !options.removes_symbol(&self.symbols[i], relocatable))
+                })
+                .collect();
+            order.sort_by_key(|&i| self.symbols[i].binding() != SymbolBinding::Local);
+            self.select_symbols(&order)?;
+        }
+
+        let symtab = (0..self.sections.len())
+            .find(|&i| self.sections[i].header.sh_type == shtype::SHT_SYMTAB);
+        if let Some(symtab) = symtab {
+            let strtab = self.linked_strtab(symtab).ok();
+            let unused = self.symbols.len() <= 1
+                && matches!(options.mode, StripMode::All | StripMode::Unneeded);
+            if unused {
+                self.remove_section(symtab)?;
+                self.strtab = None;
+                // The string table might hold the section names as well.
+                if let Some(x) = strtab.map(|x| if x > symtab { x - 1 } else { x }) {
+                    if x != self.header.e_shstrndx as usize {
+                        self.remove_section(x)?;
+                    }
+                }
+            } else if let Some(x) = strtab {
+                self.sections[x].body = vec![0];
+            }
+        }
+        if let Some(x) = self.sections.get_mut(self.header.e_shstrndx as usize) {
+            if self.header.e_shstrndx != 0 {
+                x.body = vec![0];
+            }
+        }
+
+        // The names are added back before the file is packed, so the string tables don't have to move.
+        self.update()?;
+        self.pack();
+        Ok(())
+    }
+
+    /// Returns the indices of all symbols that relocations or section groups refer to.
+    fn needed_symbols(&self) -> Result<HashSet<usize>> {
+        let Some(symtab) = (0..self.sections.len())
+            .find(|&i| self.sections[i].header.sh_type == shtype::SHT_SYMTAB)
+        else {
+            return Ok(HashSet::new());
+        };
+        let mut result = HashSet::new();
+        for i in 0..self.sections.len() {
+            let h = &self.sections[i].header;
+            if h.sh_link as usize != symtab {
+                continue;
+            }
+            match h.sh_type {
+                shtype::SHT_GROUP => {
+                    result.insert(h.sh_info as usize);
+                }
+                shtype::SHT_REL | shtype::SHT_RELA => result.extend(
+                    self.relocations(i)?
+                        .into_iter()
+                        .map(|x| x.sym_index as usize),
+                ),
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+}
@@ -1,11 +1,158 @@
+use crate::error::{Error, Result};
+use crate::hash::{gnu_hash, GnuHashTable, SysvHashTable};
 use crate::object::{Class, Endianness, Object};
+use crate::section::{shn, shtype};
 use crate::util::ReadExt;
-use crate::util::Result;
 use crate::util::WriteExt;
-use std::io::{Read, Write};
+use crate::util::{insert_cstr, read_cstr_at};
+use crate::version::{VER_NDX_GLOBAL, VER_NDX_LOCAL};
+use std::io::{Cursor, Read, Write};
 
-#[derive(Debug, Clone)]
+/// The binding of a symbol, stored in the upper four bits of `st_info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolBinding {
+    /// Not visible outside of the object
+    Local,
+    /// Visible to all objects
+    Global,
+    /// Like global, but with lower precedence
+    Weak,
+    /// Unique in the whole process (GNU extension)
+    GnuUnique,
+    /// Operating system specific, between 11 and 12
+    Os(u8),
+    /// Processor specific, between 13 and 15
+    Processor(u8),
+    /// Any other value
+    Unknown(u8),
+}
+
+impl From<u8> for SymbolBinding {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SymbolBinding::Local,
+            1 => SymbolBinding::Global,
+            2 => SymbolBinding::Weak,
+            10 => SymbolBinding::GnuUnique,
+            11..=12 => SymbolBinding::Os(value),
+            13..=15 => SymbolBinding::Processor(value),
+            x => SymbolBinding::Unknown(x),
+        }
+    }
+}
+
+impl From<SymbolBinding> for u8 {
+    fn from(value: SymbolBinding) -> Self {
+        match value {
+            SymbolBinding::Local => 0,
+            SymbolBinding::Global => 1,
+            SymbolBinding::Weak => 2,
+            SymbolBinding::GnuUnique => 10,
+            SymbolBinding::Os(x) | SymbolBinding::Processor(x) | SymbolBinding::Unknown(x) => x,
+        }
+    }
+}
+
+/// The type of a symbol, stored in the lower four bits of `st_info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolType {
+    /// Type is not specified
+    NoType,
+    /// Data object
+    Object,
+    /// Function or other executable code
+    Func,
+    /// A section, used for relocations
+    Section,
+    /// The name of the source file
+    File,
+    /// Uninitialized common block
+    Common,
+    /// Thread-local storage
+    Tls,
+    /// Function that returns the address of the actual implementation (GNU extension)
+    GnuIfunc,
+    /// Operating system specific, between 11 and 12
+    Os(u8),
+    /// Processor specific, between 13 and 15
+    Processor(u8),
+    /// Any other value
+    Unknown(u8),
+}
+
+impl From<u8> for SymbolType {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SymbolType::NoType,
+            1 => SymbolType::Object,
+            2 => SymbolType::Func,
+            3 => SymbolType::Section,
+            4 => SymbolType::File,
+            5 => SymbolType::Common,
+            6 => SymbolType::Tls,
+            10 => SymbolType::GnuIfunc,
+            11..=12 => SymbolType::Os(value),
+            13..=15 => SymbolType::Processor(value),
+            x => SymbolType::Unknown(x),
+        }
+    }
+}
+
+impl From<SymbolType> for u8 {
+    fn from(value: SymbolType) -> Self {
+        match value {
+            SymbolType::NoType => 0,
+            SymbolType::Object => 1,
+            SymbolType::Func => 2,
+            SymbolType::Section => 3,
+            SymbolType::File => 4,
+            SymbolType::Common => 5,
+            SymbolType::Tls => 6,
+            SymbolType::GnuIfunc => 10,
+            SymbolType::Os(x) | SymbolType::Processor(x) | SymbolType::Unknown(x) => x,
+        }
+    }
+}
+
+/// The visibility of a symbol, stored in the lower two bits of `st_other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolVisibility {
+    /// Determined by the binding
+    Default,
+    /// Processor specific hidden class
+    Internal,
+    /// Not visible to other components
+    Hidden,
+    /// Visible to other components, but not preemptible
+    Protected,
+}
+
+impl From<u8> for SymbolVisibility {
+    fn from(value: u8) -> Self {
+        match value & 0x3 {
+            0 => SymbolVisibility::Default,
+            1 => SymbolVisibility::Internal,
+            2 => SymbolVisibility::Hidden,
+            _ => SymbolVisibility::Protected,
+        }
+    }
+}
+
+impl From<SymbolVisibility> for u8 {
+    fn from(value: SymbolVisibility) -> Self {
+        match value {
+            SymbolVisibility::Default => 0,
+            SymbolVisibility::Internal => 1,
+            SymbolVisibility::Hidden => 2,
+            SymbolVisibility::Protected => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Symbol {
+    /// The name of this symbol. Names don't have to be unique.
+    pub name: String,
     pub sym_name: u32,
     pub sym_info: u8,
     pub sym_other: u8,
@@ -15,17 +162,60 @@ pub struct Symbol {
 }
 
 impl Symbol {
+    /// Looks up the name of a symbol from the symbol table in the string table.
+    /// Names of dynamic symbols are resolved by `Object::dynamic_symbols`.
     pub fn get_name(&self, obj: &Object) -> Result<String> {
-        let strtab = obj
-            .find_section(".strtab")
-            .expect("Unable to get the name for a symbol: Section \".strtab\" was not present!");
-        let mut name = &strtab.body[self.sym_name as usize..];
-        Ok(name.read_cstr()?)
+        let symtab = obj
+            .sections
+            .iter()
+            .position(|x| x.header.sh_type == shtype::SHT_SYMTAB)
+            .ok_or_else(|| Error::MissingSection(".symtab".into()))?;
+        let strtab = obj.linked_strtab(symtab)?;
+        read_cstr_at(&obj.sections[strtab].body, self.sym_name)
+    }
+
+    /// Returns the binding of this symbol.
+    pub fn binding(&self) -> SymbolBinding {
+        (self.sym_info >> 4).into()
+    }
+
+    /// Sets the binding of this symbol. Values above 15 are truncated.
+    pub fn set_binding(&mut self, binding: SymbolBinding) {
+        self.sym_info = (u8::from(binding) << 4) | (self.sym_info & 0xF);
+    }
+
+    /// Returns the type of this symbol.
+    pub fn kind(&self) -> SymbolType {
+        (self.sym_info & 0xF).into()
+    }
+
+    /// Sets the type of this symbol. Values above 15 are truncated.
+    pub fn set_kind(&mut self, kind: SymbolType) {
+        self.sym_info = (self.sym_info & 0xF0) | (u8::from(kind) & 0xF);
+    }
+
+    /// Returns the visibility of this symbol.
+    pub fn visibility(&self) -> SymbolVisibility {
+        self.sym_other.into()
+    }
+
+    /// Sets the visibility of this symbol. The other bits of `sym_other` are kept.
+    pub fn set_visibility(&mut self, visibility: SymbolVisibility) {
+        self.sym_other = (self.sym_other & !0x3) | u8::from(visibility);
+    }
+
+    /// Returns the size of a symbol table entry in bytes.
+    pub fn size(class: &Class) -> u64 {
+        match class {
+            Class::Bits32 => 16,
+            Class::Bits64 => 24,
+        }
     }
 
     pub fn read(class: &Class, endian: &Endianness, mut buf: impl Read) -> Result<Self> {
         let prog = match class {
             Class::Bits32 => Self {
+                name: String::new(),
                 sym_name: buf.read_u32(endian)?,
                 sym_info: buf.read_u8()?,
                 sym_other: buf.read_u8()?,
@@ -34,6 +224,7 @@ impl Symbol {
                 sym_size: buf.read_u32(endian)? as u64,
             },
             Class::Bits64 => Self {
+                name: String::new(),
                 sym_name: buf.read_u32(endian)?,
                 sym_info: buf.read_u8()?,
                 sym_other: buf.read_u8()?,
@@ -67,3 +258,234 @@ impl Symbol {
         Ok(written)
     }
 }
+
+impl Object {
+    /// Returns the index of the string table that holds the names of a symbol table.
+    pub(crate) fn linked_strtab(&self, symtab: usize) -> Result<usize> {
+        let h = &self.sections[symtab].header;
+        match self.sections.get(h.sh_link as usize) {
+            Some(x) if h.sh_link != 0 && x.header.sh_type == shtype::SHT_STRTAB => {
+                Ok(h.sh_link as usize)
+            }
+            _ => Err(Error::MissingSection(
+                match h.sh_type {
+                    shtype::SHT_DYNSYM => ".dynstr",
+                    _ => ".strtab",
+                }
+                .into(),
+            )),
+        }
+    }
+
+    /// Reads all entries of a symbol table section, with their names from the string table given by `sh_link`.
+    pub(crate) fn read_symbols(&self, symtab: usize) -> Result<Vec<Symbol>> {
+        let class = &self.header.e_ident.ei_class;
+        let section = &self.sections[symtab];
+        let entsize = match section.header.sh_entsize {
+            0 => Symbol::size(class),
+            x => x,
+        };
+        let strtab = &self.sections[self.linked_strtab(symtab)?].body;
+        let mut cursor = Cursor::new(&section.body);
+        let mut symbols = Vec::new();
+        for i in 0..section.body.len() as u64 / entsize {
+            cursor.set_position(i * entsize);
+            let mut sym = Symbol::read(class, &self.header.e_ident.ei_data, &mut cursor)?;
+            sym.name = read_cstr_at(strtab, sym.sym_name)?;
+            symbols.push(sym);
+        }
+        Ok(symbols)
+    }
+
+    /// Reads the dynamic symbol table, with names from its string table.
+    /// Returns no symbols if the object isn't dynamically linked.
+    pub fn dynamic_symbols(&self) -> Result<Vec<Symbol>> {
+        match self.dynsym_idx() {
+            Some(idx) => self.read_symbols(idx),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Replaces the dynamic symbol table.
+    ///
+    /// Symbols are expected to keep their index, so new symbols should be appended. Names are added to the
+    /// dynamic string table, new symbols get the global version in `SHT_GNU_VERSYM` and the hash tables are
+    /// rebuilt. A GNU hash table needs the exported symbols sorted by hash bucket, so symbols may be reordered;
+    /// dynamic relocations are updated accordingly. Loaded sections that grow are moved to a new place in memory.
+    pub fn set_dynamic_symbols(&mut self, symbols: &[Symbol]) -> Result<()> {
+        let idx = self
+            .dynsym_idx()
+            .ok_or_else(|| Error::MissingSection(".dynsym".into()))?;
+        let class = self.header.e_ident.ei_class.clone();
+        let endian = self.header.e_ident.ei_data.clone();
+        let strtab = self.linked_strtab(idx)?;
+        let linked = |sh_type| {
+            (0..self.sections.len()).find(|&i| {
+                let h = &self.sections[i].header;
+                h.sh_type == sh_type && h.sh_link as usize == idx
+            })
+        };
+        let versym_idx = linked(shtype::SHT_GNU_VERSYM);
+        let hash_idx = linked(shtype::SHT_HASH);
+        let gnu_hash_idx = linked(shtype::SHT_GNU_HASH);
+        let mut symbols = symbols.to_vec();
+
+        let mut versym = Vec::new();
+        if versym_idx.is_some() {
+            versym = self.symbol_version_indices()?;
+            let old_len = versym.len();
+            versym.resize(symbols.len(), VER_NDX_GLOBAL);
+            if old_len == 0 {
+                if let Some(x) = versym.first_mut() {
+                    *x = VER_NDX_LOCAL;
+                }
+            }
+        }
+
+        // Hashed symbols come last. These are the ones that were hashed before and all exported symbols.
+        let gnu_table = self.gnu_hash_table()?;
+        let mut symoffset = symbols.len();
+        if let Some(table) = &gnu_table {
+            let old_len = self.sections[idx].body.len() / Symbol::size(&class) as usize;
+            let old_hashed = old_len - table.chains.len().min(old_len);
+            let hashed = |k: usize| {
+                let x = &symbols[k];
+                (k >= old_hashed && k < old_len)
+                    || (x.sym_shndx != shn::SHN_UNDEF && x.binding() != SymbolBinding::Local)
+            };
+
+            let mut order: Vec<_> = (0..symbols.len())
+                .filter(|&k| k == 0 || !hashed(k))
+                .collect();
+            symoffset = order.len();
+            let nbuckets = table.buckets.len().max(1) as u32;
+            let mut exported: Vec<_> = (1..symbols.len()).filter(|&k| hashed(k)).collect();
+            exported.sort_by_key(|&k| gnu_hash(&symbols[k].name) % nbuckets);
+            order.extend(exported);
+            if order.iter().enumerate().any(|(new, &old)| new != old) {
+                self.reorder_dynamic_relocations(idx, &order)?;
+                symbols = order.iter().map(|&k| symbols[k].clone()).collect();
+                if !versym.is_empty() {
+                    versym = order.iter().map(|&k| versym[k]).collect();
+                }
+            }
+        }
+
+        // Names that didn't change keep their offset, the version sections refer to the same table.
+        let mut dynstr = self.sections[strtab].body.clone();
+        for symbol in &mut symbols {
+            if read_cstr_at(&dynstr, symbol.sym_name).ok().as_ref() != Some(&symbol.name) {
+                symbol.sym_name = insert_cstr(&mut dynstr, &symbol.name);
+            }
+        }
+        self.replace_body(strtab, dynstr)?;
+
+        let mut data = Vec::new();
+        for symbol in &symbols {
+            symbol.write(&class, &endian, &mut data)?;
+        }
+        self.replace_body(idx, data)?;
+        self.sections[idx].header.sh_info = symbols
+            .iter()
+            .take_while(|x| x.binding() == SymbolBinding::Local)
+            .count() as u32;
+
+        if versym_idx.is_some() {
+            self.set_symbol_version_indices(&versym)?;
+        }
+
+        let names: Vec<_> = symbols.iter().map(|x| x.name.as_str()).collect();
+        if let Some(i) = hash_idx {
+            let nbucket = self
+                .sysv_hash_table()?
+                .map_or(1, |x| x.buckets.len() as u32);
+            let data = SysvHashTable::build(&names, nbucket).write(&endian)?;
+            self.replace_body(i, data)?;
+        }
+        if let (Some(i), Some(table)) = (gnu_hash_idx, gnu_table) {
+            // The size of the table stays the same. Without hashed symbols, the offset isn't used.
+            let offset = match symoffset < symbols.len() {
+                true => symoffset as u32,
+                false => table.symoffset,
+            };
+            let data = GnuHashTable::build(
+                &names[symoffset..],
+                offset,
+                table.buckets.len() as u32,
+                table.bloom.len() as u32,
+                table.bloom_shift,
+                &class,
+            )
+            .write(&class, &endian)?;
+            self.replace_body(i, data)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the index of the dynamic symbol table.
+    pub(crate) fn dynsym_idx(&self) -> Option<usize> {
+        (0..self.sections.len()).find(|&i| self.sections[i].header.sh_type == shtype::SHT_DYNSYM)
+    }
+
+    /// Updates the relocations that refer to a symbol table after its symbols were reordered.
+    /// The symbol at index `order[i]` moves to index `i`.
+    fn reorder_dynamic_relocations(&mut self, symtab: usize, order: &[usize]) -> Result<()> {
+        let mut map = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            map[old] = new as u32;
+        }
+        self.renumber_symbols(symtab, |x| map.get(x as usize).copied().unwrap_or(x))
+    }
+
+    /// Replaces the symbols of the symbol table with a selection of them: the symbol at index `order[i]` moves to
+    /// index `i`, and symbols that aren't selected are removed. All references are updated, references to removed
+    /// symbols point to the null symbol afterwards.
+    pub(crate) fn select_symbols(&mut self, order: &[usize]) -> Result<()> {
+        let Some(symtab) = (0..self.sections.len())
+            .find(|&i| self.sections[i].header.sh_type == shtype::SHT_SYMTAB)
+        else {
+            return Ok(());
+        };
+        let mut map = vec![0; self.symbols.len()];
+        for (new, &old) in order.iter().enumerate() {
+            map[old] = new as u32;
+        }
+        self.symbols = order.iter().map(|&i| self.symbols[i].clone()).collect();
+        self.renumber_symbols(symtab, |x| map.get(x as usize).copied().unwrap_or(0))?;
+
+        // `sh_info` is the index of the first non-local symbol.
+        self.sections[symtab].header.sh_info = self
+            .symbols
+            .iter()
+            .position(|x| x.binding() != SymbolBinding::Local)
+            .unwrap_or(self.symbols.len()) as u32;
+        Ok(())
+    }
+
+    /// Updates all references into a symbol table after its symbols moved: the symbols of relocations
+    /// and the signatures of section groups. `map` returns the new index of a symbol.
+    pub(crate) fn renumber_symbols(
+        &mut self,
+        symtab: usize,
+        map: impl Fn(u32) -> u32,
+    ) -> Result<()> {
+        for i in 0..self.sections.len() {
+            let h = &mut self.sections[i].header;
+            if h.sh_link as usize != symtab {
+                continue;
+            }
+            match h.sh_type {
+                shtype::SHT_GROUP => h.sh_info = map(h.sh_info),
+                shtype::SHT_REL | shtype::SHT_RELA => {
+                    let mut relocations = self.relocations(i)?;
+                    for rel in &mut relocations {
+                        rel.sym_index = map(rel.sym_index);
+                    }
+                    self.set_relocations(i, &relocations)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -12,5 +12,1535 @@ pub fn test_elf_read() {
     assert_eq!(&bin.sections.len(), &38);
     assert!(&bin.shstrtab.is_some());
     assert!(&bin.strtab.is_some());
-    assert_eq!(&bin.symbols.len(), &40);
+    assert_eq!(&bin.symbols.len(), &42);
+}
+
+#[test]
+pub fn test_elf_read_errors() {
+    let mut cursor = Cursor::new(b"\x7FELG\x02\x01\x01\x00".to_vec());
+    let result = object::Object::read(&mut cursor);
+    assert!(matches!(result, Err(Error::BadMagic(_))));
+
+    let mut cursor = Cursor::new(b"\x7FELF\x03\x01\x01\x00".to_vec());
+    let result = object::Object::read(&mut cursor);
+    assert!(matches!(result, Err(Error::UnsupportedClass(3))));
+
+    let mut cursor = Cursor::new(b"\x7FELF\x02\x01\x01\x00".to_vec());
+    let result = object::Object::read(&mut cursor);
+    assert!(matches!(result, Err(Error::TruncatedHeader { offset: 0 })));
+
+    // Point the section header table past the end of the file.
+    let mut data = include_bytes!("../test/test_exe").to_vec();
+    data[0x28..0x30].copy_from_slice(&0xFFFF_0000u64.to_le_bytes());
+    let result = object::Object::read(&mut Cursor::new(data));
+    assert!(matches!(result, Err(Error::OutOfRange { .. })));
+}
+
+#[test]
+pub fn test_elf_read_stripped() {
+    let mut cursor = Cursor::new(include_bytes!("../test/test_exe_stripped"));
+    let mut bin = object::Object::read(&mut cursor).unwrap();
+
+    assert!(bin.find_section(".symtab").is_none());
+    assert!(bin.strtab.is_none());
+    assert!(bin.symbols.is_empty());
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    output.set_position(0);
+    let bin2 = object::Object::read(&mut output).unwrap();
+    assert_eq!(bin.sections.len(), bin2.sections.len());
+    assert!(bin2.symbols.is_empty());
+    for (a, b) in bin.sections.iter().zip(bin2.sections.iter()) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.body, b.body);
+    }
+
+    // Sections without a name table are still readable.
+    let mut data = include_bytes!("../test/test_exe_stripped").to_vec();
+    data[0x3E..0x40].copy_from_slice(&0u16.to_le_bytes());
+    let bin = object::Object::read(&mut Cursor::new(data)).unwrap();
+    assert!(bin.shstrtab.is_none());
+    assert_eq!(bin.sections.len(), 29);
+    assert!(bin.sections.iter().all(|x| x.name.is_empty()));
+}
+
+#[test]
+pub fn test_elf_read_duplicate_sections() {
+    let mut cursor = Cursor::new(include_bytes!("../test/test_obj.o"));
+    let bin = object::Object::read(&mut cursor).unwrap();
+
+    assert_eq!(bin.sections.len(), 30);
+    let groups = bin.find_sections(".group");
+    assert_eq!(groups.len(), 2);
+    assert_eq!(bin.find_section(".group").unwrap().body, groups[0].body);
+    assert_ne!(groups[0].body, groups[1].body);
+    assert_eq!(bin.find_section_idx(".symtab"), Some(27));
+    assert_eq!(bin.sections[27].header.sh_link, 28);
+}
+
+#[test]
+pub fn test_elf_symbols() {
+    let mut cursor = Cursor::new(include_bytes!("../test/test_obj.o"));
+    let mut bin = object::Object::read(&mut cursor).unwrap();
+
+    assert_eq!(bin.symbols.len(), 29);
+    assert_eq!(bin.find_symbols("_ZL6helperi").len(), 2);
+    assert_eq!(bin.find_symbol_idx("_Z6b_funci"), Some(25));
+    assert_eq!(bin.symbol_index()[""].len(), 20);
+    assert_eq!(
+        bin.symbols[23].get_name(&bin).unwrap(),
+        bin.symbols[23].name
+    );
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    output.set_position(0);
+    let bin2 = object::Object::read(&mut output).unwrap();
+    assert_eq!(bin2.symbols.len(), 29);
+    for (a, b) in bin.symbols.iter().zip(bin2.symbols.iter()) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.sym_shndx, b.sym_shndx);
+        assert_eq!(a.sym_value, b.sym_value);
+    }
+}
+
+#[test]
+pub fn test_elf_roundtrip() {
+    let inputs: [&[u8]; 3] = [
+        include_bytes!("../test/test_exe"),
+        include_bytes!("../test/test_exe_stripped"),
+        include_bytes!("../test/test_obj.o"),
+    ];
+    for input in inputs {
+        let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+        let mut output = Cursor::new(Vec::new());
+        bin.write(&mut output).unwrap();
+        assert_eq!(output.get_ref().as_slice(), input);
+    }
+}
+
+#[test]
+pub fn test_elf_roundtrip_edit() {
+    let input = include_bytes!("../test/test_exe");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+
+    // Renaming a symbol only appends to the string table.
+    let idx = bin.find_symbol_idx("main").unwrap();
+    bin.symbols[idx].name = "renamed_main".into();
+    // Growing a section moves it to the end of the file.
+    bin.find_section_mut(".comment")
+        .unwrap()
+        .body
+        .extend_from_slice(b"zehn\0");
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let output = output.into_inner();
+
+    let text = bin.find_section(".text").unwrap();
+    let range =
+        text.header.sh_offset as usize..(text.header.sh_offset + text.header.sh_size) as usize;
+    assert_eq!(output[..0x3038], input[..0x3038]);
+    assert_eq!(output[range.clone()], input[range]);
+
+    let bin2 = object::Object::read(&mut Cursor::new(&output)).unwrap();
+    assert_eq!(bin2.symbols[idx].name, "renamed_main");
+    assert!(bin2
+        .find_section(".comment")
+        .unwrap()
+        .body
+        .ends_with(b"zehn\0"));
+    assert_eq!(bin2.sections.len(), bin.sections.len());
+    for (a, b) in bin.sections.iter().zip(bin2.sections.iter()) {
+        assert_eq!(a.name, b.name);
+        assert_eq!(a.body, b.body);
+    }
+}
+
+#[test]
+pub fn test_elf_segment_layout() {
+    let input = include_bytes!("../test/test_exe");
+    let bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    assert_eq!(bin.segment_sections(2), (1..=12).collect::<Vec<_>>());
+    assert_eq!(bin.segment_sections(5), (20..=26).collect::<Vec<_>>());
+    assert_eq!(bin.segment_sections(12), (20..=23).collect::<Vec<_>>());
+
+    // Growing the last section of a segment beyond the space in the file shifts all following segments.
+    let mut grown = bin.clone();
+    grown
+        .find_section_mut(".eh_frame")
+        .unwrap()
+        .body
+        .resize(0xD40, 0);
+    let mut output = Cursor::new(Vec::new());
+    grown.write(&mut output).unwrap();
+    let grown = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let rodata = &grown.segments[4].header;
+    assert_eq!(rodata.p_filesz, 0xDC8);
+    assert_eq!(rodata.p_memsz, 0xDC8);
+    for (a, b) in bin.segments.iter().zip(grown.segments.iter()) {
+        assert_eq!(a.header.p_vaddr, b.header.p_vaddr);
+        assert_eq!(b.header.p_offset % 0x1000, b.header.p_vaddr % 0x1000);
+    }
+    assert_eq!(grown.segments[5].header.p_offset, 0x3DA8);
+    assert_eq!(
+        grown.find_section(".data").unwrap().body,
+        bin.find_section(".data").unwrap().body
+    );
+
+    // Sections can't grow into other sections.
+    let mut grown = bin.clone();
+    grown.find_section_mut(".data").unwrap().body.push(0);
+    let result = grown.write(&mut Cursor::new(Vec::new()));
+    assert!(matches!(result, Err(Error::Layout(_))));
+
+    // Segments without contents in the file don't push moved sections further back.
+    let mut empty = bin.clone();
+    let stack = empty
+        .segments
+        .iter_mut()
+        .find(|x| x.header.p_type == 0x6474_E551)
+        .unwrap();
+    assert_eq!(stack.header.p_filesz, 0);
+    stack.header.p_offset = 0x10_0000;
+    empty
+        .find_section_mut(".comment")
+        .unwrap()
+        .body
+        .resize(0x100, 0);
+    let mut output = Cursor::new(Vec::new());
+    empty.write(&mut output).unwrap();
+    assert!(output.get_ref().len() < input.len() + 0x200);
+
+    // Sections that don't match their alignment in their old place are moved.
+    let input = include_bytes!("../test/test_obj.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let comment = bin.find_section(".comment").unwrap().clone();
+    assert_ne!(comment.header.sh_offset % 0x100, 0);
+    bin.find_section_mut(".comment")
+        .unwrap()
+        .header
+        .sh_addralign = 0x100;
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let moved = bin.find_section(".comment").unwrap();
+    assert_eq!(moved.header.sh_offset % 0x100, 0);
+    assert_eq!(moved.body, comment.body);
+}
+
+#[test]
+pub fn test_elf_add_section() {
+    let input = include_bytes!("../test/test_obj.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let header = section::SectionHeader {
+        sh_type: section::shtype::SHT_PROGBITS,
+        sh_addralign: 1,
+        ..Default::default()
+    };
+    let idx = bin
+        .insert_section_after(3, ".zehn", header, b"zehn".to_vec())
+        .unwrap();
+    assert_eq!(idx, 4);
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.sections.len(), 31);
+    assert_eq!(bin.sections[4].name, ".zehn");
+    assert_eq!(bin.sections[4].body, b"zehn");
+    assert_eq!(bin.header.e_shstrndx, 30);
+    let rela_text = bin.find_section(".rela.text").unwrap();
+    assert_eq!(rela_text.header.sh_info, 3);
+    assert_eq!(rela_text.header.sh_link, 28);
+    assert_eq!(bin.find_section(".symtab").unwrap().header.sh_link, 29);
+    assert_eq!(bin.find_symbol("counter").unwrap().sym_shndx, 12);
+    assert_eq!(bin.find_symbol("_Z6b_funci").unwrap().sym_shndx, 3);
+    assert_eq!(bin.sections[1].body[4..8], 6u32.to_le_bytes());
+
+    // Loadable sections get an address in a matching segment.
+    let input = include_bytes!("../test/test_exe");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let header = section::SectionHeader {
+        sh_type: section::shtype::SHT_PROGBITS,
+        sh_flags: 0x2,
+        sh_addralign: 8,
+        ..Default::default()
+    };
+    bin.add_section(".zehn", header, vec![0xAA; 0x20]).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let zehn = bin.find_section(".zehn").unwrap();
+    assert_eq!(zehn.header.sh_addr, 0x400720);
+    assert_eq!(zehn.header.sh_offset, 0x720);
+    assert_eq!(zehn.body, vec![0xAA; 0x20]);
+    assert_eq!(bin.segments[2].header.p_filesz, 0x740);
+    assert_eq!(
+        bin.segment_sections(2),
+        (1..=12).chain([38]).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+pub fn test_elf_add_segment() {
+    let input = include_bytes!("../test/test_exe");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let old_phoff = bin.header.e_phoff;
+    let header = segment::ProgramHeader {
+        p_type: segment::ptype::PT_LOAD,
+        p_flags: 0x5,
+        p_align: 0x1000,
+        ..Default::default()
+    };
+    let idx = bin.add_segment(header, vec![0xC3; 0x10]);
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.segments.len(), 15);
+    let seg = &bin.segments[idx].header;
+    assert_eq!(seg.p_filesz, 0x10);
+    assert_eq!(seg.p_offset % 0x1000, seg.p_vaddr % 0x1000);
+    let start = seg.p_offset as usize;
+    assert_eq!(output.get_ref()[start..start + 0x10], [0xC3; 0x10]);
+
+    // The program header table no longer fits in front of .interp and has to be mapped somewhere else.
+    assert_ne!(bin.header.e_phoff, old_phoff);
+    let phdr = &bin.segments[0].header;
+    assert_eq!(phdr.p_type, segment::ptype::PT_PHDR);
+    assert_eq!(phdr.p_offset, bin.header.e_phoff);
+    assert_eq!(phdr.p_filesz, 15 * 0x38);
+    assert!(bin.segments.iter().any(|x| {
+        let x = &x.header;
+        x.p_type == segment::ptype::PT_LOAD
+            && x.p_vaddr <= phdr.p_vaddr
+            && phdr.p_vaddr + phdr.p_memsz <= x.p_vaddr + x.p_memsz
+            && phdr.p_vaddr - x.p_vaddr == phdr.p_offset - x.p_offset
+    }));
+    assert_eq!(bin.find_section(".interp").unwrap().header.sh_offset, 0x318);
+
+    // Once the table has been moved, its segment grows with it as long as nothing is loaded after it.
+    let mut bin = bin;
+    let phoff = bin.header.e_phoff;
+    let note = segment::ProgramHeader {
+        p_type: segment::ptype::PT_NOTE,
+        p_flags: 0x4,
+        p_align: 4,
+        ..Default::default()
+    };
+    bin.add_segment(note, vec![0; 0x10]);
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.segments.len(), 16);
+    assert_eq!(bin.header.e_phoff, phoff);
+    let phdr = &bin.segments[0].header;
+    assert_eq!(phdr.p_filesz, 16 * 0x38);
+    assert!(bin.segments.iter().any(|x| {
+        let x = &x.header;
+        x.p_type == segment::ptype::PT_LOAD
+            && x.p_offset == phdr.p_offset
+            && x.p_filesz >= phdr.p_filesz
+            && x.p_memsz >= phdr.p_memsz
+    }));
+}
+
+#[test]
+pub fn test_elf_remove_section() {
+    let input = include_bytes!("../test/test_obj.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let text = bin.remove_section(".text").unwrap();
+    assert_eq!(text.name, ".text");
+    assert!(matches!(
+        bin.remove_section(".text"),
+        Err(Error::MissingSection(_))
+    ));
+    assert!(matches!(bin.remove_section(0), Err(Error::NullSection)));
+
+    // Removing the last member of a group removes the group as well.
+    bin.remove_section(".text._Z5twiceIlET_S0_").unwrap();
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.sections.len(), 26);
+    assert!(bin.find_section(".rela.text").is_none());
+    assert_eq!(bin.find_sections(".group").len(), 1);
+    assert_eq!(bin.sections[1].body[4..8], 2u32.to_le_bytes());
+    assert_eq!(bin.header.e_shstrndx, 25);
+    assert_eq!(bin.find_section(".symtab").unwrap().header.sh_link, 24);
+    assert_eq!(
+        bin.find_section(".rela.eh_frame").unwrap().header.sh_info,
+        4
+    );
+    assert_eq!(bin.find_symbol("counter").unwrap().sym_shndx, 7);
+    let b_func = bin.find_symbol("_Z6b_funci").unwrap();
+    assert_eq!((b_func.sym_shndx, b_func.sym_value), (0, 0));
+    assert_eq!(bin.symbols.len(), 29);
+
+    // The symbol table is recognized by its type, not by its name.
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    bin.find_section_mut(".symtab").unwrap().name = ".zehn_symtab".into();
+    bin.remove_section(".zehn_symtab").unwrap();
+    assert!(bin.symbols.is_empty());
+    bin.write(&mut Cursor::new(Vec::new())).unwrap();
+}
+
+#[test]
+pub fn test_elf_header_enums() {
+    use object::{Machine, ObjectType, OsAbi};
+
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_exe"))).unwrap();
+    assert_eq!(bin.header.object_type(), ObjectType::Exec);
+    assert_eq!(bin.header.machine(), Machine::X86_64);
+    assert_eq!(bin.header.e_ident.osabi(), OsAbi::SysV);
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj.o"))).unwrap();
+    assert_eq!(bin.header.object_type(), ObjectType::Rel);
+
+    assert_eq!(ObjectType::from(0xFE01), ObjectType::Os(0xFE01));
+    assert_eq!(ObjectType::from(0xFF7F), ObjectType::Processor(0xFF7F));
+    for x in [0u16, 3, 62, 183, 258, 0x1234, 0xFFFF] {
+        assert_eq!(u16::from(ObjectType::from(x)), x);
+        assert_eq!(u16::from(Machine::from(x)), x);
+    }
+    for x in 0..=255u8 {
+        assert_eq!(u8::from(OsAbi::from(x)), x);
+    }
+    assert_eq!(u16::from(Machine::RiscV), 243);
+}
+
+#[test]
+pub fn test_elf_symbol_info() {
+    use symbol::{SymbolBinding, SymbolType, SymbolVisibility};
+
+    let input = include_bytes!("../test/test_obj.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    assert_eq!(bin.symbols[3].kind(), SymbolType::Section);
+    assert_eq!(bin.symbols[20].kind(), SymbolType::File);
+    assert_eq!(bin.symbols[20].sym_shndx, section::shn::SHN_ABS);
+    assert_eq!(bin.symbols[21].binding(), SymbolBinding::Local);
+    assert_eq!(bin.symbols[24].binding(), SymbolBinding::Weak);
+    let counter = bin.find_symbol("counter").unwrap();
+    assert_eq!(counter.binding(), SymbolBinding::Global);
+    assert_eq!(counter.kind(), SymbolType::Object);
+    assert_eq!(counter.visibility(), SymbolVisibility::Default);
+
+    let sym = &mut bin.symbols[28];
+    sym.set_binding(SymbolBinding::GnuUnique);
+    sym.set_kind(SymbolType::GnuIfunc);
+    sym.set_visibility(SymbolVisibility::Hidden);
+    assert_eq!(sym.sym_info, 0xAA);
+    assert_eq!(sym.sym_other, 0x2);
+
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let sym = bin.find_symbol("_Z6a_funci").unwrap();
+    assert_eq!(sym.binding(), SymbolBinding::GnuUnique);
+    assert_eq!(sym.kind(), SymbolType::GnuIfunc);
+    assert_eq!(sym.visibility(), SymbolVisibility::Hidden);
+    assert_eq!(SymbolType::from(13), SymbolType::Processor(13));
+    assert_eq!(u8::from(SymbolBinding::from(7)), 7);
+}
+
+#[test]
+pub fn test_elf_flags() {
+    use section::SectionFlags;
+    use segment::SegmentFlags;
+
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_exe"))).unwrap();
+    let flags = |name| bin.find_section(name).unwrap().header.flags().to_string();
+    assert_eq!(flags(".interp"), "A");
+    assert_eq!(flags(".rela.plt"), "AI");
+    assert_eq!(flags(".text"), "AX");
+    assert_eq!(flags(".data"), "WA");
+    assert_eq!(flags(".comment"), "MS");
+    assert_eq!(flags(".symtab"), "");
+    let flags: Vec<_> = bin
+        .segments
+        .iter()
+        .filter(|x| x.header.p_type == segment::ptype::PT_LOAD)
+        .map(|x| x.header.flags().to_string())
+        .collect();
+    assert_eq!(flags, ["R  ", "R E", "R  ", "RW "]);
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj.o"))).unwrap();
+    assert_eq!(bin.sections[5].header.flags().to_string(), "AXG");
+
+    // Unknown bits survive a round trip.
+    let mut header = section::SectionHeader::default();
+    header.set_flags(SectionFlags::SHF_ALLOC | SectionFlags::from_bits_retain(0x40_0010_1000));
+    assert_eq!(header.sh_flags, 0x40_0010_1002);
+    assert_eq!(header.flags().to_string(), "Aox");
+    let flags =
+        SectionFlags::SHF_EXCLUDE | SectionFlags::SHF_GNU_RETAIN | SectionFlags::SHF_COMPRESSED;
+    assert_eq!(flags.to_string(), "CRE");
+    assert_eq!(
+        SectionFlags::from_bits_retain(0x1000_0004).to_string(),
+        "Xp"
+    );
+    let flags = SegmentFlags::from_bits_retain(0x8000_0007);
+    assert_eq!(flags.bits(), 0x8000_0007);
+    assert_eq!(flags.to_string(), "RWE");
+}
+
+#[test]
+pub fn test_elf_relocations() {
+    use relocation::Relocation;
+
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj.o"))).unwrap();
+    let relocs = bin.relocations_for(".text").unwrap();
+    assert_eq!(relocs.len(), 5);
+    assert_eq!(
+        relocs[3],
+        Relocation {
+            offset: 0x2E,
+            sym_index: 27,
+            r_type: 2,
+            addend: -4
+        }
+    );
+    assert_eq!(bin.symbols[relocs[3].sym_index as usize].name, "counter");
+    assert_eq!(bin.relocations(".rela.eh_frame").unwrap().len(), 7);
+    assert!(matches!(
+        bin.relocations(".text"),
+        Err(Error::WrongSectionType { .. })
+    ));
+
+    // REL sections store their addends at the relocated location.
+    let input = include_bytes!("../test/test_obj32.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let relocs = bin.relocations_for(".text").unwrap();
+    let addends: Vec<_> = relocs
+        .iter()
+        .map(|x| (x.offset, x.sym_index, x.r_type, x.addend))
+        .collect();
+    assert_eq!(addends, [(0x1, 3, 1, 4), (0xC, 5, 2, -4)]);
+    let mut relocs = bin.relocations(".rel.data").unwrap();
+    assert_eq!(relocs[0].addend, 8);
+    relocs[0].addend = 12;
+    bin.set_relocations(".rel.data", &relocs).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.find_section(".data").unwrap().body, 12u32.to_le_bytes());
+    assert_eq!(bin.relocations(".rel.data").unwrap(), relocs);
+
+    // Data fields take addends that fit signed or unsigned, and offsets past the section are an error.
+    let mut bin = bin;
+    relocs[0].addend = 0xFFFF_FFFF;
+    bin.set_relocations(".rel.data", &relocs).unwrap();
+    assert_eq!(bin.relocations(".rel.data").unwrap()[0].addend, -1);
+    relocs[0].addend = 0x1_0000_0000;
+    assert!(matches!(
+        bin.set_relocations(".rel.data", &relocs),
+        Err(Error::RelocationOverflow { .. })
+    ));
+    relocs[0].addend = 8;
+    relocs[0].offset = u64::MAX;
+    assert!(matches!(
+        bin.set_relocations(".rel.data", &relocs),
+        Err(Error::OutOfRange { .. })
+    ));
+
+    // Little endian MIPS64 stores the type bytes after the symbol index.
+    let mut header = object::Header {
+        e_machine: object::Machine::Mips.into(),
+        ..Default::default()
+    };
+    let rel = Relocation {
+        offset: 0x10,
+        sym_index: 0x1234,
+        r_type: 0x00_00_12_03,
+        addend: -8,
+    };
+    let mut buffer = Vec::new();
+    rel.write(&header, true, &mut buffer).unwrap();
+    assert_eq!(buffer[8..16], [0x34, 0x12, 0, 0, 0, 0, 0x12, 0x03]);
+    assert_eq!(Relocation::read(&header, true, &buffer[..]).unwrap(), rel);
+    header.e_machine = object::Machine::X86_64.into();
+    assert_eq!(
+        Relocation::read(&header, true, &buffer[..])
+            .unwrap()
+            .sym_index,
+        0x0312_0000
+    );
+}
+
+#[test]
+pub fn test_elf_rel_instruction_addends() {
+    let addends = |bin: &object::Object, section: &str| -> Vec<i64> {
+        let relocs = bin.relocations(section).unwrap();
+        relocs.iter().map(|x| x.addend).collect()
+    };
+
+    // ARM and Thumb instructions only hold the addend in some of their bits.
+    let input = include_bytes!("../test/test_obj_arm.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    assert_eq!(addends(&bin, ".rel.text"), [-8, -8, 0x1234, 0x1234, -4, -4]);
+    assert_eq!(addends(&bin, ".rel.data"), [8, 0]);
+
+    let mut relocs = bin.relocations(".rel.text").unwrap();
+    for (rel, addend) in relocs
+        .iter_mut()
+        .zip([0x100, -0x2000000, -2, 0x5678, 0x123456, 0x7FFF])
+    {
+        rel.addend = addend;
+    }
+    bin.set_relocations(".rel.text", &relocs).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.relocations(".rel.text").unwrap(), relocs);
+    let text = &bin.find_section(".text").unwrap().body;
+    assert_eq!(text[..4], [0x40, 0x00, 0x00, 0xEB]);
+    assert_eq!(text[4..8], [0x00, 0x00, 0x80, 0xEA]);
+    assert_eq!(text[8..12], [0xFE, 0x0F, 0x0F, 0xE3]);
+    assert_eq!(text[12..16], [0x78, 0x06, 0x45, 0xE3]);
+    assert_eq!(text[16..20], [0x23, 0xF1, 0x2B, 0xFA]);
+    assert_eq!(text[20..24], [0x47, 0xF6, 0xFF, 0x71]);
+
+    // Addends that don't fit into the instruction are rejected instead of overwriting the opcode.
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let mut relocs = bin.relocations(".rel.text").unwrap();
+    relocs[0].addend = 0x4000000;
+    assert!(matches!(
+        bin.set_relocations(".rel.text", &relocs),
+        Err(Error::RelocationOverflow { .. })
+    ));
+    // Nothing is written when a later entry fails.
+    relocs[0].addend = 0x100;
+    relocs[5].addend = 0x12345;
+    assert!(matches!(
+        bin.set_relocations(".rel.text", &relocs),
+        Err(Error::RelocationOverflow { .. })
+    ));
+    assert_eq!(addends(&bin, ".rel.text"), [-8, -8, 0x1234, 0x1234, -4, -4]);
+    let mut relocs = bin.relocations(".rel.data").unwrap();
+    relocs[1].addend = 4;
+    assert!(matches!(
+        bin.set_relocations(".rel.data", &relocs),
+        Err(Error::UnsupportedRelocation(_))
+    ));
+
+    // MIPS stores the addends of jumps in words and splits addresses into 16-bit halves.
+    let input = include_bytes!("../test/test_obj_mips.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    assert_eq!(addends(&bin, ".rel.text"), [8, 1, 0x2345]);
+    assert_eq!(addends(&bin, ".rel.data"), [4]);
+
+    let mut relocs = bin.relocations(".rel.text").unwrap();
+    relocs[0].addend = 0x40;
+    relocs[2].addend = -4;
+    bin.set_relocations(".rel.text", &relocs).unwrap();
+    assert_eq!(bin.relocations(".rel.text").unwrap(), relocs);
+    let text = &bin.find_section(".text").unwrap().body;
+    assert_eq!(text[..4], [0x0C, 0x00, 0x00, 0x10]);
+    assert_eq!(
+        text[8..16],
+        [0x3C, 0x02, 0x00, 0x01, 0x24, 0x42, 0xFF, 0xFC]
+    );
+}
+
+#[test]
+pub fn test_elf_relocation_types() {
+    use relocation::{type_info, x86_64, RelocationKind};
+
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj.o"))).unwrap();
+    let machine = bin.header.machine();
+    let names: Vec<_> = bin
+        .relocations_for(".text")
+        .unwrap()
+        .iter()
+        .map(|x| x.describe(machine, &bin.symbols[x.sym_index as usize].name))
+        .collect();
+    assert_eq!(names[0], "R_X86_64_PLT32 _Z5twiceIiET_S0_-4");
+    assert_eq!(names[3], "R_X86_64_PC32 counter-4");
+    let names: Vec<_> = bin
+        .relocations(".rela.eh_frame")
+        .unwrap()
+        .iter()
+        .map(|x| x.describe(machine, &bin.symbols[x.sym_index as usize].name))
+        .collect();
+    assert_eq!(names[0], "R_X86_64_PC32");
+
+    let bin =
+        object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj32.o"))).unwrap();
+    let rel = &bin.relocations(".rel.text").unwrap()[1];
+    assert_eq!(rel.type_name(bin.header.machine()), "R_386_PC32");
+    assert_eq!(rel.type_name(object::Machine::Bpf), "0x2");
+
+    assert_eq!(
+        x86_64::RelocationType::from(9),
+        x86_64::RelocationType::Gotpcrel
+    );
+    assert_eq!(u32::from(x86_64::RelocationType::Unknown(0x99)), 0x99);
+    let info = type_info(object::Machine::X86_64, 9).unwrap();
+    assert_eq!(info.size, 4);
+    assert_eq!(info.kind, RelocationKind::PC_RELATIVE | RelocationKind::GOT);
+    let info = type_info(object::Machine::AArch64, 1027).unwrap();
+    assert_eq!((info.name, info.size), ("R_AARCH64_RELATIVE", 8));
+    assert_eq!(info.kind, RelocationKind::RELATIVE);
+    let info = type_info(object::Machine::RiscV, 19).unwrap();
+    assert_eq!(info.name, "R_RISCV_CALL_PLT");
+    assert!(info.kind.contains(RelocationKind::PLT));
+    let info = type_info(object::Machine::Arm, 10).unwrap();
+    assert_eq!(info.name, "R_ARM_THM_CALL");
+    assert_eq!(
+        type_info(object::Machine::Mips, 0x0012_040C).unwrap().name,
+        "R_MIPS_GPREL32"
+    );
+    assert_eq!(
+        type_info(object::Machine::LoongArch, 71).unwrap().name,
+        "R_LARCH_PCALA_HI20"
+    );
+    assert_eq!(
+        type_info(object::Machine::Ppc64, 10).unwrap().name,
+        "R_PPC64_REL24"
+    );
+    assert!(type_info(object::Machine::Sparc, 1).is_none());
+}
+
+#[test]
+pub fn test_elf_apply_relocations() {
+    let read_u32 =
+        |body: &[u8], pos: usize| u32::from_le_bytes(body[pos..pos + 4].try_into().unwrap());
+    let input = include_bytes!("../test/test_obj.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    bin.find_section_mut(".text").unwrap().header.sh_addr = 0x1000;
+    let resolve = |counter: u64| {
+        move |_, sym: &symbol::Symbol| match sym.name.as_str() {
+            "counter" => Some(counter),
+            _ => Some(0x1000 + sym.sym_value),
+        }
+    };
+    bin.apply_relocations(".text", resolve(0x2000)).unwrap();
+    let body = &bin.find_section(".text").unwrap().body;
+    // R_X86_64_PLT32 _Z6b_funci-4 and R_X86_64_PC32 counter-4
+    assert_eq!(read_u32(body, 0x28), 0x1040 - 4 - 0x1028);
+    assert_eq!(read_u32(body, 0x2E), 0x2000 - 4 - 0x102E);
+
+    // Nothing is applied if a later relocation fails.
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let text = bin.find_section(".text").unwrap().body.clone();
+    assert!(matches!(
+        bin.apply_relocations(".text", resolve(0x1_0000_0000)),
+        Err(Error::RelocationOverflow { offset: 0x2E, .. })
+    ));
+    assert_eq!(bin.find_section(".text").unwrap().body, text);
+    let mut relocs = bin.relocations(".rela.text").unwrap();
+    relocs[3].r_type = relocation::x86_64::RelocationType::Gotpcrel.into();
+    bin.set_relocations(".rela.text", &relocs).unwrap();
+    assert!(matches!(
+        bin.apply_relocations(".text", resolve(0x2000)),
+        Err(Error::UnsupportedRelocation(x)) if x == "R_X86_64_GOTPCREL"
+    ));
+    assert_eq!(bin.find_section(".text").unwrap().body, text);
+    assert!(matches!(
+        bin.apply_relocations(".text", |_, sym| (sym.name != "counter").then_some(0)),
+        Err(Error::UnresolvedSymbol(x)) if x == "counter"
+    ));
+
+    // R_X86_64_SIZE32 checks the size and addend, not the address.
+    relocs[3].r_type = relocation::x86_64::RelocationType::Size32.into();
+    relocs[3].addend = 1;
+    bin.set_relocations(".rela.text", &relocs).unwrap();
+    let counter = relocs[3].sym_index as usize;
+    bin.symbols[counter].sym_size = 0xFFFF_FFFE;
+    let mut copy = bin.clone();
+    copy.apply_relocations(".text", resolve(0x1_0000_0000))
+        .unwrap();
+    assert_eq!(
+        read_u32(&copy.find_section(".text").unwrap().body, 0x2E),
+        0xFFFF_FFFF
+    );
+    bin.symbols[counter].sym_size = 0xFFFF_FFFF;
+    assert!(matches!(
+        bin.apply_relocations(".text", resolve(0x2000)),
+        Err(Error::RelocationOverflow { offset: 0x2E, .. })
+    ));
+    relocs[3].offset = u64::MAX;
+    bin.set_relocations(".rela.text", &relocs).unwrap();
+    assert!(matches!(
+        bin.apply_relocations(".text", resolve(0x2000)),
+        Err(Error::OutOfRange { .. })
+    ));
+
+    // Everything undefined resolves to 0x10800, the text is at 0x10000 and the data at 0x20000.
+    for (input, words) in [
+        (
+            &include_bytes!("../test/test_obj_aarch64.o")[..],
+            // bl, adrp, add, adr, movz
+            [
+                (0x0, 0x9400_0200),
+                (0x10, 0x9000_0080),
+                (0x14, 0x9100_0000),
+                (0x1C, 0x1000_3F22),
+                (0x20, 0xD2A0_0043),
+            ],
+        ),
+        (
+            &include_bytes!("../test/test_obj_riscv.o")[..],
+            // auipc + jalr, auipc + addi, c.beqz
+            [
+                (0x0, 0x0000_1097),
+                (0x4, 0x8000_80E7),
+                (0x10, 0x0001_0517),
+                (0x14, 0xFF05_0513),
+                (0x2C, 0xC509_8082),
+            ],
+        ),
+    ] {
+        let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+        bin.find_section_mut(".text").unwrap().header.sh_addr = 0x10000;
+        bin.find_section_mut(".data").unwrap().header.sh_addr = 0x20000;
+        let addrs: Vec<_> = bin.sections.iter().map(|x| x.header.sh_addr).collect();
+        let resolve = |_, sym: &symbol::Symbol| match sym.sym_shndx {
+            section::shn::SHN_UNDEF => Some(0x10800),
+            x => Some(addrs[x as usize] + sym.sym_value),
+        };
+        bin.apply_relocations(".text", resolve).unwrap();
+        let body = &bin.find_section(".text").unwrap().body;
+        for (pos, word) in words {
+            assert_eq!(read_u32(body, pos), word, "{:#x}", pos);
+        }
+    }
+
+    // ULEB128 values keep their length.
+    use relocation::riscv::RelocationType as R;
+    let input = include_bytes!("../test/test_obj_riscv.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let mut relocs = bin.relocations(".rela.data").unwrap();
+    relocs[1].r_type = R::SetUleb128.into();
+    relocs[1].addend = 0x10;
+    relocs[2].r_type = R::SubUleb128.into();
+    bin.find_section_mut(".data").unwrap().body[8..].copy_from_slice(&[0x80, 0x80, 0x80, 0]);
+    bin.set_relocations(".rela.data", &relocs).unwrap();
+    let resolve = |_, sym: &symbol::Symbol| match sym.sym_shndx {
+        section::shn::SHN_UNDEF => Some(0x10800),
+        _ => Some(0x10),
+    };
+    bin.apply_relocations(".data", resolve).unwrap();
+    assert_eq!(
+        bin.find_section(".data").unwrap().body[8..],
+        [0x80, 0x90, 0x84, 0]
+    );
+
+    let input = include_bytes!("../test/test_obj32.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    assert!(matches!(
+        bin.apply_relocations(".text", |_, _| Some(0)),
+        Err(Error::UnsupportedRelocation(x)) if x == "R_386_32"
+    ));
+}
+
+#[test]
+pub fn test_elf_dynamic() {
+    use dynamic::{Dynamic, DynamicEntry, DynamicFlags1, DynamicTag};
+
+    let mut bin =
+        object::Object::read(&mut Cursor::new(include_bytes!("../test/test_exe"))).unwrap();
+    let dynamic = bin.dynamic().unwrap().unwrap();
+    assert_eq!(dynamic.entries.len(), 27);
+    assert_eq!(dynamic.entries[11].tag(), DynamicTag::GnuHash);
+    assert_eq!(dynamic.get(DynamicTag::StrSz), Some(238));
+    assert_eq!(dynamic.get(DynamicTag::VerNeed), Some(0x400618));
+    let needed = ["libtest_lib.so", "libtest_lib2.so", "libc.so.6"];
+    assert_eq!(dynamic.needed().unwrap(), needed);
+    assert_eq!(
+        dynamic.runpath().unwrap().as_deref(),
+        Some("/home/marvin/repos/solink/test/out")
+    );
+    assert_eq!(dynamic.soname().unwrap(), None);
+
+    // Without section headers, everything is found through the program headers.
+    bin.sections.clear();
+    let from_segment = bin.dynamic().unwrap().unwrap();
+    assert_eq!(from_segment.entries, dynamic.entries);
+    assert_eq!(from_segment.needed().unwrap(), needed);
+    assert_eq!(bin.data_at(0x400618, u64::MAX), None);
+    assert_eq!(bin.data_at(u64::MAX, 2), None);
+    let segment = bin
+        .segments
+        .iter_mut()
+        .find(|x| x.header.p_type == segment::ptype::PT_DYNAMIC)
+        .unwrap();
+    segment.header.p_offset = u64::MAX;
+    assert!(matches!(bin.dynamic(), Err(Error::OutOfRange { .. })));
+
+    let dynamic = Dynamic {
+        entries: vec![DynamicEntry::new(DynamicTag::Flags1, 0x0800_0001)],
+        strtab: Vec::new(),
+    };
+    assert_eq!(
+        dynamic.flags_1(),
+        DynamicFlags1::DF_1_NOW | DynamicFlags1::DF_1_PIE
+    );
+    assert!(dynamic.flags().is_empty());
+
+    let bin = object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj.o"))).unwrap();
+    assert!(bin.dynamic().unwrap().is_none());
+}
+
+#[test]
+pub fn test_elf_dynamic_symbols() {
+    use dynamic::DynamicTag;
+
+    let input = include_bytes!("../test/test_exe");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let mut symbols = bin.dynamic_symbols().unwrap();
+    assert_eq!(symbols.len(), 10);
+    assert_eq!(symbols[4].name, "printf");
+    assert_eq!(bin.symbols[1].get_name(&bin).unwrap(), bin.symbols[1].name);
+
+    // Writing the same symbols back doesn't change anything.
+    bin.set_dynamic_symbols(&symbols).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    assert_eq!(output.get_ref(), input);
+
+    let mut export = bin.find_symbol("main").unwrap().clone();
+    export.name = "zehn_export".into();
+    symbols.push(export);
+    bin.set_dynamic_symbols(&symbols).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let symbols = bin.dynamic_symbols().unwrap();
+    assert_eq!(symbols.len(), 11);
+    assert_eq!(symbols[10].name, "zehn_export");
+    let dynstr = bin.find_section(".dynstr").unwrap();
+    let versym = bin.find_section(".gnu.version").unwrap();
+    assert_eq!(versym.body.len(), 22);
+    assert_eq!(versym.body[20..], [1, 0]);
+    let dynamic = bin.dynamic().unwrap().unwrap();
+    assert_eq!(
+        dynamic.get(DynamicTag::StrSz),
+        Some(dynstr.body.len() as u64)
+    );
+    assert_eq!(dynamic.get(DynamicTag::StrTab), Some(dynstr.header.sh_addr));
+    assert_eq!(
+        dynamic.get(DynamicTag::SymTab),
+        Some(bin.find_section(".dynsym").unwrap().header.sh_addr)
+    );
+    assert_eq!(dynamic.get(DynamicTag::VerSym), Some(versym.header.sh_addr));
+
+    // The new symbol is the only one in the GNU hash table.
+    let gnu_hash = &bin.find_section(".gnu.hash").unwrap().body;
+    assert_eq!(gnu_hash[4..8], 10u32.to_le_bytes());
+    assert_eq!(gnu_hash[24..28], 10u32.to_le_bytes());
+    let chain = hash::gnu_hash("zehn_export") | 1;
+    assert_eq!(gnu_hash[28..], chain.to_le_bytes());
+
+    // Dynamic symbols follow their sections when earlier ones are removed.
+    let input = include_bytes!("../test/test_lib");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    bin.remove_section(".note.gnu.build-id").unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let text = bin.find_section_idx(".text").unwrap();
+    let symbols = bin.dynamic_symbols().unwrap();
+    for name in ["foo", "bar"] {
+        let symbol = symbols.iter().find(|x| x.name == name).unwrap();
+        assert_eq!(symbol.sym_shndx, text);
+    }
+}
+
+#[test]
+pub fn test_elf_symbol_versions() {
+    use version::{VersionNeed, VERSYM_HIDDEN, VER_FLG_BASE};
+
+    let input = include_bytes!("../test/test_lib");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let names = bin.versioned_symbol_names().unwrap();
+    assert!(names.contains(&"puts@GLIBC_2.2.5".to_string()));
+    assert!(names.contains(&"bar@@LIBFOO_1.0".to_string()));
+    assert!(names.contains(&"foo@@LIBFOO_2.0".to_string()));
+    assert!(names.contains(&"foo@LIBFOO_1.0".to_string()));
+    let indices = bin.symbol_version_indices().unwrap();
+    assert_eq!(indices.len(), names.len());
+    assert_eq!(
+        indices.iter().filter(|&x| x & VERSYM_HIDDEN != 0).count(),
+        1
+    );
+
+    let definitions = bin.version_definitions().unwrap();
+    assert_eq!(definitions.len(), 3);
+    assert_eq!(definitions[0].flags, VER_FLG_BASE);
+    assert_eq!(definitions[0].names, ["libfoo.so.1"]);
+    assert_eq!(definitions[2].index, 3);
+    assert_eq!(definitions[2].names, ["LIBFOO_2.0", "LIBFOO_1.0"]);
+
+    let mut requirements = bin.version_requirements().unwrap();
+    assert_eq!(requirements.len(), 1);
+    assert_eq!(requirements[0].file, "libc.so.6");
+    assert_eq!(
+        requirements[0].versions,
+        [VersionNeed {
+            name: "GLIBC_2.2.5".into(),
+            flags: 0,
+            index: 4,
+        }]
+    );
+
+    // Writing the same versions back doesn't change anything.
+    bin.set_version_definitions(&definitions).unwrap();
+    bin.set_version_requirements(&requirements).unwrap();
+    bin.set_symbol_version_indices(&indices).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    assert_eq!(output.get_ref(), input);
+
+    requirements[0].versions.push(VersionNeed {
+        name: "GLIBC_2.34".into(),
+        flags: 0,
+        index: 5,
+    });
+    bin.set_version_requirements(&requirements).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.version_requirements().unwrap(), requirements);
+}
+
+#[test]
+pub fn test_elf_hash_tables() {
+    let input = include_bytes!("../test/test_lib");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let gnu = bin.gnu_hash_table().unwrap().unwrap();
+    let section = bin.find_section(".gnu.hash").unwrap();
+    let class = &bin.header.e_ident.ei_class;
+    let endian = &bin.header.e_ident.ei_data;
+    assert_eq!(gnu.write(class, endian).unwrap(), section.body);
+    assert!(bin.sysv_hash_table().unwrap().is_none());
+    let mut data = section.body.clone();
+    data[12..16].copy_from_slice(&32u32.to_le_bytes());
+    assert!(matches!(
+        hash::GnuHashTable::read(class, endian, &data),
+        Err(Error::Malformed { .. })
+    ));
+    // Hand-built tables with such a shift still work, the shifted hash is just 0.
+    let table = hash::GnuHashTable::build(&["foo", "bar"], 1, 1, 1, 32, class);
+    let symbols: Vec<_> = ["", "foo", "bar"]
+        .iter()
+        .map(|x| symbol::Symbol {
+            name: x.to_string(),
+            ..Default::default()
+        })
+        .collect();
+    assert_eq!(table.lookup("bar", &symbols, class), Some(2));
+
+    // Only defined symbols are hashed, the loader never finds the imports.
+    let mut symbols = bin.dynamic_symbols().unwrap();
+    assert_eq!(gnu.symoffset, 6);
+    for (i, symbol) in symbols.iter().enumerate().skip(1) {
+        let found = bin.lookup_dynamic_symbol(&symbol.name).unwrap();
+        match i < gnu.symoffset as usize {
+            true => assert_eq!(found, None),
+            // The hidden version of `foo` is found through the default one.
+            false => assert_eq!(found.map(|x| &symbols[x].name), Some(&symbol.name)),
+        }
+    }
+    assert_eq!(bin.lookup_dynamic_symbol("missing").unwrap(), None);
+
+    let mut export = symbols[gnu.symoffset as usize].clone();
+    export.name = "zehn_export".into();
+    symbols.push(export);
+    bin.set_dynamic_symbols(&symbols).unwrap();
+    let idx = bin.lookup_dynamic_symbol("zehn_export").unwrap().unwrap();
+    assert_eq!(bin.dynamic_symbols().unwrap()[idx].name, "zehn_export");
+    assert!(bin.lookup_dynamic_symbol("bar").unwrap().is_some());
+
+    let input = include_bytes!("../test/test_exe");
+    let bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let sysv = bin.sysv_hash_table().unwrap().unwrap();
+    let symbols = bin.dynamic_symbols().unwrap();
+    assert_eq!(sysv.chains.len(), symbols.len());
+    assert_eq!(sysv.lookup("printf", &symbols), Some(4));
+    let section = bin.find_section(".hash").unwrap();
+    assert_eq!(
+        sysv.write(&bin.header.e_ident.ei_data).unwrap(),
+        section.body
+    );
+}
+
+#[test]
+pub fn test_elf_notes() {
+    use note::{ntype, owner, GnuProperty, KnownNote, Note, X86Isa1};
+
+    let input = include_bytes!("../test/test_exe");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let notes = bin.notes().unwrap();
+    assert_eq!(notes.len(), 3);
+    assert!(notes.iter().all(|x| x.name == owner::GNU));
+    assert_eq!(
+        notes[0].decode(&bin.header).unwrap(),
+        KnownNote::GnuProperties(vec![
+            GnuProperty::X86IsaNeeded(X86Isa1::BASELINE),
+            GnuProperty::X86Feature2Used(1),
+            GnuProperty::X86IsaUsed(X86Isa1::empty()),
+        ])
+    );
+    let build_id = [
+        0x76, 0x44, 0xCA, 0xBA, 0xD6, 0x5A, 0x82, 0x59, 0xD2, 0x28, 0x09, 0x5B, 0x3F, 0xAA, 0xB7,
+        0xF5, 0x61, 0x7D, 0xB9, 0xF9,
+    ];
+    assert_eq!(
+        notes[1].decode(&bin.header).unwrap(),
+        KnownNote::GnuBuildId(build_id.to_vec())
+    );
+    assert_eq!(
+        notes[2].decode(&bin.header).unwrap(),
+        KnownNote::GnuAbiTag {
+            os: note::abi_os::ELF_NOTE_OS_LINUX,
+            major: 3,
+            minor: 2,
+            patch: 0,
+        }
+    );
+
+    // Notes are the same with and without section headers.
+    let mut stripped = bin.clone();
+    stripped.sections.clear();
+    assert_eq!(stripped.notes().unwrap(), notes);
+    let segment = stripped
+        .segments
+        .iter_mut()
+        .find(|x| x.header.p_type == segment::ptype::PT_NOTE)
+        .unwrap();
+    segment.header.p_filesz = u64::MAX;
+    assert!(matches!(stripped.notes(), Err(Error::OutOfRange { .. })));
+
+    let package = Note::new(
+        owner::FDO,
+        ntype::NT_FDO_PACKAGING_METADATA,
+        b"{\"type\":\"rpm\",\"name\":\"zehn\"}\0".to_vec(),
+    );
+    bin.add_note(".note.package", &package).unwrap();
+    let go = Note::new(owner::GO, ntype::NT_GO_BUILD_ID, b"zehn/build\0".to_vec());
+    bin.add_note(".note.ABI-tag", &go).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+
+    let mut bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let notes = bin.section_notes(".note.package").unwrap();
+    assert_eq!(
+        notes[0].decode(&bin.header).unwrap(),
+        KnownNote::Package("{\"type\":\"rpm\",\"name\":\"zehn\"}".into())
+    );
+    let notes = bin.section_notes(".note.ABI-tag").unwrap();
+    assert_eq!(notes.len(), 2);
+    assert_eq!(
+        notes[1].decode(&bin.header).unwrap(),
+        KnownNote::GoBuildId("zehn/build".into())
+    );
+    bin.sections.clear();
+    assert_eq!(bin.notes().unwrap().len(), 5);
+}
+
+#[test]
+#[cfg(all(feature = "md5", feature = "sha1", feature = "xxhash"))]
+pub fn test_elf_build_id() {
+    use build_id::BuildIdStyle;
+
+    let input = include_bytes!("../test/test_exe");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let old = bin.build_id().unwrap().unwrap();
+    assert_eq!(old[..4], [0x76, 0x44, 0xCA, 0xBA]);
+    assert!(
+        object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj.o")))
+            .unwrap()
+            .build_id()
+            .unwrap()
+            .is_none()
+    );
+
+    // The ID doesn't depend on the old one.
+    let sha1 = bin.compute_build_id(BuildIdStyle::Sha1).unwrap();
+    assert_eq!(sha1.len(), 20);
+    assert_ne!(sha1, old);
+    bin.set_build_id(&[0; 20]).unwrap();
+    assert_eq!(bin.compute_build_id(BuildIdStyle::Sha1).unwrap(), sha1);
+    assert_eq!(bin.compute_build_id(BuildIdStyle::Md5).unwrap().len(), 16);
+    assert_eq!(bin.compute_build_id(BuildIdStyle::Fast).unwrap().len(), 8);
+
+    // Changing the contents changes the ID.
+    let text = bin.find_section_mut(".text").unwrap();
+    text.body[0] ^= 0xFF;
+    let id = bin.regenerate_build_id().unwrap();
+    assert_ne!(id, sha1);
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    assert_eq!(output.get_ref().len(), input.len());
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.build_id().unwrap(), Some(id));
+
+    // A longer ID moves the note.
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    bin.set_build_id(&[0xAB; 32]).unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let mut bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert_eq!(bin.build_id().unwrap(), Some(vec![0xAB; 32]));
+    bin.sections.clear();
+    assert_eq!(bin.build_id().unwrap(), Some(vec![0xAB; 32]));
+}
+
+#[test]
+pub fn test_elf_groups() {
+    use group::GroupFlags;
+    use section::SectionFlags;
+    use symbol::SymbolBinding;
+
+    let input = include_bytes!("../test/test_obj.o");
+    let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let groups = bin.groups().unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].0, 1);
+    assert!(groups[0].1.is_comdat());
+    assert_eq!(groups[0].1.members, [5]);
+    assert_eq!(bin.group_signature(1).unwrap(), "_Z5twiceIiET_S0_");
+    assert_eq!(bin.group_signature(2).unwrap(), "_Z5twiceIlET_S0_");
+    assert_eq!(bin.group_of(".text._Z5twiceIlET_S0_").unwrap(), Some(2));
+    assert_eq!(bin.group_of(".text").unwrap(), None);
+    assert!(matches!(
+        bin.group(".text"),
+        Err(Error::WrongSectionType { .. })
+    ));
+
+    // A new group takes the relocations of its members along and gets a new local signature symbol.
+    let text = bin.find_section_idx(".text").unwrap() as usize;
+    let idx = bin
+        .add_group("my_group", GroupFlags::GRP_COMDAT, &[text])
+        .unwrap();
+    assert_eq!(idx, text);
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let mut bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    let group = bin.group(idx).unwrap();
+    assert_eq!(group.members, [4, 5]);
+    assert_eq!(bin.sections[5].name, ".rela.text");
+    assert!(bin.sections[4]
+        .header
+        .flags()
+        .contains(SectionFlags::SHF_GROUP));
+    assert_eq!(bin.group_signature(idx).unwrap(), "my_group");
+    assert_eq!(bin.group_of(".text._Z5twiceIiET_S0_").unwrap(), Some(1));
+
+    // Local symbols still come first, and references to later symbols moved along.
+    let symtab = bin.find_section(".symtab").unwrap();
+    let first_global = symtab.header.sh_info as usize;
+    assert!(bin.symbols[..first_global]
+        .iter()
+        .all(|x| x.binding() == SymbolBinding::Local));
+    assert_eq!(bin.symbols[first_global - 1].name, "my_group");
+    let relocations = bin.relocations(".rela.text").unwrap();
+    let symbol = &bin.symbols[relocations[0].sym_index as usize];
+    assert_eq!(symbol.name, "_Z5twiceIiET_S0_");
+    assert_eq!(bin.group_signature(1).unwrap(), "_Z5twiceIiET_S0_");
+
+    // Members of a removed group leave it.
+    bin.remove_section(idx).unwrap();
+    let text = bin.find_section(".text").unwrap();
+    assert!(!text.header.flags().contains(SectionFlags::SHF_GROUP));
+    assert_eq!(bin.groups().unwrap().len(), 2);
+}
+
+#[test]
+#[cfg(all(feature = "zlib", feature = "zstd"))]
+pub fn test_elf_compressed_sections() {
+    use compression::{chtype, CompressionAlgorithm, CompressionHeader};
+    use object::{Class, Endianness};
+    use section::SectionFlags;
+
+    let input = include_bytes!("../test/test_obj.o");
+    let plain = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let debug_info = &plain.find_section(".debug_info").unwrap().body;
+    assert!(!plain.find_section(".debug_info").unwrap().is_compressed());
+
+    // Compressed by objcopy with zstd.
+    let mut bin =
+        object::Object::read(&mut Cursor::new(include_bytes!("../test/test_obj_zstd.o"))).unwrap();
+    let section = bin.find_section(".debug_info").unwrap();
+    assert!(section.is_compressed());
+    let header = section
+        .compression_header(&Class::Bits64, &Endianness::Little)
+        .unwrap()
+        .unwrap();
+    assert_eq!(header.ch_type, chtype::ELFCOMPRESS_ZSTD);
+    assert_eq!(header.ch_size, debug_info.len() as u64);
+    assert_eq!(&bin.decompressed_body(".debug_info").unwrap(), debug_info);
+
+    // A wrong size in the header is an error, without reserving or decompressing more than that.
+    for size in [u64::MAX, 16] {
+        let mut bin = bin.clone();
+        let section = bin.find_section_mut(".debug_info").unwrap();
+        section.body[8..16].copy_from_slice(&size.to_le_bytes());
+        assert!(matches!(
+            bin.decompressed_body(".debug_info"),
+            Err(Error::Compression(_))
+        ));
+    }
+    bin.decompress_section(".debug_info").unwrap();
+    let section = bin.find_section(".debug_info").unwrap();
+    assert!(!section
+        .header
+        .flags()
+        .contains(SectionFlags::SHF_COMPRESSED));
+    assert_eq!(section.header.sh_addralign, 1);
+
+    // Compressed the old GNU way.
+    let mut bin = object::Object::read(&mut Cursor::new(include_bytes!(
+        "../test/test_obj_zdebug.o"
+    )))
+    .unwrap();
+    assert!(bin.find_section(".zdebug_info").unwrap().is_compressed());
+    bin.decompress_section(".zdebug_info").unwrap();
+    assert_eq!(&bin.find_section(".debug_info").unwrap().body, debug_info);
+    assert!(bin.find_section(".rela.debug_info").is_some());
+
+    // Compressed sections survive a round trip.
+    let mut bin = plain.clone();
+    bin.compress_debug_sections(CompressionAlgorithm::Zlib)
+        .unwrap();
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    for section in plain
+        .sections
+        .iter()
+        .filter(|x| x.name.starts_with(".debug"))
+    {
+        let compressed = bin.find_section(&section.name).unwrap();
+        assert!(compressed.is_compressed());
+        assert_eq!(compressed.header.sh_offset % 8, 0);
+        assert_eq!(bin.decompressed_body(&*section.name).unwrap(), section.body);
+    }
+    assert!(!bin.find_section(".text").unwrap().is_compressed());
+
+    // The header follows the class and endianness of the object.
+    let mut section = plain.find_section(".comment").unwrap().clone();
+    let body = section.body.clone();
+    section
+        .compress(CompressionAlgorithm::Zstd, &Class::Bits32, &Endianness::Big)
+        .unwrap();
+    assert_eq!(section.header.sh_addralign, 4);
+    assert_eq!(section.body[..4], [0, 0, 0, 2]);
+    let header =
+        CompressionHeader::read(&Class::Bits32, &Endianness::Big, &section.body[..]).unwrap();
+    assert_eq!(header.ch_size, body.len() as u64);
+    assert_eq!(header.ch_addralign, 1);
+    assert_eq!(
+        section
+            .decompressed_body(&Class::Bits32, &Endianness::Big)
+            .unwrap(),
+        body
+    );
+
+    // Loaded sections can't be compressed.
+    let mut bin = plain.clone();
+    assert!(matches!(
+        bin.compress_section(".text", CompressionAlgorithm::Zlib),
+        Err(Error::Compression(_))
+    ));
+}
+
+#[test]
+pub fn test_elf_split_debug() {
+    use debuglink::{debuglink_crc, DebugLink};
+    use object::Endianness;
+    use section::shtype;
+
+    let input = include_bytes!("../test/test_exe");
+    let original = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let mut bin = original.clone();
+    let debug = bin.split_debug("test_exe.debug").unwrap();
+    assert!(debug.len() < input.len());
+
+    // The debug file keeps the debug sections, notes and symbols, but not the code.
+    let debug_bin = object::Object::read(&mut Cursor::new(&debug)).unwrap();
+    assert_eq!(debug_bin.sections.len(), original.sections.len());
+    let text = debug_bin.find_section(".text").unwrap();
+    assert_eq!(text.header.sh_type, shtype::SHT_NOBITS);
+    assert_eq!(
+        text.header.sh_addr,
+        original.find_section(".text").unwrap().header.sh_addr
+    );
+    assert_eq!(
+        debug_bin.find_section(".debug_info").unwrap().body,
+        original.find_section(".debug_info").unwrap().body
+    );
+    assert_eq!(debug_bin.build_id().unwrap(), original.build_id().unwrap());
+    assert_eq!(debug_bin.symbols.len(), original.symbols.len());
+
+    // The object itself loses its debug sections and links to the debug file.
+    let mut output = Cursor::new(Vec::new());
+    bin.write(&mut output).unwrap();
+    assert!(output.get_ref().len() < input.len());
+    let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert!(!bin.sections.iter().any(|x| x.is_debug()));
+    assert_eq!(
+        bin.find_section(".text").unwrap().body,
+        original.find_section(".text").unwrap().body
+    );
+    let link = bin.debuglink().unwrap().unwrap();
+    assert_eq!(link.name, "test_exe.debug");
+    assert_eq!(link.crc, debuglink_crc(&debug));
+    assert!(original.debuglink().unwrap().is_none());
+
+    // The name is padded to 4 bytes before the checksum.
+    let link = DebugLink {
+        name: "abc".into(),
+        crc: 0x12345678,
+    };
+    let data = link.write(&Endianness::Big).unwrap();
+    assert_eq!(data, b"abc\0\x12\x34\x56\x78");
+    assert_eq!(DebugLink::read(&Endianness::Big, &data).unwrap(), link);
+    assert_eq!(debuglink_crc(b"123456789"), 0xCBF43926);
+
+    let mut bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+    assert!(matches!(
+        bin.split_debug("test_exe.debug"),
+        Err(Error::MissingSection(_))
+    ));
+}
+
+#[test]
+pub fn test_elf_strip() {
+    use strip::{StripMode, StripOptions};
+    use symbol::{SymbolBinding, SymbolType};
+
+    fn strip(input: &[u8], options: &StripOptions) -> (Vec<u8>, object::Object) {
+        let mut bin = object::Object::read(&mut Cursor::new(input)).unwrap();
+        bin.strip(options).unwrap();
+        let mut output = Cursor::new(Vec::new());
+        bin.write(&mut output).unwrap();
+        let bin = object::Object::read(&mut Cursor::new(output.get_ref())).unwrap();
+        (output.into_inner(), bin)
+    }
+    // Locals come first, and `sh_info` of the symbol table is the index of the first non-local symbol.
+    fn check_locals(bin: &object::Object) {
+        let locals = bin
+            .symbols
+            .iter()
+            .take_while(|x| x.binding() == SymbolBinding::Local)
+            .count();
+        assert!(bin.symbols[locals..]
+            .iter()
+            .all(|x| x.binding() != SymbolBinding::Local));
+        let symtab = bin.find_section(".symtab").unwrap();
+        assert_eq!(symtab.header.sh_info as usize, locals);
+    }
+    // The names of the symbols that the relocations of a section refer to.
+    fn reloc_names(bin: &object::Object, section: &str) -> Vec<String> {
+        let idx = bin.find_section_idx(section).unwrap();
+        bin.relocations(idx as usize)
+            .unwrap()
+            .iter()
+            .map(|x| {
+                let symbol = &bin.symbols[x.sym_index as usize];
+                match symbol.kind() {
+                    SymbolType::Section => bin.sections[symbol.sym_shndx as usize].name.clone(),
+                    _ => symbol.name.clone(),
+                }
+            })
+            .collect()
+    }
+
+    let input = include_bytes!("../test/test_exe");
+    let original = object::Object::read(&mut Cursor::new(input)).unwrap();
+
+    // `--strip-all` removes the symbol table and the names of everything that is gone.
+    let (output, bin) = strip(input, &StripOptions::new(StripMode::All));
+    assert!(output.len() < input.len());
+    assert!(bin.symbols.is_empty());
+    assert!(bin.find_section(".symtab").is_none());
+    assert!(bin.find_section(".strtab").is_none());
+    assert!(!bin.sections.iter().any(|x| x.is_debug()));
+    let shstrtab = &bin.sections[bin.header.e_shstrndx as usize].body;
+    assert!(!shstrtab.windows(6).any(|x| x == b".debug"));
+    assert_eq!(
+        bin.find_section(".text").unwrap().body,
+        original.find_section(".text").unwrap().body
+    );
+    assert_eq!(bin.segments.len(), original.segments.len());
+
+    // Kept symbols survive, and the string table only holds their names.
+    let mut options = StripOptions::new(StripMode::All);
+    options.keep_symbols = vec!["main".into()];
+    let (_, bin) = strip(input, &options);
+    assert_eq!(bin.symbols.len(), 2);
+    assert_eq!(bin.symbols[1].name, "main");
+    assert_eq!(bin.find_section(".strtab").unwrap().body, b"\0main\0");
+    check_locals(&bin);
+
+    // `--strip-debug` only removes file symbols along with the debug sections.
+    let (_, bin) = strip(input, &StripOptions::new(StripMode::Debug));
+    assert!(!bin.sections.iter().any(|x| x.is_debug()));
+    assert!(bin.symbols.iter().all(|x| x.kind() != SymbolType::File));
+    assert_eq!(
+        bin.symbols.len(),
+        original.symbols.len()
+            - original
+                .symbols
+                .iter()
+                .filter(|x| x.kind() == SymbolType::File)
+                .count()
+    );
+    assert!(bin.find_symbol("main").is_some());
+    check_locals(&bin);
+
+    // `--strip-unneeded` keeps the symbols that linkers and relocations need.
+    let input = include_bytes!("../test/test_obj.o");
+    let original = object::Object::read(&mut Cursor::new(input)).unwrap();
+    let (_, bin) = strip(input, &StripOptions::new(StripMode::Unneeded));
+    check_locals(&bin);
+    assert!(bin.find_symbol("_ZL6helperi").is_none());
+    for name in ["_Z5twiceIiET_S0_", "_Z6b_funci", "counter", "_Z6a_funci"] {
+        assert!(bin.find_symbol(name).is_some());
+    }
+    assert!(bin
+        .symbols
+        .iter()
+        .all(|x| x.binding() != SymbolBinding::Local
+            || x.kind() == SymbolType::Section
+            || x.name.is_empty()));
+    for section in [".rela.text", ".rela.eh_frame"] {
+        assert_eq!(reloc_names(&bin, section), reloc_names(&original, section));
+    }
+    assert_eq!(
+        bin.groups().unwrap().len(),
+        original.groups().unwrap().len()
+    );
+    for (i, _) in bin.groups().unwrap() {
+        assert!(bin.group_signature(i).unwrap().starts_with("_Z5twice"));
+    }
+
+    // `--strip-all` still keeps what relocations need.
+    let (_, bin) = strip(input, &StripOptions::new(StripMode::All));
+    check_locals(&bin);
+    assert!(bin.find_symbol("_Z6a_funci").is_none());
+    assert!(bin.find_symbol("counter").is_some());
+    assert_eq!(
+        reloc_names(&bin, ".rela.text"),
+        reloc_names(&original, ".rela.text")
+    );
+
+    // Only the listed symbols and sections are removed.
+    let mut options = StripOptions::new(StripMode::Listed);
+    options.remove_symbols = vec!["_Z6a_funci".into(), "counter".into()];
+    options.remove_sections = vec![".comment".into()];
+    let (_, bin) = strip(input, &options);
+    check_locals(&bin);
+    assert!(bin.find_symbol("_Z6a_funci").is_none());
+    // The relocations still refer to `counter`.
+    assert!(bin.find_symbol("counter").is_some());
+    assert!(bin.find_section(".comment").is_none());
+    assert!(bin.find_section(".debug_info").is_some());
+    assert_eq!(bin.symbols.len(), original.symbols.len() - 2);
+    let strtab = &bin.find_section(".strtab").unwrap().body;
+    assert!(!strtab.windows(10).any(|x| x == b"_Z6a_funci"));
 }
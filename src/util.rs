@@ -1,9 +1,8 @@
-use std::io::{self, Read, Seek, Write};
+use std::io::{Read, Write};
 
+use crate::error::{Error, Result};
 use crate::object::Endianness;
 
-pub(crate) type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
-
 /// Aligns a given number to a given multiple.
 ///
 /// # Arguments
@@ -11,25 +10,39 @@ pub(crate) type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 /// * `align` - The multiple to align by.
 pub fn align_to(pos: &u64, align: &u64) -> u64 {
     if *align == 0 {
-        return pos.clone();
-    }
-    if pos % align == 0 {
-        return pos.clone();
+        return *pos;
     }
-    return pos + (align - pos % align);
+    pos.next_multiple_of(*align)
 }
 
-pub trait SeekExt {
-    fn align(&mut self, align: u64) -> Result<()>;
+/// Reads a null-terminated string starting at `offset` in a string table.
+pub fn read_cstr_at(data: &[u8], offset: u32) -> Result<String> {
+    let mut body = data.get(offset as usize..).ok_or(Error::OutOfRange {
+        offset: offset as u64,
+        size: 1,
+        limit: data.len() as u64,
+    })?;
+    body.read_cstr()
 }
 
-impl<S: Seek> SeekExt for S {
-    fn align(&mut self, align: u64) -> Result<()> {
-        let cur = self.stream_position()?;
-        self.seek(io::SeekFrom::Start(align_to(&cur, &align)))?;
-
-        return Ok(());
+/// Returns the offset of `value` in a string table, appending it if it isn't present yet.
+pub fn insert_cstr(table: &mut Vec<u8>, value: &str) -> u32 {
+    if table.is_empty() {
+        table.push(0);
+    }
+    let mut needle = value.as_bytes().to_vec();
+    needle.push(0);
+    if let Some(pos) = table.windows(needle.len()).position(|x| x == needle) {
+        return pos as u32;
     }
+    let pos = table.len();
+    table.extend_from_slice(&needle);
+    pos as u32
+}
+
+/// Checks if two ranges of the form (offset, size) intersect.
+pub fn overlaps(a: (u64, u64), b: (u64, u64)) -> bool {
+    a.1 != 0 && b.1 != 0 && a.0 < b.0 + b.1 && b.0 < a.0 + a.1
 }
 
 pub trait ReadExt {
@@ -44,7 +57,7 @@ pub trait ReadExt {
 impl<R: Read> ReadExt for R {
     fn read_bytes<const COUNT: usize>(&mut self) -> Result<[u8; COUNT]> {
         let mut x = [0; COUNT];
-        self.read(&mut x)?;
+        self.read_exact(&mut x)?;
         Ok(x)
     }
 
@@ -59,7 +72,7 @@ impl<R: Read> ReadExt for R {
             }
             result.push(c);
         }
-        return Ok(String::from_utf8(result)?);
+        Ok(String::from_utf8(result)?)
     }
 
     fn read_u8(&mut self) -> Result<u8> {
@@ -90,7 +103,6 @@ impl<R: Read> ReadExt for R {
 
 pub trait WriteExt {
     fn write_bytes<const COUNT: usize>(&mut self, value: &[u8; COUNT]) -> Result<usize>;
-    fn write_cstr(&mut self, value: &str) -> Result<usize>;
     fn write_u8(&mut self, value: u8) -> Result<usize>;
     fn write_u16(&mut self, endian: &Endianness, value: u16) -> Result<usize>;
     fn write_u32(&mut self, endian: &Endianness, value: u32) -> Result<usize>;
@@ -99,37 +111,32 @@ pub trait WriteExt {
 
 impl<W: Write> WriteExt for W {
     fn write_bytes<const COUNT: usize>(&mut self, value: &[u8; COUNT]) -> Result<usize> {
-        Ok(self.write(value)?)
-    }
-
-    fn write_cstr(&mut self, value: &str) -> Result<usize> {
-        let written = self.write(value.as_bytes())?;
-        self.write_u8(0)?;
-        Ok(written + 1)
+        self.write_all(value)?;
+        Ok(COUNT)
     }
 
     fn write_u8(&mut self, value: u8) -> Result<usize> {
-        Ok(self.write(&value.to_le_bytes())?)
+        self.write_bytes(&value.to_le_bytes())
     }
 
     fn write_u16(&mut self, endian: &Endianness, value: u16) -> Result<usize> {
         match endian {
-            Endianness::Little => Ok(self.write(&value.to_le_bytes())?),
-            Endianness::Big => Ok(self.write(&value.to_be_bytes())?),
+            Endianness::Little => self.write_bytes(&value.to_le_bytes()),
+            Endianness::Big => self.write_bytes(&value.to_be_bytes()),
         }
     }
 
     fn write_u32(&mut self, endian: &Endianness, value: u32) -> Result<usize> {
         match endian {
-            Endianness::Little => Ok(self.write(&value.to_le_bytes())?),
-            Endianness::Big => Ok(self.write(&value.to_be_bytes())?),
+            Endianness::Little => self.write_bytes(&value.to_le_bytes()),
+            Endianness::Big => self.write_bytes(&value.to_be_bytes()),
         }
     }
 
     fn write_u64(&mut self, endian: &Endianness, value: u64) -> Result<usize> {
         match endian {
-            Endianness::Little => Ok(self.write(&value.to_le_bytes())?),
-            Endianness::Big => Ok(self.write(&value.to_be_bytes())?),
+            Endianness::Little => self.write_bytes(&value.to_le_bytes()),
+            Endianness::Big => self.write_bytes(&value.to_be_bytes()),
         }
     }
 }
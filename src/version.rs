@@ -0,0 +1,328 @@
+use crate::{
+    error::{Error, Result},
+    hash::sysv_hash,
+    object::Object,
+    section::shtype,
+    util::{insert_cstr, read_cstr_at, ReadExt, WriteExt},
+};
+
+/// Version index of local symbols.
+pub const VER_NDX_LOCAL: u16 = 0;
+/// Version index of global symbols without a version.
+pub const VER_NDX_GLOBAL: u16 = 1;
+/// Bit of a `SHT_GNU_VERSYM` entry that marks a version that isn't the default one for the symbol.
+pub const VERSYM_HIDDEN: u16 = 0x8000;
+
+/// Version definition of the object itself.
+pub const VER_FLG_BASE: u16 = 0x1;
+/// Weak version requirement.
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+/// A version defined by an object, read from `SHT_GNU_VERDEF`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionDefinition {
+    /// `VER_FLG_BASE` marks the definition that names the object itself.
+    pub flags: u16,
+    /// The index that `SHT_GNU_VERSYM` entries use for this version.
+    pub index: u16,
+    /// The name of the version, followed by the names of the versions it inherits from.
+    pub names: Vec<String>,
+}
+
+/// The versions that an object requires from one library, read from `SHT_GNU_VERNEED`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionRequirement {
+    /// The name of the library.
+    pub file: String,
+    pub versions: Vec<VersionNeed>,
+}
+
+/// A single version required from a library.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VersionNeed {
+    pub name: String,
+    /// `VER_FLG_WEAK` marks requirements that don't have to be satisfied.
+    pub flags: u16,
+    /// The index that `SHT_GNU_VERSYM` entries use for this version.
+    pub index: u16,
+}
+
+/// The version a dynamic symbol is bound to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolVersion {
+    pub name: String,
+    /// The library that has to provide the version, if it is required rather than defined by the object.
+    pub file: Option<String>,
+    /// Hidden versions can only be bound to explicitly, they aren't the default version of the symbol.
+    pub hidden: bool,
+}
+
+impl SymbolVersion {
+    /// Appends the version to a symbol name like readelf does, e.g. `memcpy@GLIBC_2.14` for required
+    /// versions and `foo@@LIBFOO_1.0` for default versions.
+    pub fn format(&self, symbol: &str) -> String {
+        match self.file.is_none() && !self.hidden {
+            true => format!("{}@@{}", symbol, self.name),
+            false => format!("{}@{}", symbol, self.name),
+        }
+    }
+}
+
+/// Returns the part of `body` that starts at `pos`, or a truncation error.
+fn entry_at(body: &[u8], pos: usize) -> Result<&[u8]> {
+    body.get(pos..)
+        .ok_or(Error::TruncatedHeader { offset: pos as u64 })
+}
+
+impl Object {
+    /// Reads the version index of every dynamic symbol from `SHT_GNU_VERSYM`.
+    /// Returns no indices if the object doesn't use symbol versioning.
+    pub fn symbol_version_indices(&self) -> Result<Vec<u16>> {
+        let Some(idx) = self.version_section(shtype::SHT_GNU_VERSYM) else {
+            return Ok(Vec::new());
+        };
+        let endian = &self.header.e_ident.ei_data;
+        let mut result = Vec::new();
+        for mut chunk in self.sections[idx].body.chunks_exact(2) {
+            result.push(chunk.read_u16(endian)?);
+        }
+        Ok(result)
+    }
+
+    /// Replaces the version indices of the dynamic symbols.
+    pub fn set_symbol_version_indices(&mut self, indices: &[u16]) -> Result<()> {
+        let idx = self
+            .version_section(shtype::SHT_GNU_VERSYM)
+            .ok_or_else(|| Error::MissingSection(".gnu.version".into()))?;
+        let mut data = Vec::new();
+        for &x in indices {
+            data.write_u16(&self.header.e_ident.ei_data, x)?;
+        }
+        self.replace_body(idx, data)
+    }
+
+    /// Reads all versions defined by the object.
+    pub fn version_definitions(&self) -> Result<Vec<VersionDefinition>> {
+        let Some(idx) = self.version_section(shtype::SHT_GNU_VERDEF) else {
+            return Ok(Vec::new());
+        };
+        let endian = &self.header.e_ident.ei_data;
+        let section = &self.sections[idx];
+        let strtab = &self.sections[self.linked_strtab(idx)?].body;
+
+        let mut result = Vec::new();
+        let mut pos = 0;
+        for _ in 0..section.header.sh_info {
+            let read = |mut buf: &[u8]| -> Result<_> {
+                let _version = buf.read_u16(endian)?;
+                let flags = buf.read_u16(endian)?;
+                let index = buf.read_u16(endian)?;
+                let count = buf.read_u16(endian)?;
+                let _hash = buf.read_u32(endian)?;
+                Ok((
+                    flags,
+                    index,
+                    count,
+                    buf.read_u32(endian)?,
+                    buf.read_u32(endian)?,
+                ))
+            };
+            let (flags, index, count, aux, next) =
+                read(entry_at(&section.body, pos)?).map_err(|e| e.truncated(pos as u64))?;
+
+            let mut names = Vec::new();
+            let mut aux_pos = pos + aux as usize;
+            for _ in 0..count {
+                let read = |mut buf: &[u8]| -> Result<_> {
+                    Ok((buf.read_u32(endian)?, buf.read_u32(endian)?))
+                };
+                let (name, next) = read(entry_at(&section.body, aux_pos)?)
+                    .map_err(|e| e.truncated(aux_pos as u64))?;
+                names.push(read_cstr_at(strtab, name)?);
+                aux_pos += next as usize;
+            }
+            result.push(VersionDefinition {
+                flags,
+                index,
+                names,
+            });
+            if next == 0 {
+                break;
+            }
+            pos += next as usize;
+        }
+        Ok(result)
+    }
+
+    /// Reads the versions the object requires, grouped by library.
+    pub fn version_requirements(&self) -> Result<Vec<VersionRequirement>> {
+        let Some(idx) = self.version_section(shtype::SHT_GNU_VERNEED) else {
+            return Ok(Vec::new());
+        };
+        let endian = &self.header.e_ident.ei_data;
+        let section = &self.sections[idx];
+        let strtab = &self.sections[self.linked_strtab(idx)?].body;
+
+        let mut result = Vec::new();
+        let mut pos = 0;
+        for _ in 0..section.header.sh_info {
+            let read = |mut buf: &[u8]| -> Result<_> {
+                let _version = buf.read_u16(endian)?;
+                let count = buf.read_u16(endian)?;
+                let file = buf.read_u32(endian)?;
+                Ok((count, file, buf.read_u32(endian)?, buf.read_u32(endian)?))
+            };
+            let (count, file, aux, next) =
+                read(entry_at(&section.body, pos)?).map_err(|e| e.truncated(pos as u64))?;
+
+            let mut versions = Vec::new();
+            let mut aux_pos = pos + aux as usize;
+            for _ in 0..count {
+                let read = |mut buf: &[u8]| -> Result<_> {
+                    let _hash = buf.read_u32(endian)?;
+                    let flags = buf.read_u16(endian)?;
+                    let index = buf.read_u16(endian)?;
+                    Ok((flags, index, buf.read_u32(endian)?, buf.read_u32(endian)?))
+                };
+                let (flags, index, name, next) = read(entry_at(&section.body, aux_pos)?)
+                    .map_err(|e| e.truncated(aux_pos as u64))?;
+                versions.push(VersionNeed {
+                    name: read_cstr_at(strtab, name)?,
+                    flags,
+                    index,
+                });
+                aux_pos += next as usize;
+            }
+            result.push(VersionRequirement {
+                file: read_cstr_at(strtab, file)?,
+                versions,
+            });
+            if next == 0 {
+                break;
+            }
+            pos += next as usize;
+        }
+        Ok(result)
+    }
+
+    /// Replaces the versions defined by the object. Names are added to the dynamic string table.
+    pub fn set_version_definitions(&mut self, definitions: &[VersionDefinition]) -> Result<()> {
+        let idx = self
+            .version_section(shtype::SHT_GNU_VERDEF)
+            .ok_or_else(|| Error::MissingSection(".gnu.version_d".into()))?;
+        let strtab = self.linked_strtab(idx)?;
+        let endian = self.header.e_ident.ei_data.clone();
+        let mut dynstr = self.sections[strtab].body.clone();
+
+        let mut data = Vec::new();
+        for (i, def) in definitions.iter().enumerate() {
+            let size = 20 + 8 * def.names.len();
+            let last = i + 1 == definitions.len();
+            let hash = def.names.first().map_or(0, |x| sysv_hash(x));
+            data.write_u16(&endian, 1)?;
+            data.write_u16(&endian, def.flags)?;
+            data.write_u16(&endian, def.index)?;
+            data.write_u16(&endian, def.names.len() as u16)?;
+            data.write_u32(&endian, hash)?;
+            data.write_u32(&endian, if def.names.is_empty() { 0 } else { 20 })?;
+            data.write_u32(&endian, if last { 0 } else { size as u32 })?;
+            for (k, name) in def.names.iter().enumerate() {
+                let last = k + 1 == def.names.len();
+                data.write_u32(&endian, insert_cstr(&mut dynstr, name))?;
+                data.write_u32(&endian, if last { 0 } else { 8 })?;
+            }
+        }
+        self.replace_body(strtab, dynstr)?;
+        self.replace_body(idx, data)?;
+        self.sections[idx].header.sh_info = definitions.len() as u32;
+        Ok(())
+    }
+
+    /// Replaces the versions the object requires. Names are added to the dynamic string table.
+    pub fn set_version_requirements(&mut self, requirements: &[VersionRequirement]) -> Result<()> {
+        let idx = self
+            .version_section(shtype::SHT_GNU_VERNEED)
+            .ok_or_else(|| Error::MissingSection(".gnu.version_r".into()))?;
+        let strtab = self.linked_strtab(idx)?;
+        let endian = self.header.e_ident.ei_data.clone();
+        let mut dynstr = self.sections[strtab].body.clone();
+
+        let mut data = Vec::new();
+        for (i, req) in requirements.iter().enumerate() {
+            let size = 16 + 16 * req.versions.len();
+            let last = i + 1 == requirements.len();
+            data.write_u16(&endian, 1)?;
+            data.write_u16(&endian, req.versions.len() as u16)?;
+            data.write_u32(&endian, insert_cstr(&mut dynstr, &req.file))?;
+            data.write_u32(&endian, if req.versions.is_empty() { 0 } else { 16 })?;
+            data.write_u32(&endian, if last { 0 } else { size as u32 })?;
+            for (k, version) in req.versions.iter().enumerate() {
+                let last = k + 1 == req.versions.len();
+                data.write_u32(&endian, sysv_hash(&version.name))?;
+                data.write_u16(&endian, version.flags)?;
+                data.write_u16(&endian, version.index)?;
+                data.write_u32(&endian, insert_cstr(&mut dynstr, &version.name))?;
+                data.write_u32(&endian, if last { 0 } else { 16 })?;
+            }
+        }
+        self.replace_body(strtab, dynstr)?;
+        self.replace_body(idx, data)?;
+        self.sections[idx].header.sh_info = requirements.len() as u32;
+        Ok(())
+    }
+
+    /// Looks up the version of every dynamic symbol. Symbols that are local or unversioned have no version.
+    pub fn symbol_versions(&self) -> Result<Vec<Option<SymbolVersion>>> {
+        let definitions = self.version_definitions()?;
+        let requirements = self.version_requirements()?;
+        let lookup = |index: u16| {
+            let index = index & !VERSYM_HIDDEN;
+            if index == VER_NDX_LOCAL || index == VER_NDX_GLOBAL {
+                return None;
+            }
+            let defined = definitions
+                .iter()
+                .find(|x| x.index == index)
+                .and_then(|x| x.names.first())
+                .map(|x| (x.clone(), None));
+            defined.or_else(|| {
+                requirements.iter().find_map(|req| {
+                    req.versions
+                        .iter()
+                        .find(|x| x.index == index)
+                        .map(|x| (x.name.clone(), Some(req.file.clone())))
+                })
+            })
+        };
+        Ok(self
+            .symbol_version_indices()?
+            .into_iter()
+            .map(|x| {
+                lookup(x).map(|(name, file)| SymbolVersion {
+                    name,
+                    file,
+                    hidden: x & VERSYM_HIDDEN != 0,
+                })
+            })
+            .collect())
+    }
+
+    /// Returns the names of all dynamic symbols together with their version, e.g. `memcpy@GLIBC_2.14`.
+    pub fn versioned_symbol_names(&self) -> Result<Vec<String>> {
+        let versions = self.symbol_versions()?;
+        Ok(self
+            .dynamic_symbols()?
+            .iter()
+            .enumerate()
+            .map(|(i, sym)| match versions.get(i) {
+                Some(Some(version)) => version.format(&sym.name),
+                _ => sym.name.clone(),
+            })
+            .collect())
+    }
+
+    /// Returns the index of the version section of the given type that belongs to the dynamic symbols.
+    fn version_section(&self, sh_type: u32) -> Option<usize> {
+        (0..self.sections.len()).find(|&i| self.sections[i].header.sh_type == sh_type)
+    }
+}